@@ -0,0 +1,536 @@
+//! Bitflags whose width is only known at runtime, e.g. in inspectors and scripting layers.
+
+use crate::{
+    BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8, BitFlagsError, BitFlagsIter128,
+    FlagNames, FlagsDisplay,
+};
+
+/// Bitflags of any of the five widths, chosen at runtime.
+///
+/// Set operations between different widths promote to the wider of the two, e.g. `Eight |
+/// SixtyFour` is `SixtyFour`. Flags of different widths are never equal: compare `bits()` to
+/// ignore width.
+///
+/// With `serde-support`, flags are (de)serialized as an externally tagged enum, e.g.
+/// `{"Eight":5}` in JSON, so mixed widths round-trip. With `nanoserde-support`, BIN is prefixed
+/// by the width in bits (as one byte), and JSON and RON are tagged with it, e.g.
+/// `{"width":8,"bits":5}`. At 128 bits, JSON and RON write `bits` as a decimal string, as
+/// nanoserde only reads numbers up to `u64::MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnyBitFlags {
+    Eight(BitFlags8),
+    Sixteen(BitFlags16),
+    ThirtyTwo(BitFlags32),
+    SixtyFour(BitFlags64),
+    OneTwentyEight(BitFlags128),
+}
+
+/// Evaluates `$e` with `$f` bound to the inner flags, or with `map`, rewraps the result in the
+/// same variant.
+macro_rules! dispatch {
+    ($self:expr, $f:ident => $e:expr) => {
+        match $self {
+            AnyBitFlags::Eight($f) => $e,
+            AnyBitFlags::Sixteen($f) => $e,
+            AnyBitFlags::ThirtyTwo($f) => $e,
+            AnyBitFlags::SixtyFour($f) => $e,
+            AnyBitFlags::OneTwentyEight($f) => $e,
+        }
+    };
+    (map $self:expr, $f:ident => $e:expr) => {
+        match $self {
+            AnyBitFlags::Eight($f) => AnyBitFlags::Eight($e),
+            AnyBitFlags::Sixteen($f) => AnyBitFlags::Sixteen($e),
+            AnyBitFlags::ThirtyTwo($f) => AnyBitFlags::ThirtyTwo($e),
+            AnyBitFlags::SixtyFour($f) => AnyBitFlags::SixtyFour($e),
+            AnyBitFlags::OneTwentyEight($f) => AnyBitFlags::OneTwentyEight($e),
+        }
+    };
+}
+
+impl AnyBitFlags {
+    /// The supported widths, in bits.
+    pub const WIDTHS: [u32; 5] = [8, 16, 32, 64, 128];
+
+    /// Returns empty flags `width` bits wide.
+    ///
+    /// __Panics__ if `width` is not one of `WIDTHS`.
+    #[inline]
+    pub fn empty(width: u32) -> Self {
+        Self::from_bits(width, 0)
+    }
+    /// Returns flags `width` bits wide with every bit set.
+    ///
+    /// __Panics__ if `width` is not one of `WIDTHS`.
+    #[inline]
+    pub fn full(width: u32) -> Self {
+        Self::empty(width).complement()
+    }
+    /// Returns flags `width` bits wide with `bits` set.
+    ///
+    /// __Panics__ if `width` is not one of `WIDTHS`, or `bits` does not fit in `width` bits.
+    #[inline]
+    pub fn from_bits(width: u32, bits: u128) -> Self {
+        Self::try_from_bits(width, bits).unwrap()
+    }
+    /// Returns flags `width` bits wide with `bits` set. Returns `Err` if `width` is not one of
+    /// `WIDTHS`, or `bits` does not fit in `width` bits.
+    pub fn try_from_bits(width: u32, bits: u128) -> Result<Self, BitFlagsError> {
+        if !Self::WIDTHS.contains(&width) {
+            return Err(BitFlagsError::InvalidWidth { width });
+        }
+        let unknown = bits & !mask(width);
+        if unknown != 0 {
+            return Err(BitFlagsError::UnknownBits { bits: unknown });
+        }
+        Ok(Self::with_width(width, bits))
+    }
+    /// Returns the width in bits: `8`, `16`, `32`, `64`, or `128`.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        match self {
+            AnyBitFlags::Eight(_) => 8,
+            AnyBitFlags::Sixteen(_) => 16,
+            AnyBitFlags::ThirtyTwo(_) => 32,
+            AnyBitFlags::SixtyFour(_) => 64,
+            AnyBitFlags::OneTwentyEight(_) => 128,
+        }
+    }
+    /// Returns the narrowest width that holds every set bit.
+    #[inline]
+    pub fn min_width(&self) -> u32 {
+        let used = 128 - self.bits().leading_zeros();
+        Self::WIDTHS.into_iter().find(|&width| width >= used).unwrap()
+    }
+    /// Returns the set bits, zero-extended.
+    #[inline]
+    pub fn bits(&self) -> u128 {
+        match *self {
+            AnyBitFlags::Eight(f) => f.0 as u128,
+            AnyBitFlags::Sixteen(f) => f.0 as u128,
+            AnyBitFlags::ThirtyTwo(f) => f.0 as u128,
+            AnyBitFlags::SixtyFour(f) => f.0 as u128,
+            AnyBitFlags::OneTwentyEight(f) => f.0,
+        }
+    }
+    /// Returns the flags widened to `width` bits, or unchanged if already at least as wide.
+    ///
+    /// __Panics__ if `width` is not one of `WIDTHS`.
+    #[inline]
+    pub fn widen(self, width: u32) -> Self {
+        Self::from_bits(width.max(self.width()), self.bits())
+    }
+    /// Returns the flags converted to `width` bits.
+    ///
+    /// __Panics__ if `width` is not one of `WIDTHS`, or any set bit does not fit in `width` bits.
+    #[inline]
+    pub fn resize(self, width: u32) -> Self {
+        Self::from_bits(width, self.bits())
+    }
+    /// Returns the flags converted to `width` bits. Returns `Err` if `width` is not one of
+    /// `WIDTHS`, or any set bit does not fit in `width` bits.
+    #[inline]
+    pub fn try_resize(self, width: u32) -> Result<Self, BitFlagsError> {
+        Self::try_from_bits(width, self.bits())
+    }
+    /// Returns the flags converted to the narrowest width that holds every set bit.
+    #[inline]
+    pub fn shrink_to_fit(self) -> Self {
+        Self::with_width(self.min_width(), self.bits())
+    }
+    /// Returns `true` if no bits are set.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bits() == 0
+    }
+    /// Returns `true` if every bit (of its width) is set.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        dispatch!(self, f => f.is_full())
+    }
+    /// Returns the number of set bits.
+    #[inline]
+    pub fn count_ones(&self) -> u32 {
+        self.bits().count_ones()
+    }
+    /// Returns `true` if `self` and `other` have any set bits in common.
+    #[inline]
+    pub fn intersects(&self, other: Self) -> bool {
+        self.bits() & other.bits() != 0
+    }
+    /// Returns `true` if every bit set in `other` is set in `self`, whatever their widths.
+    #[inline]
+    pub fn contains(&self, other: Self) -> bool {
+        self.bits() & other.bits() == other.bits()
+    }
+    /// Returns the bits set in `self` or `other`, at the wider of their widths.
+    #[inline]
+    pub fn union(&self, other: Self) -> Self {
+        self.promoted(other, self.bits() | other.bits())
+    }
+    /// Returns the bits set in both `self` and `other`, at the wider of their widths.
+    #[inline]
+    pub fn intersection(&self, other: Self) -> Self {
+        self.promoted(other, self.bits() & other.bits())
+    }
+    /// Returns the bits set in `self` but not in `other`, at the wider of their widths.
+    #[inline]
+    pub fn difference(&self, other: Self) -> Self {
+        self.promoted(other, self.bits() & !other.bits())
+    }
+    /// Returns the bits set in exactly one of `self` and `other`, at the wider of their widths.
+    #[inline]
+    pub fn symmetric_difference(&self, other: Self) -> Self {
+        self.promoted(other, self.bits() ^ other.bits())
+    }
+    /// Returns the bits (of its width) not set in `self`.
+    #[inline]
+    pub fn complement(&self) -> Self {
+        dispatch!(map self, f => f.complement())
+    }
+    /// Returns `true` if the bit at `index` is set.
+    ///
+    /// __Panics__ if `index` is not less than the width.
+    #[inline]
+    pub fn bit_at_index(&self, index: usize) -> bool {
+        dispatch!(self, f => f.bit_at_index(index))
+    }
+    /// Returns `Some(true)` if the bit at `index` is set, or `None` if `index` is not less than
+    /// the width.
+    #[inline]
+    pub fn get_bit_at_index(&self, index: usize) -> Option<bool> {
+        dispatch!(self, f => f.get_bit_at_index(index))
+    }
+    /// Sets the bit at `index`.
+    ///
+    /// __Panics__ if `index` is not less than the width.
+    #[inline]
+    pub fn insert_at_index(&mut self, index: usize) {
+        dispatch!(self, f => f.insert_at_index(index))
+    }
+    /// Sets the bit at `index`. Returns `Err` if `index` is not less than the width.
+    #[inline]
+    pub fn try_insert_at_index(&mut self, index: usize) -> Result<(), BitFlagsError> {
+        dispatch!(self, f => f.try_insert_at_index(index))
+    }
+    /// Clears the bit at `index`.
+    ///
+    /// __Panics__ if `index` is not less than the width.
+    #[inline]
+    pub fn remove_at_index(&mut self, index: usize) {
+        dispatch!(self, f => f.remove_at_index(index))
+    }
+    /// Clears the bit at `index`. Returns `Err` if `index` is not less than the width.
+    #[inline]
+    pub fn try_remove_at_index(&mut self, index: usize) -> Result<(), BitFlagsError> {
+        dispatch!(self, f => f.try_remove_at_index(index))
+    }
+    /// Iterates over the indexes of set bits, in increasing order.
+    #[inline]
+    pub fn iter(&self) -> BitFlagsIter128 {
+        BitFlags128(self.bits()).iter()
+    }
+    /// Returns an adapter that writes the flags by name (see [`FlagsDisplay`]).
+    #[inline]
+    pub fn display_with<'a, N: FlagNames + ?Sized>(&self, names: &'a N) -> FlagsDisplay<'a, N> {
+        FlagsDisplay::new(self.bits(), names)
+    }
+    /// Returns `bits` (which must fit) at the wider of the widths of `self` and `other`.
+    #[inline]
+    fn promoted(&self, other: Self, bits: u128) -> Self {
+        Self::with_width(self.width().max(other.width()), bits)
+    }
+    /// Returns `bits` (truncated) at `width` bits, which must be one of `WIDTHS`.
+    #[inline]
+    fn with_width(width: u32, bits: u128) -> Self {
+        match width {
+            8 => AnyBitFlags::Eight(BitFlags8(bits as u8)),
+            16 => AnyBitFlags::Sixteen(BitFlags16(bits as u16)),
+            32 => AnyBitFlags::ThirtyTwo(BitFlags32(bits as u32)),
+            64 => AnyBitFlags::SixtyFour(BitFlags64(bits as u64)),
+            _ => AnyBitFlags::OneTwentyEight(BitFlags128(bits)),
+        }
+    }
+}
+
+/// Returns the bits below `width`.
+#[inline]
+fn mask(width: u32) -> u128 {
+    u128::MAX >> (128 - width)
+}
+
+/// Returns empty `BitFlags8`, the identity for `|` at any width.
+impl Default for AnyBitFlags {
+    fn default() -> Self {
+        AnyBitFlags::Eight(BitFlags8(0))
+    }
+}
+
+impl core::fmt::Display for AnyBitFlags {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        dispatch!(self, flags => core::fmt::Display::fmt(flags, f))
+    }
+}
+
+impl core::fmt::Binary for AnyBitFlags {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        dispatch!(self, flags => core::fmt::Binary::fmt(flags, f))
+    }
+}
+
+impl core::str::FromStr for AnyBitFlags {
+    type Err = BitFlagsError;
+
+    /// Parses the `Display` format, e.g. `BitFlags16(0x10)`, with the width taken from the type
+    /// name. A bare integer (decimal, `0x`, or `0b`) takes the narrowest width that holds it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.starts_with("BitFlags8(") {
+            s.parse().map(AnyBitFlags::Eight)
+        } else if s.starts_with("BitFlags16(") {
+            s.parse().map(AnyBitFlags::Sixteen)
+        } else if s.starts_with("BitFlags32(") {
+            s.parse().map(AnyBitFlags::ThirtyTwo)
+        } else if s.starts_with("BitFlags64(") {
+            s.parse().map(AnyBitFlags::SixtyFour)
+        } else if s.starts_with("BitFlags128(") {
+            s.parse().map(AnyBitFlags::OneTwentyEight)
+        } else {
+            s.parse()
+                .map(|flags| AnyBitFlags::OneTwentyEight(flags).shrink_to_fit())
+        }
+    }
+}
+
+impl core::ops::BitOr<AnyBitFlags> for AnyBitFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl core::ops::BitOrAssign<AnyBitFlags> for AnyBitFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = self.union(rhs)
+    }
+}
+
+impl core::ops::BitAnd<AnyBitFlags> for AnyBitFlags {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl core::ops::BitAndAssign<AnyBitFlags> for AnyBitFlags {
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = self.intersection(rhs)
+    }
+}
+
+impl core::ops::BitXor<AnyBitFlags> for AnyBitFlags {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        self.symmetric_difference(rhs)
+    }
+}
+
+impl core::ops::BitXorAssign<AnyBitFlags> for AnyBitFlags {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        *self = self.symmetric_difference(rhs)
+    }
+}
+
+impl core::ops::Not for AnyBitFlags {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        self.complement()
+    }
+}
+
+macro_rules! impl_any {
+    ($flags:ident, $int:ty, $bits:literal, $variant:ident) => {
+        impl From<$flags> for AnyBitFlags {
+            #[inline]
+            fn from(flags: $flags) -> Self {
+                AnyBitFlags::$variant(flags)
+            }
+        }
+
+        /// Converts flags of any width whose set bits fit, returning `Err` with the bits that
+        /// do not.
+        impl TryFrom<AnyBitFlags> for $flags {
+            type Error = BitFlagsError;
+
+            fn try_from(flags: AnyBitFlags) -> Result<Self, Self::Error> {
+                let unknown = flags.bits() & !mask($bits);
+                if unknown != 0 {
+                    return Err(BitFlagsError::UnknownBits { bits: unknown });
+                }
+                Ok($flags(flags.bits() as $int))
+            }
+        }
+    };
+}
+
+impl_any!(BitFlags8, u8, 8, Eight);
+impl_any!(BitFlags16, u16, 16, Sixteen);
+impl_any!(BitFlags32, u32, 32, ThirtyTwo);
+impl_any!(BitFlags64, u64, 64, SixtyFour);
+impl_any!(BitFlags128, u128, 128, OneTwentyEight);
+
+//  #######   ########   ######   ########  #######
+//  ##    ##  ##        ##        ##        ##    ##
+//  ##    ##  ######     ######   ######    #######
+//  ##    ##  ##              ##  ##        ##   ##
+//  #######   ########  #######   ########  ##    ##
+
+#[cfg(feature = "nanoserde-support")]
+mod impl_nanoserde {
+    extern crate std;
+    use super::AnyBitFlags;
+    use crate::{BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8};
+    use nanoserde::{
+        DeBin, DeBinErr, DeJson, DeJsonErr, DeJsonState, DeJsonTok, DeRon, DeRonErr, DeRonState,
+        DeRonTok, SerBin, SerJson, SerJsonState, SerRon, SerRonState,
+    };
+    use std::prelude::v1::*;
+
+    impl SerBin for AnyBitFlags {
+        fn ser_bin(&self, output: &mut Vec<u8>) {
+            output.push(self.width() as u8);
+            dispatch!(self, flags => flags.ser_bin(output))
+        }
+    }
+
+    /// An unknown width is rejected as if the input were too short, as `DeBinErr` carries no
+    /// message.
+    impl DeBin for AnyBitFlags {
+        fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr> {
+            let start = *offset;
+            let width = u8::de_bin(offset, bytes)?;
+
+            match width {
+                8 => BitFlags8::de_bin(offset, bytes).map(AnyBitFlags::Eight),
+                16 => BitFlags16::de_bin(offset, bytes).map(AnyBitFlags::Sixteen),
+                32 => BitFlags32::de_bin(offset, bytes).map(AnyBitFlags::ThirtyTwo),
+                64 => BitFlags64::de_bin(offset, bytes).map(AnyBitFlags::SixtyFour),
+                128 => BitFlags128::de_bin(offset, bytes).map(AnyBitFlags::OneTwentyEight),
+                _ => {
+                    *offset = start;
+                    Err(DeBinErr { o: start, l: 1, s: bytes.len() })
+                }
+            }
+        }
+    }
+
+    /// Returns the bits as a JSON/RON value: a number up to 64 bits wide, and a decimal string at
+    /// 128 bits, as nanoserde only reads numbers up to `u64::MAX`.
+    fn bits_value(flags: &AnyBitFlags) -> String {
+        match flags {
+            AnyBitFlags::OneTwentyEight(flags) => format!("\"{}\"", flags.0),
+            _ => flags.bits().to_string(),
+        }
+    }
+
+    impl SerJson for AnyBitFlags {
+        fn ser_json(&self, d: usize, state: &mut SerJsonState) {
+            state.st_pre();
+            state.field(d + 1, "width");
+            self.width().ser_json(d + 1, state);
+            state.conl();
+            state.field(d + 1, "bits");
+            state.out.push_str(&bits_value(self));
+            state.st_post(d);
+        }
+    }
+
+    /// Fields may come in either order. `bits` may be a number or a decimal string at any width.
+    impl DeJson for AnyBitFlags {
+        fn de_json(
+            state: &mut DeJsonState,
+            input: &mut core::str::Chars,
+        ) -> Result<Self, DeJsonErr> {
+            let (mut width, mut bits) = (None, None);
+            state.curly_open(input)?;
+            while state.next_str().is_some() {
+                match state.strbuf.as_str() {
+                    "width" => {
+                        state.next_colon(input)?;
+                        width = Some(u32::de_json(state, input)?);
+                    }
+                    "bits" => {
+                        state.next_colon(input)?;
+                        bits = Some(match state.tok {
+                            DeJsonTok::U64(bits) => u128::from(bits),
+                            DeJsonTok::Str => {
+                                state.strbuf.parse().map_err(|_| state.err_parse("u128"))?
+                            }
+                            _ => return Err(state.err_type("u128")),
+                        });
+                        state.next_tok(input)?;
+                    }
+                    _ => return Err(state.err_exp(&state.strbuf)),
+                }
+                state.eat_comma_curly(input)?;
+            }
+            state.curly_close(input)?;
+
+            let width = width.ok_or_else(|| state.err_nf("width"))?;
+            let bits = bits.ok_or_else(|| state.err_nf("bits"))?;
+            AnyBitFlags::try_from_bits(width, bits).map_err(|err| state.err_range(&err.to_string()))
+        }
+    }
+
+    impl SerRon for AnyBitFlags {
+        fn ser_ron(&self, d: usize, state: &mut SerRonState) {
+            state.st_pre();
+            state.field(d + 1, "width");
+            self.width().ser_ron(d + 1, state);
+            state.conl();
+            state.field(d + 1, "bits");
+            state.out.push_str(&bits_value(self));
+            state.conl();
+            state.st_post(d);
+        }
+    }
+
+    /// Fields may come in either order. `bits` may be a number or a decimal string at any width.
+    impl DeRon for AnyBitFlags {
+        fn de_ron(state: &mut DeRonState, input: &mut core::str::Chars) -> Result<Self, DeRonErr> {
+            let (mut width, mut bits) = (None, None);
+            state.paren_open(input)?;
+            while state.next_ident().is_some() {
+                match state.identbuf.as_str() {
+                    "width" => {
+                        state.next_colon(input)?;
+                        width = Some(u32::de_ron(state, input)?);
+                    }
+                    "bits" => {
+                        state.next_colon(input)?;
+                        bits = Some(match state.tok {
+                            DeRonTok::U64(bits) => u128::from(bits),
+                            DeRonTok::Str => {
+                                state.strbuf.parse().map_err(|_| state.err_parse("u128"))?
+                            }
+                            _ => return Err(state.err_type("u128")),
+                        });
+                        state.next_tok(input)?;
+                    }
+                    _ => return Err(state.err_exp(&state.identbuf)),
+                }
+                state.eat_comma_paren(input)?;
+            }
+            state.paren_close(input)?;
+
+            let width = width.ok_or_else(|| state.err_nf("width"))?;
+            let bits = bits.ok_or_else(|| state.err_nf("bits"))?;
+            AnyBitFlags::try_from_bits(width, bits).map_err(|err| state.err_range(&err.to_string()))
+        }
+    }
+}
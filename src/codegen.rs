@@ -0,0 +1,467 @@
+//! Build-time generation of typed flag constants, for use from `build.rs`.
+//!
+//! The input is either a [`SchemaDefinition`] in RON (`.ron`) or JSON (`.json`), or a plain list
+//! with one flag name per line (optionally `name = index`, with `#` starting a comment). The
+//! output is a module body with a constant per flag, alias, and group, a name table, an enum of
+//! flag indexes, and `Display`/`FromStr` impls for the enum. Deprecated flags are marked
+//! `#[deprecated]` as both constants and enum variants:
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("status.rs");
+//!     arctan_bitflags::Codegen::new("flags/status.ron").type_name("Status").write_to(out).unwrap();
+//! }
+//!
+//! // src/status.rs
+//! include!(concat!(env!("OUT_DIR"), "/status.rs"));
+//! ```
+
+extern crate std;
+
+use crate::{
+    FlagDefinition, FlagRegistry, RegisteredFlag, RegisteredGroup, SchemaDefinition, SchemaError,
+};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::prelude::v1::*;
+use std::{format, fs, io, println};
+
+/// An error produced while generating code.
+#[derive(Debug)]
+pub enum CodegenError {
+    /// The input could not be read, or the output could not be written.
+    Io(io::Error),
+    /// The input could not be parsed.
+    Parse(String),
+    /// The input did not describe a valid registry.
+    Schema(Vec<SchemaError>),
+    /// A name (or the type name) does not make a valid Rust identifier, e.g. it is a keyword.
+    InvalidIdent { name: String },
+    /// Two names map to the same Rust identifier.
+    IdentCollision {
+        ident:  String,
+        first:  String,
+        second: String,
+    },
+    /// The input has no flags.
+    NoFlags,
+}
+
+impl From<io::Error> for CodegenError {
+    fn from(err: io::Error) -> Self {
+        CodegenError::Io(err)
+    }
+}
+
+impl core::fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CodegenError::Io(err) => write!(f, "{}", err),
+            CodegenError::Parse(message) => write!(f, "failed to parse flags: {}", message),
+            CodegenError::Schema(errors) => {
+                write!(f, "invalid flags:")?;
+                errors.iter().try_for_each(|err| write!(f, "\n  {}", err))
+            }
+            CodegenError::InvalidIdent { name } => {
+                write!(f, "{:?} does not make a valid Rust identifier", name)
+            }
+            CodegenError::IdentCollision { ident, first, second } => {
+                write!(f, "{:?} and {:?} both generate `{}`", first, second, ident)
+            }
+            CodegenError::NoFlags => write!(f, "no flags are defined"),
+        }
+    }
+}
+
+impl std::error::Error for CodegenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CodegenError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Generates a module of typed flag constants from a flag file.
+#[derive(Debug, Clone)]
+pub struct Codegen {
+    input:     PathBuf,
+    type_name: String,
+    width:     u32,
+}
+
+impl Codegen {
+    /// Returns a generator reading flags from `input`. The enum of flag indexes defaults to
+    /// `Flag`, and name lists default to `BitFlags64`.
+    pub fn new(input: impl AsRef<Path>) -> Codegen {
+        Codegen {
+            input:     input.as_ref().to_path_buf(),
+            type_name: String::from("Flag"),
+            width:     64,
+        }
+    }
+    /// Sets the name of the generated enum of flag indexes (e.g. `Status`), which also prefixes
+    /// the name table (e.g. `STATUS_NAMES`).
+    pub fn type_name(mut self, type_name: &str) -> Codegen {
+        self.type_name = String::from(type_name);
+        self
+    }
+    /// Sets the `BitFlagsN` width used for name lists. Schema files set their own width.
+    pub fn width(mut self, width: u32) -> Codegen {
+        self.width = width;
+        self
+    }
+    /// Reads the input and returns the generated source.
+    pub fn generate(&self) -> Result<String, CodegenError> {
+        let text = fs::read_to_string(&self.input)?;
+        let extension = self.input.extension().and_then(|ext| ext.to_str());
+        let registry = match extension {
+            Some("ron") => {
+                let definition =
+                    ron::from_str(&text).map_err(|err| CodegenError::Parse(err.to_string()))?;
+                FlagRegistry::new(&definition).map_err(CodegenError::Schema)?
+            }
+            Some("json") => {
+                let definition = serde_json::from_str(&text)
+                    .map_err(|err| CodegenError::Parse(err.to_string()))?;
+                FlagRegistry::new(&definition).map_err(CodegenError::Schema)?
+            }
+            _ => registry_from_list(&text, self.width)?,
+        };
+
+        let source = self.input.file_name().map(|name| name.to_string_lossy().into_owned());
+        generate_module(&registry, &self.type_name, source.as_deref())
+    }
+    /// Generates the source and writes it to `output`, unless it already holds the same source.
+    /// Returns `true` if the file was written.
+    ///
+    /// Also tells Cargo (on standard output) to rerun the build script only if the input changes.
+    pub fn write_to(&self, output: impl AsRef<Path>) -> Result<bool, CodegenError> {
+        println!("cargo:rerun-if-changed={}", self.input.display());
+        let generated = self.generate()?;
+        if fs::read_to_string(output.as_ref()).is_ok_and(|existing| existing == generated) {
+            return Ok(false);
+        }
+
+        fs::write(output, generated)?;
+        Ok(true)
+    }
+}
+
+/// Builds a registry from a list of flag names, one per line, reporting problems by line.
+fn registry_from_list(text: &str, width: u32) -> Result<FlagRegistry, CodegenError> {
+    let mut lines = Vec::new();
+    let mut flags = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (name, index) = match line.split_once('=') {
+            Some((name, index)) => {
+                let index = index.trim().parse().map_err(|err| {
+                    CodegenError::Parse(format!(
+                        "line {}: invalid index {:?}: {}",
+                        n + 1,
+                        index.trim(),
+                        err
+                    ))
+                })?;
+                (name.trim(), Some(index))
+            }
+            None => (line, None),
+        };
+        lines.push(n + 1);
+        flags.push(FlagDefinition {
+            name: String::from(name),
+            index,
+            ..Default::default()
+        });
+    }
+
+    let definition = SchemaDefinition { width, flags, ..Default::default() };
+    FlagRegistry::new(&definition).map_err(|errors| {
+        // Points `flags[i]...` at the line that flag `i` came from.
+        let errors = errors.into_iter().map(|mut err| {
+            let index = err
+                .path
+                .strip_prefix("flags[")
+                .and_then(|rest| rest.split(']').next());
+            if let Some(line) = index.and_then(|i| i.parse::<usize>().ok()).map(|i| lines[i]) {
+                err.path = format!("line {}", line);
+            }
+            err
+        });
+        CodegenError::Schema(errors.collect())
+    })
+}
+
+/// Returns `name` in `SCREAMING_SNAKE_CASE`.
+fn constant_ident(name: &str) -> String {
+    let mut ident = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && prev_lower {
+            ident.push('_');
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        ident.push(c.to_ascii_uppercase());
+    }
+    ident
+}
+
+/// Returns `name` in `UpperCamelCase`.
+fn variant_ident(name: &str) -> String {
+    let mut ident = String::new();
+    for word in constant_ident(name).split('_').filter(|word| !word.is_empty()) {
+        let mut chars = word.chars();
+        ident.extend(chars.next());
+        ident.extend(chars.map(|c| c.to_ascii_lowercase()));
+    }
+    ident
+}
+
+/// Rust's strict and reserved keywords (in any edition), which can't be used as identifiers.
+const KEYWORDS: &[&str] = &[
+    "Self", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Returns `true` if `ident` is usable as a generated identifier.
+fn is_ident(ident: &str) -> bool {
+    let mut chars = ident.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && ident != "_"
+        && !KEYWORDS.contains(&ident)
+}
+
+/// Records that `name` generates `ident`, failing if another name already did.
+fn claim<'a>(
+    idents: &mut BTreeMap<String, &'a str>,
+    ident: String,
+    name: &'a str,
+) -> Result<String, CodegenError> {
+    if !is_ident(&ident) {
+        return Err(CodegenError::InvalidIdent { name: String::from(name) });
+    }
+    if let Some(first) = idents.get(&ident) {
+        let (first, second) = (String::from(*first), String::from(name));
+        return Err(CodegenError::IdentCollision { ident, first, second });
+    }
+
+    idents.insert(ident.clone(), name);
+    Ok(ident)
+}
+
+/// A flag with the identifiers generated for it and its aliases.
+struct FlagIdents<'a> {
+    flag:     &'a RegisteredFlag,
+    constant: String,
+    variant:  String,
+    aliases:  Vec<(&'a str, String)>,
+}
+
+/// Returns the source of a module of typed constants for `registry`, with an enum of flag
+/// indexes named `type_name`. `source` names the input file in the generated header.
+pub fn generate_module(
+    registry: &FlagRegistry,
+    type_name: &str,
+    source: Option<&str>,
+) -> Result<String, CodegenError> {
+    if registry.flags().is_empty() {
+        return Err(CodegenError::NoFlags);
+    }
+
+    let mut constants = BTreeMap::new();
+    let mut variants = BTreeMap::new();
+    claim(&mut variants, String::from(type_name), type_name)?;
+    claim(&mut variants, format!("Parse{}Error", type_name), type_name)?;
+    claim(&mut constants, format!("{}_NAMES", constant_ident(type_name)), type_name)?;
+
+    let mut flags = Vec::new();
+    for flag in registry.flags() {
+        let constant = claim(&mut constants, constant_ident(flag.name()), flag.name())?;
+        let variant = claim(&mut variants, variant_ident(flag.name()), flag.name())?;
+        let mut aliases = Vec::new();
+        for alias in flag.aliases() {
+            aliases.push((alias.as_str(), claim(&mut constants, constant_ident(alias), alias)?));
+        }
+        flags.push(FlagIdents { flag, constant, variant, aliases });
+    }
+    let mut groups = Vec::new();
+    for group in registry.groups() {
+        groups.push((group, claim(&mut constants, constant_ident(group.name()), group.name())?));
+    }
+
+    let mut out = String::new();
+    write_module(&mut out, registry.width(), type_name, source, &flags, &groups)
+        .expect("writing to a `String` cannot fail");
+    Ok(out)
+}
+
+/// Writes `text` as `///` doc comment lines at `indent`.
+fn write_doc(out: &mut String, indent: &str, text: &str) -> fmt::Result {
+    for line in text.lines() {
+        writeln!(out, "{}///{}{}", indent, if line.is_empty() { "" } else { " " }, line)?;
+    }
+    Ok(())
+}
+
+fn write_module(
+    out: &mut String,
+    width: u32,
+    ty: &str,
+    source: Option<&str>,
+    flags: &[FlagIdents],
+    groups: &[(&RegisteredGroup, String)],
+) -> fmt::Result {
+    let flags_ty = format!("::arctan_bitflags::BitFlags{}", width);
+    let from = source.map(|source| format!(" from `{}`", source)).unwrap_or_default();
+    writeln!(out, "// @generated by arctan-bitflags{}. Do not edit by hand.", from)?;
+
+    for FlagIdents { flag, constant, aliases, .. } in flags {
+        writeln!(out)?;
+        write_doc(out, "", flag.description().unwrap_or(""))?;
+        if flag.is_deprecated() {
+            writeln!(out, "#[deprecated]")?;
+        }
+        let bits = format!("{}({:#x})", flags_ty, 1u128 << flag.index());
+        writeln!(out, "pub const {}: {} = {};", constant, flags_ty, bits)?;
+        for (alias, alias_constant) in aliases {
+            writeln!(out, "/// Deprecated alias (`{}`) of `{}`.", alias, constant)?;
+            writeln!(out, "#[deprecated(note = \"renamed to `{}`\")]", constant)?;
+            writeln!(out, "pub const {}: {} = {};", alias_constant, flags_ty, bits)?;
+        }
+    }
+    for (group, constant) in groups {
+        writeln!(out)?;
+        match group.description() {
+            Some(description) => write_doc(out, "", description)?,
+            None => writeln!(out, "/// The `{}` group.", group.name())?,
+        }
+        writeln!(out, "pub const {}: {} = {}({:#x});", constant, flags_ty, flags_ty, group.bits())?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "/// The name and bit index of every flag, in index order.")?;
+    writeln!(
+        out,
+        "pub const {}_NAMES: [(&str, usize); {}] = [",
+        constant_ident(ty),
+        flags.len()
+    )?;
+    for FlagIdents { flag, .. } in flags {
+        writeln!(out, "    ({:?}, {}),", flag.name(), flag.index())?;
+    }
+    writeln!(out, "];")?;
+
+    writeln!(out)?;
+    writeln!(out, "/// The bit index of each flag.")?;
+    writeln!(out, "#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]")?;
+    writeln!(out, "#[repr(u8)]")?;
+    writeln!(out, "pub enum {} {{", ty)?;
+    for FlagIdents { flag, variant, .. } in flags {
+        write_doc(out, "    ", flag.description().unwrap_or(""))?;
+        if flag.is_deprecated() {
+            writeln!(out, "    #[deprecated]")?;
+        }
+        writeln!(out, "    {} = {},", variant, flag.index())?;
+    }
+    writeln!(out, "}}")?;
+
+    let mut all = String::new();
+    let mut names = String::new();
+    let mut indexes = String::new();
+    let mut parses = String::new();
+    for FlagIdents { flag, variant, aliases, .. } in flags {
+        let name = flag.name();
+        let patterns = core::iter::once(name).chain(aliases.iter().map(|(alias, _)| *alias));
+        let patterns: Vec<String> = patterns.map(|name| format!("{:?}", name)).collect();
+        writeln!(all, "        {}::{},", ty, variant)?;
+        writeln!(names, "            {}::{} => {:?},", ty, variant, name)?;
+        writeln!(indexes, "            {} => Some({}::{}),", flag.index(), ty, variant)?;
+        writeln!(parses, "            {} => Some({}::{}),", patterns.join(" | "), ty, variant)?;
+    }
+
+    write!(
+        out,
+        r#"
+#[allow(deprecated)]
+impl {ty} {{
+    /// Every flag, in index order.
+    pub const ALL: [{ty}; {count}] = [
+{all}    ];
+
+    /// Returns the flag's bit index.
+    pub const fn index(self) -> usize {{
+        self as usize
+    }}
+    /// Returns the flag as bitflags.
+    pub const fn flag(self) -> {flags_ty} {{
+        {flags_ty}(1 << (self as u32))
+    }}
+    /// Returns the flag's name.
+    pub const fn name(self) -> &'static str {{
+        match self {{
+{names}        }}
+    }}
+    /// Returns the flag at bit `index`.
+    pub const fn from_index(index: usize) -> Option<{ty}> {{
+        match index {{
+{indexes}            _ => None,
+        }}
+    }}
+    /// Returns the flag named (or aliased) `name`.
+    pub fn from_name(name: &str) -> Option<{ty}> {{
+        match name {{
+{parses}            _ => None,
+        }}
+    }}
+}}
+
+impl From<{ty}> for {flags_ty} {{
+    fn from(flag: {ty}) -> Self {{
+        flag.flag()
+    }}
+}}
+
+impl ::core::fmt::Display for {ty} {{
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {{
+        f.write_str(self.name())
+    }}
+}}
+
+/// The error returned when parsing a name that is not a `{ty}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parse{ty}Error;
+
+impl ::core::fmt::Display for Parse{ty}Error {{
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {{
+        f.write_str("unknown {ty} name")
+    }}
+}}
+
+impl ::core::str::FromStr for {ty} {{
+    type Err = Parse{ty}Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {{
+        {ty}::from_name(s).ok_or(Parse{ty}Error)
+    }}
+}}
+"#,
+        ty = ty,
+        count = flags.len(),
+        flags_ty = flags_ty,
+        all = all,
+        names = names,
+        indexes = indexes,
+        parses = parses,
+    )
+}
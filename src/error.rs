@@ -0,0 +1,102 @@
+//! Errors returned by the non-panicking (`try_*`) bitflag methods.
+
+use core::num::ParseIntError;
+
+/// An error produced when an index, range, or value is invalid for a given bitflag width.
+///
+/// More variants may be added in minor releases, so matches must include a wildcard arm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BitFlagsError {
+    /// A bit index was outside of `0..bits`.
+    IndexOutOfRange { index: usize, bits: usize },
+    /// A bit range (`start..end`) extended past `bits`, or its start exceeded its end.
+    InvalidRange {
+        start: usize,
+        end:   usize,
+        bits:  usize,
+    },
+    /// A lane index was outside of `0..lanes`.
+    LaneOutOfRange { lane: usize, lanes: usize },
+    /// A value was too large for a packed field whose largest value is `max`.
+    FieldOverflow { value: u128, max: u128 },
+    /// No bits were set where _at least one_ is required.
+    Empty,
+    /// Bits outside of those permitted were set.
+    UnknownBits { bits: u128 },
+    /// More than one bit was mapped to bit `index`.
+    NotAPermutation { index: usize },
+    /// A width (in bits) was not one of `8`, `16`, `32`, `64`, or `128`.
+    InvalidWidth { width: u32 },
+    /// A subset table's length was not a power of two.
+    InvalidTableLength { len: usize },
+    /// A subset table's length did not match that of the table it was combined with.
+    TableLengthMismatch { len: usize, expected: usize },
+    /// Text could not be parsed as an integer.
+    Parse(ParseIntError),
+}
+
+impl From<ParseIntError> for BitFlagsError {
+    fn from(err: ParseIntError) -> Self {
+        BitFlagsError::Parse(err)
+    }
+}
+
+impl core::fmt::Display for BitFlagsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BitFlagsError::IndexOutOfRange { index, bits } => write!(
+                f,
+                "bit index {} is out of range: BitFlags{} are indexed from 0 to {}",
+                index,
+                bits,
+                bits.saturating_sub(1)
+            ),
+            BitFlagsError::InvalidRange { start, end, bits } => write!(
+                f,
+                "bit range {}..{} is invalid: BitFlags{} ranges must lie within 0..{}, and start \
+                 must be <= end",
+                start, end, bits, bits
+            ),
+            BitFlagsError::LaneOutOfRange { lane, lanes } => write!(
+                f,
+                "lane {} is out of range: lanes are indexed from 0 to {}",
+                lane,
+                lanes.saturating_sub(1)
+            ),
+            BitFlagsError::FieldOverflow { value, max } => {
+                write!(f, "value {} does not fit in field (max {})", value, max)
+            }
+            BitFlagsError::Empty => write!(f, "no bits set: at least one bit is required"),
+            BitFlagsError::UnknownBits { bits } => write!(f, "unknown bits set: {:#x}", bits),
+            BitFlagsError::NotAPermutation { index } => {
+                write!(f, "not a permutation: bit {} is the target of more than one bit", index)
+            }
+            BitFlagsError::InvalidWidth { width } => write!(
+                f,
+                "width {} is invalid: BitFlags are 8, 16, 32, 64, or 128 bits wide",
+                width
+            ),
+            BitFlagsError::InvalidTableLength { len } => {
+                write!(f, "table length {} is invalid: subset tables have 2^n entries", len)
+            }
+            BitFlagsError::TableLengthMismatch { len, expected } => {
+                write!(
+                    f,
+                    "table length {} does not match the other table's length {}",
+                    len, expected
+                )
+            }
+            BitFlagsError::Parse(err) => write!(f, "failed to parse bitflags: {}", err),
+        }
+    }
+}
+
+impl core::error::Error for BitFlagsError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            BitFlagsError::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
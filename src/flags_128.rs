@@ -4,7 +4,10 @@
 //! - JSON and RON (de)serialization of `BitFlags128` is _not_ supported for `nanoserde`.
 //! - BIN (de)serialization of `BitFlags128` is supported for both `serde` and `nanoserde`.
 
+use crate::{BitFlagsError, FlagNames, FlagsDisplay, Lanes};
 use core::convert::TryFrom;
+use core::num::NonZeroU128;
+use core::ops::{Bound, RangeBounds, RangeInclusive};
 
 /// 128-bit bitflags, indexed from bit indexes `[0]` to `[127]`.
 #[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -36,7 +39,13 @@ impl BitFlags128 {
         );
 
         let e = 1 << end;
-        BitFlags128(e | e - (1 << start))
+        BitFlags128(e | (e - (1 << start)))
+    }
+    /// Returns a new instance with bits in range `(start..=end)` set. Returns `Err` if
+    /// `start > 127`, `end > 127`, or `start > end`.
+    #[inline]
+    pub fn try_with_set_bit_range(start: u8, end: u8) -> Result<Self, BitFlagsError> {
+        Ok(Self(Self::inclusive_mask(start, end)?))
     }
     /// Returns a new instance from a `u128`.
     #[inline]
@@ -55,6 +64,11 @@ impl BitFlags128 {
     pub fn from_index(index: usize) -> Self {
         Self::try_from(index).unwrap()
     }
+    /// Converts an index (0-127) into a `BitFlags128`. Returns `Err` if `index > 127`.
+    #[inline]
+    pub fn try_from_index(index: usize) -> Result<Self, BitFlagsError> {
+        Self::try_from(index)
+    }
     /// Converts a slice of indexes (0-127) into a `BitFlags128`.
     #[inline]
     pub fn from_slice(s: &[usize]) -> Self {
@@ -67,6 +81,19 @@ impl BitFlags128 {
 
         bits
     }
+    /// Converts a slice of indexes (0-127) into a `BitFlags128`. Returns `Err` on the first
+    /// index greater than 127.
+    #[inline]
+    pub fn try_from_slice(s: &[usize]) -> Result<Self, BitFlagsError> {
+        let mut bits = Self(0);
+
+        for index in s.iter() {
+            let temp_bits = Self::try_from_index(*index)?;
+            bits.insert(temp_bits);
+        }
+
+        Ok(bits)
+    }
     /// Returns `true` if _no_ bits are set.
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -115,7 +142,7 @@ impl BitFlags128 {
     /// Inserts `other` flags into current flags using bitwise `OR` (`|`).
     #[inline]
     pub fn insert(&mut self, other: Self) {
-        self.0 = self.0 | other.0;
+        self.0 |= other.0;
     }
     /// Sets bit at given index (0-127).
     ///
@@ -123,7 +150,13 @@ impl BitFlags128 {
     #[inline]
     pub fn insert_at_index(&mut self, index: usize) {
         assert!(index < 128, "BitFlags128 are indexed from 0 to 127");
-        self.0 = self.0 | 2_u128.pow(index as u32);
+        self.0 |= 2_u128.pow(index as u32);
+    }
+    /// Sets bit at given index (0-127). Returns `Err` if `index > 127`.
+    #[inline]
+    pub fn try_insert_at_index(&mut self, index: usize) -> Result<(), BitFlagsError> {
+        self.insert(Self::try_from_index(index)?);
+        Ok(())
     }
     /// Inserts `other` if `value` is `true`; removes `other` if `value` is `false`.
     #[inline]
@@ -146,6 +179,13 @@ impl BitFlags128 {
             self.remove_at_index(index);
         }
     }
+    /// Sets bit at given index (0-127) to specific value (`true` = `1`; `false` = `0`). Returns
+    /// `Err` if `index > 127`.
+    #[inline]
+    pub fn try_set_at_index(&mut self, index: usize, value: bool) -> Result<(), BitFlagsError> {
+        self.set(Self::try_from_index(index)?, value);
+        Ok(())
+    }
     /// Sets bits in range `(start..=end)`.
     ///
     /// __Panics__ if `start > 127`, `end > 127`, or `start > end`.
@@ -157,12 +197,19 @@ impl BitFlags128 {
         );
 
         let e = 1 << end;
-        self.0 |= e | e - (1 << start);
+        self.0 |= e | (e - (1 << start));
+    }
+    /// Sets bits in range `(start..=end)`. Returns `Err` if `start > 127`, `end > 127`, or
+    /// `start > end`.
+    #[inline]
+    pub fn try_set_bit_range(&mut self, start: u8, end: u8) -> Result<(), BitFlagsError> {
+        self.0 |= Self::inclusive_mask(start, end)?;
+        Ok(())
     }
     /// Toggles bits based on mask (using bitwise `XOR`).
     #[inline]
     pub fn toggle(&mut self, mask: Self) {
-        self.0 = self.0 ^ mask.0;
+        self.0 ^= mask.0;
     }
     /// Toggles bit at given index (0-127).
     ///
@@ -170,12 +217,18 @@ impl BitFlags128 {
     #[inline]
     pub fn toggle_at_index(&mut self, index: usize) {
         assert!(index < 128, "BitFlags128 are indexed from 0 to 127");
-        self.0 = self.0 ^ 2_u128.pow(index as u32);
+        self.0 ^= 2_u128.pow(index as u32);
+    }
+    /// Toggles bit at given index (0-127). Returns `Err` if `index > 127`.
+    #[inline]
+    pub fn try_toggle_at_index(&mut self, index: usize) -> Result<(), BitFlagsError> {
+        self.toggle(Self::try_from_index(index)?);
+        Ok(())
     }
     /// Unsets bits that match those of incoming `BitFlags128` (bitwise `AND NOT`).
     #[inline]
     pub fn remove(&mut self, other: Self) {
-        self.0 = self.0 & !other.0;
+        self.0 &= !other.0;
     }
     /// Unsets bit at given index (0-127). Indexes 0-127 allowed.
     ///
@@ -183,7 +236,13 @@ impl BitFlags128 {
     #[inline]
     pub fn remove_at_index(&mut self, index: usize) {
         assert!(index < 128, "BitFlags128 are indexed from 0 to 127");
-        self.0 = self.0 & !2_u128.pow(index as u32);
+        self.0 &= !2_u128.pow(index as u32);
+    }
+    /// Unsets bit at given index (0-127). Returns `Err` if `index > 127`.
+    #[inline]
+    pub fn try_remove_at_index(&mut self, index: usize) -> Result<(), BitFlagsError> {
+        self.remove(Self::try_from_index(index)?);
+        Ok(())
     }
     /// Returns the number of bits.
     #[inline]
@@ -198,6 +257,12 @@ impl BitFlags128 {
         assert!(index < 128, "BitFlags128 are indexed from 0 to 127");
         self.0 & 2_u128.pow(index as u32) > 0
     }
+    /// Returns value of bit at given index (`0` is `false`; `1` is `true`). Returns `Err` if
+    /// `index > 127`.
+    #[inline]
+    pub fn try_bit_at_index(&self, index: usize) -> Result<bool, BitFlagsError> {
+        Ok(self.intersects(Self::try_from_index(index)?))
+    }
     /// Returns value of bit at given index (`0` is `false`; `1` is `true`). Returns `None` if out
     /// of bounds.
     #[inline]
@@ -210,7 +275,7 @@ impl BitFlags128 {
     /// Returns the value of the highest set bit. If none, returns empty flags.
     #[inline]
     pub fn highest_set_bit(&self) -> Self {
-        let mut n = self.0.clone();
+        let mut n = self.0;
         n |= n >> 1;
         n |= n >> 2;
         n |= n >> 4;
@@ -228,7 +293,7 @@ impl BitFlags128 {
             return None;
         }
 
-        let mut val = self.0.clone();
+        let mut val = self.0;
         let mut bit_ix = 0;
         while val > 0 {
             bit_ix += 1;
@@ -292,174 +357,1620 @@ impl BitFlags128 {
     pub fn iter(&self) -> BitFlagsIter128 {
         BitFlagsIter128 { current_bit: 0, bits: self.0 }
     }
-}
-
-impl From<u128> for BitFlags128 {
-    fn from(value: u128) -> Self {
-        BitFlags128(value)
+    /// Iterates over every subset of `self` (including empty and `self` itself), in increasing
+    /// order.
+    ///
+    /// E.g. the submasks of `0b0101` are `0b0000`, `0b0001`, `0b0100`, and `0b0101`.
+    #[inline]
+    pub fn submasks(&self) -> Subsets128 {
+        Subsets128 { base: 0, mask: self.0, next: Some(0) }
     }
-}
-
-impl TryFrom<u32> for BitFlags128 {
-    type Error = &'static str;
-
-    fn try_from(value: u32) -> Result<Self, Self::Error> {
-        if value < 128 {
-            Ok(Self(2_u128.pow(value)))
-        } else {
-            Err("BitFlags128 are indexed from 0 to 127")
+    /// Iterates over every non-empty subset of `self`, in increasing order.
+    #[inline]
+    pub fn nonempty_submasks(&self) -> Subsets128 {
+        let lowest = self.0 & self.0.wrapping_neg();
+        Subsets128 {
+            base: 0,
+            mask: self.0,
+            next: if lowest == 0 { None } else { Some(lowest) },
         }
     }
-}
-
-impl TryFrom<usize> for BitFlags128 {
-    type Error = &'static str;
-
-    fn try_from(value: usize) -> Result<Self, Self::Error> {
-        if value < 128 {
-            Ok(Self(2_u128.pow(value as u32)))
-        } else {
-            Err("BitFlags128 are indexed from 0 to 127")
+    /// Iterates over every superset of `self` that lies within `universe` (including `self` and
+    /// `universe` itself), in increasing order. Yields nothing if `self` is not within `universe`.
+    #[inline]
+    pub fn supersets_within(&self, universe: Self) -> Subsets128 {
+        Subsets128 {
+            base: self.0,
+            mask: universe.0 & !self.0,
+            next: if universe.contains(*self) { Some(0) } else { None },
         }
     }
-}
-
-impl core::fmt::Display for BitFlags128 {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "BitFlags128({})", self.0)
+    /// Iterates over every subset of `self` with exactly `k` bits set, in colexicographic
+    /// (increasing) order. Yields nothing if `k` is greater than the number of set bits.
+    ///
+    /// E.g. the 2-bit combinations of `0b0111` are `0b0011`, `0b0101`, and `0b0110`.
+    #[inline]
+    pub fn combinations(&self, k: u32) -> Combinations128 {
+        let ones = self.0.count_ones();
+        let first = (k <= ones).then(|| u128::MAX.checked_shr(128 - k).unwrap_or(0));
+        Combinations128 {
+            mask:  *self,
+            limit: u128::MAX.checked_shr(128 - ones).unwrap_or(0),
+            next:  first,
+        }
     }
-}
-
-impl core::fmt::Binary for BitFlags128 {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{:#0130b}", self.0)
+    /// Iterates over every subset of `self` in Gray-code order, starting from empty, so that
+    /// consecutive subsets differ by exactly one bit.
+    ///
+    /// E.g. the Gray-code order of `0b0101` is `0b0000`, `0b0001`, `0b0101`, and `0b0100`.
+    #[inline]
+    pub fn gray_code(&self) -> GrayCode128 {
+        GrayCode128 {
+            mask:  *self,
+            limit: u128::MAX.checked_shr(128 - self.0.count_ones()).unwrap_or(0),
+            next:  Some(0),
+        }
     }
-}
-
-impl core::ops::BitOr<BitFlags128> for BitFlags128 {
-    type Output = BitFlags128;
-
-    fn bitor(self, rhs: BitFlags128) -> Self::Output {
-        BitFlags128(self.0 | rhs.0)
+    /// Returns the value of the packed field in range `(start..=end)`, shifted down to bit `0`.
+    ///
+    /// __Panics__ if `start > 127`, `end > 127`, or `start > end`.
+    #[inline]
+    pub fn get_field(&self, range: RangeInclusive<u8>) -> u128 {
+        let (start, end) = (*range.start(), *range.end());
+        (self.0 & Self::with_set_bit_range(start, end).0) >> start
     }
-}
-
-impl core::ops::BitOrAssign<BitFlags128> for BitFlags128 {
-    fn bitor_assign(&mut self, rhs: BitFlags128) {
-        self.0 |= rhs.0
+    /// Returns the value of the packed field in range `(start..=end)`, shifted down to bit `0`.
+    /// Returns `Err` if `start > 127`, `end > 127`, or `start > end`.
+    #[inline]
+    pub fn try_get_field(&self, range: RangeInclusive<u8>) -> Result<u128, BitFlagsError> {
+        let (start, end) = (*range.start(), *range.end());
+        Ok((self.0 & Self::inclusive_mask(start, end)?) >> start)
     }
-}
-
-impl core::ops::BitAnd<BitFlags128> for BitFlags128 {
-    type Output = BitFlags128;
+    /// Sets the packed field in range `(start..=end)` to `value`. Bits outside of the field are
+    /// left unchanged.
+    ///
+    /// __Panics__ if `start > 127`, `end > 127`, `start > end`, or `value` does not fit in
+    /// the field.
+    #[inline]
+    pub fn set_field(&mut self, range: RangeInclusive<u8>, value: u128) {
+        let (start, end) = (*range.start(), *range.end());
+        let mask = Self::with_set_bit_range(start, end).0;
+        assert!(value <= mask >> start, "value does not fit in field ({}..={})", start, end);
 
-    fn bitand(self, rhs: BitFlags128) -> Self::Output {
-        BitFlags128(self.0 & rhs.0)
+        self.0 = (self.0 & !mask) | (value << start);
     }
-}
+    /// Sets the packed field in range `(start..=end)` to `value`. Returns `Err` (leaving `self`
+    /// unchanged) if `start > 127`, `end > 127`, `start > end`, or `value` does not fit in
+    /// the field.
+    #[inline]
+    pub fn try_set_field(
+        &mut self,
+        range: RangeInclusive<u8>,
+        value: u128,
+    ) -> Result<(), BitFlagsError> {
+        let (start, end) = (*range.start(), *range.end());
+        let mask = Self::inclusive_mask(start, end)?;
+        if value > mask >> start {
+            return Err(BitFlagsError::FieldOverflow { value, max: mask >> start });
+        }
 
-impl core::ops::BitAndAssign<BitFlags128> for BitFlags128 {
-    fn bitand_assign(&mut self, rhs: BitFlags128) {
-        self.0 &= rhs.0
+        self.0 = (self.0 & !mask) | (value << start);
+        Ok(())
     }
-}
-
-impl core::ops::BitXor<BitFlags128> for BitFlags128 {
-    type Output = BitFlags128;
-
-    fn bitxor(self, rhs: BitFlags128) -> Self::Output {
-        BitFlags128(self.0 ^ rhs.0)
+    /// Returns a new instance with bits in `range` set.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn from_range<R: RangeBounds<usize>>(range: R) -> Self {
+        Self(Self::range_mask(range).unwrap())
     }
-}
-
-impl core::ops::BitXorAssign<BitFlags128> for BitFlags128 {
-    fn bitxor_assign(&mut self, rhs: BitFlags128) {
-        self.0 ^= rhs.0
+    /// Returns a new instance with bits in `range` set. Returns `Err` if `range` extends past index
+    /// 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_from_range<R: RangeBounds<usize>>(range: R) -> Result<Self, BitFlagsError> {
+        Ok(Self(Self::range_mask(range)?))
     }
-}
-
-impl core::ops::Not for BitFlags128 {
-    type Output = BitFlags128;
-
-    /// Toggles _all_ bits.
-    fn not(self) -> Self::Output {
-        BitFlags128(!self.0)
+    /// Sets bits in `range`.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn insert_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        self.0 |= Self::range_mask(range).unwrap();
     }
-}
-
-/// Iterator over set bits of a `BitFlags128`.
-pub struct BitFlagsIter128 {
-    current_bit: usize,
-    bits:        u128,
-}
-
-impl core::iter::Iterator for BitFlagsIter128 {
-    type Item = usize;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        while self.bits != 0 {
-            if self.bits & 1 == 1 {
-                let ret = Some(self.current_bit);
-                self.bits >>= 1;
-                self.current_bit += 1;
-                return ret;
-            }
-            self.bits >>= 1;
-            self.current_bit += 1;
+    /// Sets bits in `range`. Returns `Err` (leaving `self` unchanged) if `range` extends past
+    /// index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_insert_range<R: RangeBounds<usize>>(
+        &mut self,
+        range: R,
+    ) -> Result<(), BitFlagsError> {
+        self.0 |= Self::range_mask(range)?;
+        Ok(())
+    }
+    /// Unsets bits in `range`.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn remove_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        self.0 &= !Self::range_mask(range).unwrap();
+    }
+    /// Unsets bits in `range`. Returns `Err` (leaving `self` unchanged) if `range` extends past
+    /// index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_remove_range<R: RangeBounds<usize>>(
+        &mut self,
+        range: R,
+    ) -> Result<(), BitFlagsError> {
+        self.0 &= !Self::range_mask(range)?;
+        Ok(())
+    }
+    /// Toggles bits in `range`.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn toggle_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        self.0 ^= Self::range_mask(range).unwrap();
+    }
+    /// Toggles bits in `range`. Returns `Err` (leaving `self` unchanged) if `range` extends past
+    /// index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_toggle_range<R: RangeBounds<usize>>(
+        &mut self,
+        range: R,
+    ) -> Result<(), BitFlagsError> {
+        self.0 ^= Self::range_mask(range)?;
+        Ok(())
+    }
+    /// Returns `true` if _all_ bits in `range` are set.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn contains_range<R: RangeBounds<usize>>(&self, range: R) -> bool {
+        self.contains(Self::from_range(range))
+    }
+    /// Returns `true` if _all_ bits in `range` are set. Returns `Err` if `range` extends past
+    /// index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_contains_range<R: RangeBounds<usize>>(
+        &self,
+        range: R,
+    ) -> Result<bool, BitFlagsError> {
+        Ok(self.contains(Self::try_from_range(range)?))
+    }
+    /// Returns `true` if _at least one_ bit in `range` is set.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn intersects_range<R: RangeBounds<usize>>(&self, range: R) -> bool {
+        self.intersects(Self::from_range(range))
+    }
+    /// Returns `true` if _at least one_ bit in `range` is set. Returns `Err` if `range` extends
+    /// past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_intersects_range<R: RangeBounds<usize>>(
+        &self,
+        range: R,
+    ) -> Result<bool, BitFlagsError> {
+        Ok(self.intersects(Self::try_from_range(range)?))
+    }
+    /// Returns the number of ones in `range`.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn count_ones_in<R: RangeBounds<usize>>(&self, range: R) -> u32 {
+        (self.0 & Self::range_mask(range).unwrap()).count_ones()
+    }
+    /// Returns the number of ones in `range`. Returns `Err` if `range` extends past index 127,
+    /// or if its start exceeds its end.
+    #[inline]
+    pub fn try_count_ones_in<R: RangeBounds<usize>>(&self, range: R) -> Result<u32, BitFlagsError> {
+        Ok((self.0 & Self::range_mask(range)?).count_ones())
+    }
+    /// Converts inclusive range `(start..=end)` into a mask of the bits it covers.
+    #[inline]
+    fn inclusive_mask(start: u8, end: u8) -> Result<u128, BitFlagsError> {
+        if start < 128 && end < 128 && start <= end {
+            let e: u128 = 1 << end;
+            Ok(e | (e - (1 << start)))
+        } else {
+            Err(BitFlagsError::InvalidRange {
+                start: start as usize,
+                end:   end as usize + 1,
+                bits:  128,
+            })
         }
-        None
     }
-}
-
-//  #######   ########   ######   ########  #######
-//  ##    ##  ##        ##        ##        ##    ##
-//  ##    ##  ######     ######   ######    #######
-//  ##    ##  ##              ##  ##        ##   ##
-//  #######   ########  #######   ########  ##    ##
-
-#[cfg(feature = "serde-support")]
-mod impl_serde {
-    use super::BitFlags128;
-    use serde::{Deserialize, Serialize};
+    /// Converts `range` into a mask of the bits it covers. An empty range produces an empty mask.
+    #[inline]
+    fn range_mask<R: RangeBounds<usize>>(range: R) -> Result<u128, BitFlagsError> {
+        let start = match range.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&e) => e.saturating_add(1),
+            Bound::Excluded(&e) => e,
+            Bound::Unbounded => 128,
+        };
 
-    impl<'de> Deserialize<'de> for BitFlags128 {
-        fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<BitFlags128, D::Error> {
-            let val = u128::deserialize(d)?;
-            Ok(BitFlags128(val))
+        if start > end || end > 128 {
+            return Err(BitFlagsError::InvalidRange { start, end, bits: 128 });
+        }
+        if start == end {
+            return Ok(0);
         }
-    }
 
-    impl Serialize for BitFlags128 {
-        fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
-            u128::serialize(&self.0, s)
+        Ok((u128::MAX >> (128 - (end - start))) << start)
+    }
+    /// Sets bit at given index.
+    #[inline]
+    pub fn insert_at(&mut self, index: Index128) {
+        self.0 |= 1 << index.0;
+    }
+    /// Unsets bit at given index.
+    #[inline]
+    pub fn remove_at(&mut self, index: Index128) {
+        self.0 &= !(1 << index.0);
+    }
+    /// Toggles bit at given index.
+    #[inline]
+    pub fn toggle_at(&mut self, index: Index128) {
+        self.0 ^= 1 << index.0;
+    }
+    /// Sets bit at given index to specific value (`true` = `1`; `false` = `0`).
+    #[inline]
+    pub fn set_at(&mut self, index: Index128, value: bool) {
+        if value {
+            self.insert_at(index);
+        } else {
+            self.remove_at(index);
         }
     }
-}
-
-#[cfg(feature = "nanoserde-support")]
-mod impl_nanoserde {
-    extern crate std;
-    use super::BitFlags128;
-    use nanoserde::{DeBin, DeBinErr, SerBin};
-    use std::prelude::v1::*;
-
-    impl DeBin for BitFlags128 {
-        fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr> {
-            let l = core::mem::size_of::<u128>();
-            if *offset + l > bytes.len() {
-                return Err(DeBinErr { o: *offset, l: l, s: bytes.len() });
+    /// Returns value of bit at given index (`0` is `false`; `1` is `true`).
+    #[inline]
+    pub fn get_at(&self, index: Index128) -> bool {
+        (self.0 >> index.0) & 1 == 1
+    }
+    /// Sets bit at given index (0-127). Returns `true` if the bit was _not_ already set.
+    ///
+    /// __Panics__ if `index > 127`.
+    #[inline]
+    pub fn add(&mut self, index: usize) -> bool {
+        let bit = Self::from_index(index);
+        let added = !self.intersects(bit);
+        self.insert(bit);
+        added
+    }
+    /// Sets bit at given index (0-127). Returns `Ok(true)` if the bit was _not_ already set, or
+    /// `Err` if `index > 127`.
+    #[inline]
+    pub fn try_add(&mut self, index: usize) -> Result<bool, BitFlagsError> {
+        let bit = Self::try_from_index(index)?;
+        let added = !self.intersects(bit);
+        self.insert(bit);
+        Ok(added)
+    }
+    /// Unsets bit at given index (0-127). Returns `true` if the bit was set.
+    ///
+    /// __Panics__ if `index > 127`.
+    #[inline]
+    pub fn take(&mut self, index: usize) -> bool {
+        let bit = Self::from_index(index);
+        let taken = self.intersects(bit);
+        self.remove(bit);
+        taken
+    }
+    /// Unsets bit at given index (0-127). Returns `Ok(true)` if the bit was set, or `Err` if
+    /// `index > 127`.
+    #[inline]
+    pub fn try_take(&mut self, index: usize) -> Result<bool, BitFlagsError> {
+        let bit = Self::try_from_index(index)?;
+        let taken = self.intersects(bit);
+        self.remove(bit);
+        Ok(taken)
+    }
+    /// Sets bit at given index (0-127) to specific value (`true` = `1`; `false` = `0`). Returns
+    /// the previous value of the bit.
+    ///
+    /// __Panics__ if `index > 127`.
+    #[inline]
+    pub fn replace(&mut self, index: usize, value: bool) -> bool {
+        let bit = Self::from_index(index);
+        let previous = self.intersects(bit);
+        self.set(bit, value);
+        previous
+    }
+    /// Sets bit at given index (0-127) to specific value. Returns the previous value of the bit, or
+    /// `Err` if `index > 127`.
+    #[inline]
+    pub fn try_replace(&mut self, index: usize, value: bool) -> Result<bool, BitFlagsError> {
+        let bit = Self::try_from_index(index)?;
+        let previous = self.intersects(bit);
+        self.set(bit, value);
+        Ok(previous)
+    }
+    /// Retains only the set bits whose index satisfies the predicate `f`.
+    #[inline]
+    pub fn retain<F: FnMut(usize) -> bool>(&mut self, mut f: F) {
+        for index in self.iter() {
+            if !f(index) {
+                self.remove_at_index(index);
             }
-            let val = u128::from_le_bytes(bytes[*offset..(*offset + l)].try_into().unwrap());
-            *offset += l;
-
-            Ok(BitFlags128(val))
         }
     }
-
-    impl SerBin for BitFlags128 {
-        fn ser_bin(&self, output: &mut Vec<u8>) {
-            let bytes = self.0.to_le_bytes();
-            output.extend_from_slice(&bytes);
+    /// Unsets _all_ bits, returning an iterator over the indexes that were set.
+    #[inline]
+    pub fn drain(&mut self) -> BitFlagsIter128 {
+        let iter = self.iter();
+        self.clear();
+        iter
+    }
+    /// Unsets _all_ bits.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0 = 0;
+    }
+    /// Returns the number of set bits.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+    /// Returns a mutable proxy for the bit at given index (0-127).
+    ///
+    /// __Panics__ if `index > 127`.
+    #[inline]
+    pub fn bit_mut(&mut self, index: usize) -> BitMut128<'_> {
+        assert!(index < 128, "BitFlags128 are indexed from 0 to 127");
+        BitMut128 { flags: self, index: index as u8 }
+    }
+    /// Returns a mutable proxy for the bit at given index (0-127). Returns `Err` if `index > 127`.
+    #[inline]
+    pub fn try_bit_mut(&mut self, index: usize) -> Result<BitMut128<'_>, BitFlagsError> {
+        if index >= 128 {
+            return Err(BitFlagsError::IndexOutOfRange { index, bits: 128 });
+        }
+        Ok(BitMut128 { flags: self, index: index as u8 })
+    }
+    /// Splits the bitflag into lanes of narrower bitflags `T`, lowest lane first.
+    ///
+    /// E.g. `flags.split::<BitFlags8>()` returns an array of 16 `BitFlags8`.
+    #[inline]
+    pub fn split<T>(self) -> <Self as Lanes<T>>::Array
+    where
+        Self: Lanes<T>,
+    {
+        Lanes::<T>::split(self)
+    }
+    /// Joins lanes of narrower bitflags `T` (lowest lane first) into a `BitFlags128`.
+    #[inline]
+    pub fn join<T>(lanes: <Self as Lanes<T>>::Array) -> Self
+    where
+        Self: Lanes<T>,
+    {
+        Lanes::<T>::join(lanes)
+    }
+    /// Returns lane `k` of narrower bitflags `T`, where lane `0` holds the lowest bits.
+    ///
+    /// __Panics__ if `k` is not less than the number of `T` lanes.
+    #[inline]
+    pub fn lane<T>(&self, k: usize) -> T
+    where
+        Self: Lanes<T>,
+    {
+        Lanes::<T>::lane(self, k)
+    }
+    /// Returns lane `k` of narrower bitflags `T`, where lane `0` holds the lowest bits. Returns
+    /// `Err` if `k` is not less than the number of `T` lanes.
+    #[inline]
+    pub fn try_lane<T>(&self, k: usize) -> Result<T, BitFlagsError>
+    where
+        Self: Lanes<T>,
+    {
+        Lanes::<T>::try_lane(self, k)
+    }
+    /// Replaces lane `k` of narrower bitflags `T` with `lane`, where lane `0` holds the lowest
+    /// bits.
+    ///
+    /// __Panics__ if `k` is not less than the number of `T` lanes.
+    #[inline]
+    pub fn set_lane<T>(&mut self, k: usize, lane: T)
+    where
+        Self: Lanes<T>,
+    {
+        Lanes::<T>::set_lane(self, k, lane)
+    }
+    /// Replaces lane `k` of narrower bitflags `T` with `lane`, where lane `0` holds the lowest
+    /// bits. Returns `Err` (leaving `self` unchanged) if `k` is not less than the number of `T`
+    /// lanes.
+    #[inline]
+    pub fn try_set_lane<T>(&mut self, k: usize, lane: T) -> Result<(), BitFlagsError>
+    where
+        Self: Lanes<T>,
+    {
+        Lanes::<T>::try_set_lane(self, k, lane)
+    }
+    /// Returns an MSB-0 view of the bitflag, numbering bits from the most significant end.
+    #[inline]
+    pub fn msb0(self) -> Msb0BitFlags128 {
+        Msb0BitFlags128(self)
+    }
+    /// Returns `true` if an _odd_ number of bits are set.
+    #[inline]
+    pub fn parity(&self) -> bool {
+        self.0.count_ones() & 1 == 1
+    }
+    /// Returns the lowest set bit only (`BLSI`). If none, returns empty flags.
+    #[inline]
+    pub fn isolate_lowest(&self) -> Self {
+        Self(self.0 & self.0.wrapping_neg())
+    }
+    /// Returns the bitflag with its lowest set bit unset (`BLSR`).
+    #[inline]
+    pub fn clear_lowest(&self) -> Self {
+        Self(self.0 & self.0.wrapping_sub(1))
+    }
+    /// Returns a mask of all bits up to and including the lowest set bit (`BLSMSK`). If none,
+    /// returns full flags.
+    #[inline]
+    pub fn mask_up_to_lowest(&self) -> Self {
+        Self(self.0 ^ self.0.wrapping_sub(1))
+    }
+    /// Returns `true` if _exactly one_ bit is set.
+    #[inline]
+    pub fn is_single_bit(&self) -> bool {
+        self.0.is_power_of_two()
+    }
+    /// Returns the index of the set bit if _exactly one_ bit is set, otherwise `None`.
+    #[inline]
+    pub fn single_index(&self) -> Option<usize> {
+        if self.is_single_bit() {
+            return Some(self.0.trailing_zeros() as usize);
+        }
+        None
+    }
+    /// Returns the bits of `a` where `mask` is set, and the bits of `b` where it is not (a bitwise
+    /// select, `(mask & a) | (!mask & b)`).
+    ///
+    /// Named `blend` rather than `select(mask, a, b)` because [`BitFlags128::select`] already
+    /// returns the index of the `k`-th set bit.
+    #[inline]
+    pub fn blend(mask: Self, a: Self, b: Self) -> Self {
+        Self(b.0 ^ ((a.0 ^ b.0) & mask.0))
+    }
+    /// Returns the next larger bitflag with the same number of set bits (Gosper's hack). Returns
+    /// `None` if empty, or if there is no larger bitflag with the same number of set bits.
+    #[inline]
+    pub fn next_same_popcount(&self) -> Option<Self> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let lowest = self.0 & self.0.wrapping_neg();
+        let ripple = self.0.checked_add(lowest)?;
+        let ones = ((ripple ^ self.0) >> self.0.trailing_zeros()) >> 2;
+        Some(Self(ripple | ones))
+    }
+    /// Returns the index of `self` among all bitflags with the same number of set bits, in
+    /// colexicographic order (the combinatorial number system). This is the position of `self`
+    /// in `BitFlags128::full().combinations(k)`, where `k` is the number of set bits.
+    ///
+    /// E.g. the 2-bit ranks of `0b0011`, `0b0101`, and `0b0110` are `0`, `1`, and `2`.
+    #[inline]
+    pub fn combinatorial_rank(&self) -> u128 {
+        // `binom` is `C(n, i)` for the `i`-th set bit (from 1), with `n` advanced to one past it.
+        let (mut rank, mut binom, mut n, mut i) = (0, 1, 1, 1);
+        for bit in self.iter() {
+            let bit = bit as u128;
+            while n <= bit {
+                binom = mul_div128(binom, n + 1, n + 1 - i);
+                n += 1;
+            }
+            rank += mul_div128(binom, n - i, n);
+            binom = mul_div128(binom, n + 1, i + 1);
+            n += 1;
+            i += 1;
+        }
+        rank
+    }
+    /// Returns the bitflag with `k` set bits whose combinatorial rank is `rank`. Returns `None` if
+    /// `k > 128`, or if `rank` is not less than the number of `k`-bit bitflags.
+    #[inline]
+    pub fn from_combinatorial_rank(k: u32, rank: u128) -> Option<Self> {
+        if k > 128 {
+            return None;
+        }
+        let k = k as u128;
+        let mut binom = binomial128(128, k);
+        if rank >= binom {
+            return None;
+        }
+
+        // `binom` is `C(c, i)`, walking `c` down to the largest with `C(c, i) <= rank`.
+        let mut rank = rank;
+        let mut bits = 0;
+        let mut c = 128;
+        for i in (1..=k).rev() {
+            if rank == 0 {
+                bits |= u128::MAX >> (128 - i);
+                break;
+            }
+            loop {
+                binom = mul_div128(binom, c - i, c);
+                c -= 1;
+                if binom <= rank {
+                    break;
+                }
+            }
+            bits |= 1 << c;
+            rank -= binom;
+            binom = mul_div128(binom, i, c - i + 1);
+        }
+        Some(Self(bits))
+    }
+    /// Returns the bitflag with all bits below the highest set bit also set.
+    #[inline]
+    pub fn smear_right(&self) -> Self {
+        if self.0 == 0 {
+            return Self(0);
+        }
+        Self(u128::MAX >> self.0.leading_zeros())
+    }
+    /// Returns the bitflag with all bits above the lowest set bit also set.
+    #[inline]
+    pub fn smear_left(&self) -> Self {
+        if self.0 == 0 {
+            return Self(0);
+        }
+        Self(u128::MAX << self.0.trailing_zeros())
+    }
+    /// Reverses the order of the bits: bit `0` becomes bit `127`, and vice versa.
+    #[inline]
+    pub fn reverse_bits(&self) -> Self {
+        Self(self.0.reverse_bits())
+    }
+    /// Gathers the bits of `self` selected by `mask` into the lowest bits of the result, in order
+    /// (parallel bit extract, `PEXT`).
+    ///
+    /// E.g. extracting `0b1010_0110` with mask `0b1111_0000` returns `0b1010`.
+    ///
+    /// Uses the BMI2 `PEXT` instruction when compiled with the `bmi2` target feature.
+    #[inline]
+    pub fn extract(&self, mask: Self) -> Self {
+        #[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+        {
+            use core::arch::x86_64::_pext_u64;
+
+            let (val_lo, val_hi) = (self.0 as u64, (self.0 >> 64) as u64);
+            let (mask_lo, mask_hi) = (mask.0 as u64, (mask.0 >> 64) as u64);
+            // SAFETY: the `bmi2` target feature is enabled at compile time.
+            let (lo, hi) = unsafe { (_pext_u64(val_lo, mask_lo), _pext_u64(val_hi, mask_hi)) };
+            Self(lo as u128 | ((hi as u128) << mask_lo.count_ones()))
+        }
+        #[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+        {
+            Self(Self::extract_portable(self.0, mask.0))
+        }
+    }
+    /// Scatters the lowest bits of `self`, in order, to the bits selected by `mask` (parallel bit
+    /// deposit, `PDEP`). This is the inverse of [`BitFlags128::extract`].
+    ///
+    /// E.g. depositing `0b1010` with mask `0b1111_0000` returns `0b1010_0000`.
+    ///
+    /// Uses the BMI2 `PDEP` instruction when compiled with the `bmi2` target feature.
+    #[inline]
+    pub fn deposit(&self, mask: Self) -> Self {
+        #[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+        {
+            use core::arch::x86_64::_pdep_u64;
+
+            let (mask_lo, mask_hi) = (mask.0 as u64, (mask.0 >> 64) as u64);
+            let (val_lo, val_hi) = (self.0 as u64, (self.0 >> mask_lo.count_ones()) as u64);
+            // SAFETY: the `bmi2` target feature is enabled at compile time.
+            let (lo, hi) = unsafe { (_pdep_u64(val_lo, mask_lo), _pdep_u64(val_hi, mask_hi)) };
+            Self(lo as u128 | ((hi as u128) << 64))
+        }
+        #[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+        {
+            Self(Self::deposit_portable(self.0, mask.0))
+        }
+    }
+    /// Portable `PEXT`, moving one run of consecutive mask bits per step.
+    #[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+    #[inline]
+    fn extract_portable(val: u128, mut mask: u128) -> u128 {
+        let mut result = 0;
+        let mut filled = 0;
+
+        while mask != 0 {
+            let start = mask.trailing_zeros();
+            let run = (mask >> start).trailing_ones();
+            let run_mask = u128::MAX >> (128 - run);
+
+            result |= ((val >> start) & run_mask) << filled;
+            filled += run;
+            mask &= !(run_mask << start);
+        }
+
+        result
+    }
+    /// Portable `PDEP`, moving one run of consecutive mask bits per step.
+    #[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+    #[inline]
+    fn deposit_portable(val: u128, mut mask: u128) -> u128 {
+        let mut result = 0;
+        let mut taken = 0;
+
+        while mask != 0 {
+            let start = mask.trailing_zeros();
+            let run = (mask >> start).trailing_ones();
+            let run_mask = u128::MAX >> (128 - run);
+
+            result |= ((val >> taken) & run_mask) << start;
+            taken += run;
+            mask &= !(run_mask << start);
+        }
+
+        result
+    }
+    /// Returns the number of set bits _below_ index `i` (0-128). `rank(128)` counts all set bits.
+    ///
+    /// __Panics__ if `i > 128`.
+    #[inline]
+    pub fn rank(&self, i: usize) -> u32 {
+        assert!(i <= 128, "BitFlags128 rank is defined for indexes 0 to 128");
+        if i == 0 {
+            return 0;
+        }
+        (self.0 << (128 - i)).count_ones()
+    }
+    /// Returns the number of set bits _below_ index `i` (0-128). Returns `Err` (the invalid range
+    /// `0..i`) if `i > 128`.
+    #[inline]
+    pub fn try_rank(&self, i: usize) -> Result<u32, BitFlagsError> {
+        if i > 128 {
+            return Err(BitFlagsError::InvalidRange { start: 0, end: i, bits: 128 });
+        }
+        Ok(self.rank(i))
+    }
+    /// Returns the number of set bits _at or below_ index `i` (0-127).
+    ///
+    /// __Panics__ if `i > 127`.
+    #[inline]
+    pub fn rank_inclusive(&self, i: usize) -> u32 {
+        assert!(i < 128, "BitFlags128 are indexed from 0 to 127");
+        (self.0 << (127 - i)).count_ones()
+    }
+    /// Returns the number of set bits _at or below_ index `i` (0-127). Returns `Err` if
+    /// `i > 127`.
+    #[inline]
+    pub fn try_rank_inclusive(&self, i: usize) -> Result<u32, BitFlagsError> {
+        if i >= 128 {
+            return Err(BitFlagsError::IndexOutOfRange { index: i, bits: 128 });
+        }
+        Ok(self.rank_inclusive(i))
+    }
+    /// Returns the index of the `k`-th set bit (counting from `0`), or `None` if fewer than
+    /// `k + 1` bits are set.
+    ///
+    /// Uses [`BitFlags128::deposit`], and so a single `PDEP` with the `bmi2` target feature.
+    #[inline]
+    pub fn select(&self, k: usize) -> Option<usize> {
+        if k >= self.0.count_ones() as usize {
+            return None;
+        }
+        Some(BitFlags128(1 << k).deposit(*self).0.trailing_zeros() as usize)
+    }
+    /// Returns the index of the `k`-th unset bit (counting from `0`), or `None` if fewer than
+    /// `k + 1` bits are unset.
+    #[inline]
+    pub fn select_unset(&self, k: usize) -> Option<usize> {
+        self.complement().select(k)
+    }
+    /// Returns an adapter that writes the set bits by name from `names`, e.g. as
+    /// `burning | stunned | 0x400`, with any unnamed bits as a single hex value.
+    #[inline]
+    pub fn display_with<'a, N: FlagNames + ?Sized>(&self, names: &'a N) -> FlagsDisplay<'a, N> {
+        FlagsDisplay::new(self.0, names)
+    }
+}
+
+/// Returns `C(n, k)`, computed incrementally rather than stored, to keep a `u128` table out of
+/// every binary.
+#[inline]
+fn binomial128(n: u128, k: u128) -> u128 {
+    let k = k.min(n - k);
+    (1..=k).fold(1, |binom, j| mul_div128(binom, n - j + 1, j))
+}
+
+/// Returns `a * b / c` for small `b` and `c`, where the result is known to be an integer that fits,
+/// without overflowing on `a * b`.
+#[inline]
+fn mul_div128(a: u128, b: u128, c: u128) -> u128 {
+    a / c * b + a % c * b / c
+}
+
+impl From<u128> for BitFlags128 {
+    fn from(value: u128) -> Self {
+        BitFlags128(value)
+    }
+}
+
+impl From<Index128> for BitFlags128 {
+    fn from(index: Index128) -> Self {
+        BitFlags128(1 << index.0)
+    }
+}
+
+impl TryFrom<u32> for BitFlags128 {
+    type Error = BitFlagsError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        if value < 128 {
+            Ok(Self(2_u128.pow(value)))
+        } else {
+            Err(BitFlagsError::IndexOutOfRange { index: value as usize, bits: 128 })
+        }
+    }
+}
+
+impl TryFrom<usize> for BitFlags128 {
+    type Error = BitFlagsError;
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        if value < 128 {
+            Ok(Self(2_u128.pow(value as u32)))
+        } else {
+            Err(BitFlagsError::IndexOutOfRange { index: value, bits: 128 })
+        }
+    }
+}
+
+impl core::fmt::Display for BitFlags128 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "BitFlags128({})", self.0)
+    }
+}
+
+impl core::str::FromStr for BitFlags128 {
+    type Err = BitFlagsError;
+
+    /// Parses a decimal, `0x`-prefixed hexadecimal, or `0b`-prefixed binary integer, optionally
+    /// wrapped as `BitFlags128(...)` (the `Display` format).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s
+            .strip_prefix("BitFlags128(")
+            .and_then(|s| s.strip_suffix(')'))
+            .unwrap_or(s);
+
+        let val = if let Some(hex) = s.strip_prefix("0x") {
+            u128::from_str_radix(hex, 16)?
+        } else if let Some(bin) = s.strip_prefix("0b") {
+            u128::from_str_radix(bin, 2)?
+        } else {
+            s.parse::<u128>()?
+        };
+
+        Ok(BitFlags128(val))
+    }
+}
+
+impl core::fmt::Binary for BitFlags128 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:#0130b}", self.0)
+    }
+}
+
+impl core::ops::BitOr<BitFlags128> for BitFlags128 {
+    type Output = BitFlags128;
+
+    fn bitor(self, rhs: BitFlags128) -> Self::Output {
+        BitFlags128(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign<BitFlags128> for BitFlags128 {
+    fn bitor_assign(&mut self, rhs: BitFlags128) {
+        self.0 |= rhs.0
+    }
+}
+
+impl core::ops::BitAnd<BitFlags128> for BitFlags128 {
+    type Output = BitFlags128;
+
+    fn bitand(self, rhs: BitFlags128) -> Self::Output {
+        BitFlags128(self.0 & rhs.0)
+    }
+}
+
+impl core::ops::BitAndAssign<BitFlags128> for BitFlags128 {
+    fn bitand_assign(&mut self, rhs: BitFlags128) {
+        self.0 &= rhs.0
+    }
+}
+
+impl core::ops::BitXor<BitFlags128> for BitFlags128 {
+    type Output = BitFlags128;
+
+    fn bitxor(self, rhs: BitFlags128) -> Self::Output {
+        BitFlags128(self.0 ^ rhs.0)
+    }
+}
+
+impl core::ops::BitXorAssign<BitFlags128> for BitFlags128 {
+    fn bitxor_assign(&mut self, rhs: BitFlags128) {
+        self.0 ^= rhs.0
+    }
+}
+
+impl core::ops::Not for BitFlags128 {
+    type Output = BitFlags128;
+
+    /// Toggles _all_ bits.
+    fn not(self) -> Self::Output {
+        BitFlags128(!self.0)
+    }
+}
+
+impl core::ops::Index<usize> for BitFlags128 {
+    type Output = bool;
+
+    /// Returns value of bit at given index (`0` is `false`; `1` is `true`).
+    ///
+    /// __Panics__ if `index > 127`.
+    fn index(&self, index: usize) -> &Self::Output {
+        if self.bit_at_index(index) {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+/// Iterator over set bits of a `BitFlags128`.
+pub struct BitFlagsIter128 {
+    current_bit: usize,
+    bits:        u128,
+}
+
+impl core::iter::Iterator for BitFlagsIter128 {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.bits != 0 {
+            if self.bits & 1 == 1 {
+                let ret = Some(self.current_bit);
+                self.bits >>= 1;
+                self.current_bit += 1;
+                return ret;
+            }
+            self.bits >>= 1;
+            self.current_bit += 1;
+        }
+        None
+    }
+}
+
+/// Iterator over the bitflags between `base` and `base | mask` (inclusive) that contain `base`,
+/// in increasing order. Returned by `submasks`, `nonempty_submasks`, and `supersets_within`.
+#[derive(Debug, Clone)]
+pub struct Subsets128 {
+    base: u128,
+    mask: u128,
+    next: Option<u128>,
+}
+
+impl core::iter::Iterator for Subsets128 {
+    type Item = BitFlags128;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = if current == self.mask {
+            None
+        } else {
+            Some(current.wrapping_sub(self.mask) & self.mask)
+        };
+        Some(BitFlags128(self.base | current))
+    }
+}
+
+/// Iterator over the `k`-bit subsets of a `BitFlags128`, in colexicographic order. Returned by
+/// `combinations`.
+#[derive(Debug, Clone)]
+pub struct Combinations128 {
+    mask:  BitFlags128,
+    limit: u128,
+    next:  Option<u128>,
+}
+
+impl core::iter::Iterator for Combinations128 {
+    type Item = BitFlags128;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Steps through the `k`-bit values below `limit`, then scatters each one into `mask`.
+        let current = BitFlags128(self.next?);
+        self.next = current
+            .next_same_popcount()
+            .map(|c| c.0)
+            .filter(|c| c & !self.limit == 0);
+        Some(current.deposit(self.mask))
+    }
+}
+
+/// Iterator over the subsets of a `BitFlags128` in Gray-code order. Returned by `gray_code`.
+#[derive(Debug, Clone)]
+pub struct GrayCode128 {
+    mask:  BitFlags128,
+    limit: u128,
+    next:  Option<u128>,
+}
+
+impl core::iter::Iterator for GrayCode128 {
+    type Item = BitFlags128;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = if current == self.limit { None } else { Some(current + 1) };
+        Some(BitFlags128(current ^ (current >> 1)).deposit(self.mask))
+    }
+}
+
+/// A named field of packed bits in range `(start..=end)` of a `BitFlags128`.
+///
+/// Lets a single `BitFlags128` hold both flags and small integers, e.g.
+/// `const LEVEL: BitField128 = BitField128::new(4, 6);`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct BitField128 {
+    start: u8,
+    end:   u8,
+}
+
+impl BitField128 {
+    /// Returns a new field over bits in range `(start..=end)`.
+    ///
+    /// __Panics__ if `start > 127`, `end > 127`, or `start > end`.
+    #[inline]
+    pub const fn new(start: u8, end: u8) -> Self {
+        assert!(
+            start < 128 && end < 128 && start <= end,
+            "BitFlags128 are indexed from 0 to 127, and start must be <= end"
+        );
+
+        Self { start, end }
+    }
+    /// Returns a new field over bits in range `(start..=end)`. Returns `Err` if `start > 127`,
+    /// `end > 127`, or `start > end`.
+    #[inline]
+    pub const fn try_new(start: u8, end: u8) -> Result<Self, BitFlagsError> {
+        if start < 128 && end < 128 && start <= end {
+            Ok(Self { start, end })
+        } else {
+            Err(BitFlagsError::InvalidRange {
+                start: start as usize,
+                end:   end as usize + 1,
+                bits:  128,
+            })
+        }
+    }
+    /// Returns the index of the lowest bit of the field.
+    #[inline]
+    pub fn start(&self) -> u8 {
+        self.start
+    }
+    /// Returns the index of the highest bit of the field.
+    #[inline]
+    pub fn end(&self) -> u8 {
+        self.end
+    }
+    /// Returns the number of bits in the field.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        (self.end - self.start + 1) as u32
+    }
+    /// Returns the largest value the field can hold.
+    #[inline]
+    pub fn max_value(&self) -> u128 {
+        self.mask().0 >> self.start
+    }
+    /// Returns a `BitFlags128` with all bits of the field set.
+    #[inline]
+    pub fn mask(&self) -> BitFlags128 {
+        BitFlags128::with_set_bit_range(self.start, self.end)
+    }
+    /// Returns the value of the field within `flags`.
+    #[inline]
+    pub fn get(&self, flags: BitFlags128) -> u128 {
+        flags.get_field(self.start..=self.end)
+    }
+    /// Sets the value of the field within `flags`.
+    ///
+    /// __Panics__ if `value > self.max_value()`.
+    #[inline]
+    pub fn set(&self, flags: &mut BitFlags128, value: u128) {
+        flags.set_field(self.start..=self.end, value)
+    }
+    /// Sets the value of the field within `flags`. Returns `Err` (leaving `flags` unchanged) if
+    /// `value > self.max_value()`.
+    #[inline]
+    pub fn try_set(&self, flags: &mut BitFlags128, value: u128) -> Result<(), BitFlagsError> {
+        flags.try_set_field(self.start..=self.end, value)
+    }
+}
+
+/// A bit index (0-127) of a `BitFlags128`, checked on construction.
+///
+/// Methods taking an `Index128` (e.g. [`BitFlags128::insert_at`]) skip the bounds checks made by
+/// their `usize` counterparts.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Index128(u8);
+
+impl Index128 {
+    /// The lowest index, `0`.
+    pub const MIN: Self = Self(0);
+    /// The highest index, `127`.
+    pub const MAX: Self = Self(127);
+
+    /// Returns a new index.
+    ///
+    /// __Panics__ if `index > 127`.
+    #[inline]
+    pub const fn new(index: usize) -> Self {
+        assert!(index < 128, "BitFlags128 are indexed from 0 to 127");
+        Self(index as u8)
+    }
+    /// Returns a new index. Returns `Err` if `index > 127`.
+    #[inline]
+    pub const fn try_new(index: usize) -> Result<Self, BitFlagsError> {
+        if index < 128 {
+            Ok(Self(index as u8))
+        } else {
+            Err(BitFlagsError::IndexOutOfRange { index, bits: 128 })
+        }
+    }
+    /// Returns the index as a `usize`.
+    #[inline]
+    pub const fn get(self) -> usize {
+        self.0 as usize
+    }
+    /// Iterates over all indexes, from `0` to `127`.
+    #[inline]
+    pub fn all() -> impl DoubleEndedIterator<Item = Self> + ExactSizeIterator {
+        (0..128).map(Self)
+    }
+}
+
+impl From<Index128> for usize {
+    fn from(index: Index128) -> Self {
+        index.get()
+    }
+}
+
+impl TryFrom<usize> for Index128 {
+    type Error = BitFlagsError;
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Self::try_new(value)
+    }
+}
+
+impl core::fmt::Display for Index128 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Mutable proxy for a single bit of a `BitFlags128`, returned by [`BitFlags128::bit_mut`].
+pub struct BitMut128<'a> {
+    flags: &'a mut BitFlags128,
+    index: u8,
+}
+
+impl BitMut128<'_> {
+    /// Returns value of the bit (`0` is `false`; `1` is `true`).
+    #[inline]
+    pub fn get(&self) -> bool {
+        (self.flags.0 >> self.index) & 1 == 1
+    }
+    /// Sets the bit to specific value (`true` = `1`; `false` = `0`).
+    #[inline]
+    pub fn set(&mut self, value: bool) {
+        self.flags.set_at(Index128(self.index), value);
+    }
+    /// Toggles the bit.
+    #[inline]
+    pub fn toggle(&mut self) {
+        self.flags.toggle_at(Index128(self.index));
+    }
+}
+
+/// 128-bit bitflags with _at least one_ bit set, backed by a `NonZeroU128`.
+///
+/// `Option<NonEmptyBitFlags128>` is the same size as `BitFlags128`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NonEmptyBitFlags128(NonZeroU128);
+
+impl NonEmptyBitFlags128 {
+    /// Returns a new instance from `flags`, or `None` if `flags` is empty.
+    #[inline]
+    pub const fn new(flags: BitFlags128) -> Option<Self> {
+        match NonZeroU128::new(flags.0) {
+            Some(val) => Some(Self(val)),
+            None => None,
+        }
+    }
+    /// Returns a new instance with all bits set to `1`.
+    #[inline]
+    pub const fn full() -> Self {
+        Self(NonZeroU128::MAX)
+    }
+    /// Converts an index (0-127) into a `NonEmptyBitFlags128`.
+    ///
+    /// __Panics__ if `index > 127`.
+    #[inline]
+    pub fn from_index(index: usize) -> Self {
+        Self::try_from_index(index).unwrap()
+    }
+    /// Converts an index (0-127) into a `NonEmptyBitFlags128`. Returns `Err` if `index > 127`.
+    #[inline]
+    pub fn try_from_index(index: usize) -> Result<Self, BitFlagsError> {
+        Self::try_from(BitFlags128::try_from_index(index)?)
+    }
+    /// Returns the underlying `BitFlags128`.
+    #[inline]
+    pub const fn get(&self) -> BitFlags128 {
+        BitFlags128(self.0.get())
+    }
+    /// Returns the underlying `u128` value.
+    #[inline]
+    pub const fn to_u128(&self) -> u128 {
+        self.0.get()
+    }
+    /// Returns `true` if current flags contain _all_ incoming flags.
+    #[inline]
+    pub fn contains(&self, other: BitFlags128) -> bool {
+        self.get().contains(other)
+    }
+    /// Returns `true` if `self` and `other` have _at least one_ matching set bit.
+    #[inline]
+    pub fn intersects(&self, other: BitFlags128) -> bool {
+        self.get().intersects(other)
+    }
+    /// Returns the bitwise `OR` (`|`) of two flags, which is never empty.
+    #[inline]
+    pub fn union(&self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+    /// Returns the bitwise `AND` (`&`) of two flags, or `None` if they share no set bits.
+    #[inline]
+    pub fn intersection(&self, other: Self) -> Option<Self> {
+        Self::new(self.get().intersection(other.get()))
+    }
+    /// Returns the bits set in `self` that are _not_ set in `other`, or `None` if there are none.
+    #[inline]
+    pub fn difference(&self, other: Self) -> Option<Self> {
+        Self::new(self.get().difference(other.get()))
+    }
+    /// Returns the number of ones in the bitflag. Always at least `1`.
+    #[inline]
+    pub fn count_ones(&self) -> u32 {
+        self.0.count_ones().get()
+    }
+    /// Iterates over set bits of the structure. Yields _at least one_ bit index.
+    #[inline]
+    pub fn iter(&self) -> BitFlagsIter128 {
+        self.get().iter()
+    }
+}
+
+impl From<NonEmptyBitFlags128> for BitFlags128 {
+    fn from(value: NonEmptyBitFlags128) -> Self {
+        value.get()
+    }
+}
+
+impl TryFrom<BitFlags128> for NonEmptyBitFlags128 {
+    type Error = BitFlagsError;
+
+    fn try_from(value: BitFlags128) -> Result<Self, Self::Error> {
+        Self::new(value).ok_or(BitFlagsError::Empty)
+    }
+}
+
+impl core::fmt::Display for NonEmptyBitFlags128 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "NonEmptyBitFlags128({})", self.0)
+    }
+}
+
+impl core::ops::BitOr<NonEmptyBitFlags128> for NonEmptyBitFlags128 {
+    type Output = NonEmptyBitFlags128;
+
+    fn bitor(self, rhs: NonEmptyBitFlags128) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl core::ops::BitOrAssign<NonEmptyBitFlags128> for NonEmptyBitFlags128 {
+    fn bitor_assign(&mut self, rhs: NonEmptyBitFlags128) {
+        *self = self.union(rhs)
+    }
+}
+
+/// MSB-0 view of a `BitFlags128`, numbering bits from the most significant end: index `0` is the
+/// most significant bit and index `127` is the least significant bit.
+///
+/// Useful for transcribing network RFCs and hardware datasheets, which often number bits this way.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Msb0BitFlags128(pub BitFlags128);
+
+impl Msb0BitFlags128 {
+    /// Returns a new instance with all bits set to `0`.
+    #[inline]
+    pub fn empty() -> Self {
+        Self(BitFlags128::empty())
+    }
+    /// Returns a new instance with all bits set to `1`.
+    #[inline]
+    pub fn full() -> Self {
+        Self(BitFlags128::full())
+    }
+    /// Returns the underlying (LSB-0) `BitFlags128`.
+    #[inline]
+    pub fn lsb0(self) -> BitFlags128 {
+        self.0
+    }
+    /// Converts an MSB-0 index (0-127) into a `Msb0BitFlags128`.
+    ///
+    /// __Panics__ if `index > 127`.
+    #[inline]
+    pub fn from_index(index: usize) -> Self {
+        Self::try_from_index(index).unwrap()
+    }
+    /// Converts an MSB-0 index (0-127) into a `Msb0BitFlags128`. Returns `Err` if
+    /// `index > 127`.
+    #[inline]
+    pub fn try_from_index(index: usize) -> Result<Self, BitFlagsError> {
+        Ok(Self(BitFlags128::try_from_index(Self::lsb0_index(index)?)?))
+    }
+    /// Converts a slice of MSB-0 indexes (0-127) into a `Msb0BitFlags128`.
+    ///
+    /// __Panics__ if any index is greater than 127.
+    #[inline]
+    pub fn from_slice(s: &[usize]) -> Self {
+        Self::try_from_slice(s).unwrap()
+    }
+    /// Converts a slice of MSB-0 indexes (0-127) into a `Msb0BitFlags128`. Returns `Err` on the
+    /// first index greater than 127.
+    #[inline]
+    pub fn try_from_slice(s: &[usize]) -> Result<Self, BitFlagsError> {
+        let mut bits = Self::empty();
+
+        for index in s.iter() {
+            bits.try_insert_at_index(*index)?;
+        }
+
+        Ok(bits)
+    }
+    /// Returns a new instance with bits in MSB-0 `range` set.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn from_range<R: RangeBounds<usize>>(range: R) -> Self {
+        Self::try_from_range(range).unwrap()
+    }
+    /// Returns a new instance with bits in MSB-0 `range` set. Returns `Err` if `range` extends
+    /// past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_from_range<R: RangeBounds<usize>>(range: R) -> Result<Self, BitFlagsError> {
+        Ok(Self(Self::range_mask(range)?))
+    }
+    /// Sets bit at given MSB-0 index (0-127).
+    ///
+    /// __Panics__ if `index > 127`.
+    #[inline]
+    pub fn insert_at_index(&mut self, index: usize) {
+        self.try_insert_at_index(index).unwrap()
+    }
+    /// Sets bit at given MSB-0 index (0-127). Returns `Err` if `index > 127`.
+    #[inline]
+    pub fn try_insert_at_index(&mut self, index: usize) -> Result<(), BitFlagsError> {
+        self.0.try_insert_at_index(Self::lsb0_index(index)?)
+    }
+    /// Unsets bit at given MSB-0 index (0-127).
+    ///
+    /// __Panics__ if `index > 127`.
+    #[inline]
+    pub fn remove_at_index(&mut self, index: usize) {
+        self.try_remove_at_index(index).unwrap()
+    }
+    /// Unsets bit at given MSB-0 index (0-127). Returns `Err` if `index > 127`.
+    #[inline]
+    pub fn try_remove_at_index(&mut self, index: usize) -> Result<(), BitFlagsError> {
+        self.0.try_remove_at_index(Self::lsb0_index(index)?)
+    }
+    /// Toggles bit at given MSB-0 index (0-127).
+    ///
+    /// __Panics__ if `index > 127`.
+    #[inline]
+    pub fn toggle_at_index(&mut self, index: usize) {
+        self.try_toggle_at_index(index).unwrap()
+    }
+    /// Toggles bit at given MSB-0 index (0-127). Returns `Err` if `index > 127`.
+    #[inline]
+    pub fn try_toggle_at_index(&mut self, index: usize) -> Result<(), BitFlagsError> {
+        self.0.try_toggle_at_index(Self::lsb0_index(index)?)
+    }
+    /// Sets bit at given MSB-0 index (0-127) to specific value (`true` = `1`; `false` = `0`).
+    ///
+    /// __Panics__ if `index > 127`.
+    #[inline]
+    pub fn set_at_index(&mut self, index: usize, value: bool) {
+        self.try_set_at_index(index, value).unwrap()
+    }
+    /// Sets bit at given MSB-0 index (0-127) to specific value (`true` = `1`; `false` = `0`).
+    /// Returns `Err` if `index > 127`.
+    #[inline]
+    pub fn try_set_at_index(&mut self, index: usize, value: bool) -> Result<(), BitFlagsError> {
+        self.0.try_set_at_index(Self::lsb0_index(index)?, value)
+    }
+    /// Returns value of bit at given MSB-0 index (`0` is `false`; `1` is `true`).
+    ///
+    /// __Panics__ if `index > 127`.
+    #[inline]
+    pub fn bit_at_index(&self, index: usize) -> bool {
+        self.try_bit_at_index(index).unwrap()
+    }
+    /// Returns value of bit at given MSB-0 index (`0` is `false`; `1` is `true`). Returns `Err` if
+    /// `index > 127`.
+    #[inline]
+    pub fn try_bit_at_index(&self, index: usize) -> Result<bool, BitFlagsError> {
+        self.0.try_bit_at_index(Self::lsb0_index(index)?)
+    }
+    /// Returns value of bit at given MSB-0 index (`0` is `false`; `1` is `true`). Returns `None`
+    /// if out of bounds.
+    #[inline]
+    pub fn get_bit_at_index(&self, index: usize) -> Option<bool> {
+        self.try_bit_at_index(index).ok()
+    }
+    /// Sets bits in MSB-0 `range`.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn insert_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        self.try_insert_range(range).unwrap()
+    }
+    /// Sets bits in MSB-0 `range`. Returns `Err` (leaving `self` unchanged) if `range` extends
+    /// past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_insert_range<R: RangeBounds<usize>>(
+        &mut self,
+        range: R,
+    ) -> Result<(), BitFlagsError> {
+        self.0.insert(Self::range_mask(range)?);
+        Ok(())
+    }
+    /// Unsets bits in MSB-0 `range`.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn remove_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        self.try_remove_range(range).unwrap()
+    }
+    /// Unsets bits in MSB-0 `range`. Returns `Err` (leaving `self` unchanged) if `range` extends
+    /// past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_remove_range<R: RangeBounds<usize>>(
+        &mut self,
+        range: R,
+    ) -> Result<(), BitFlagsError> {
+        self.0.remove(Self::range_mask(range)?);
+        Ok(())
+    }
+    /// Toggles bits in MSB-0 `range`.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn toggle_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        self.try_toggle_range(range).unwrap()
+    }
+    /// Toggles bits in MSB-0 `range`. Returns `Err` (leaving `self` unchanged) if `range` extends
+    /// past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_toggle_range<R: RangeBounds<usize>>(
+        &mut self,
+        range: R,
+    ) -> Result<(), BitFlagsError> {
+        self.0.toggle(Self::range_mask(range)?);
+        Ok(())
+    }
+    /// Returns `true` if _all_ bits in MSB-0 `range` are set.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn contains_range<R: RangeBounds<usize>>(&self, range: R) -> bool {
+        self.try_contains_range(range).unwrap()
+    }
+    /// Returns `true` if _all_ bits in MSB-0 `range` are set. Returns `Err` if `range` extends
+    /// past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_contains_range<R: RangeBounds<usize>>(
+        &self,
+        range: R,
+    ) -> Result<bool, BitFlagsError> {
+        Ok(self.0.contains(Self::range_mask(range)?))
+    }
+    /// Returns `true` if _at least one_ bit in MSB-0 `range` is set.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn intersects_range<R: RangeBounds<usize>>(&self, range: R) -> bool {
+        self.try_intersects_range(range).unwrap()
+    }
+    /// Returns `true` if _at least one_ bit in MSB-0 `range` is set. Returns `Err` if `range`
+    /// extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_intersects_range<R: RangeBounds<usize>>(
+        &self,
+        range: R,
+    ) -> Result<bool, BitFlagsError> {
+        Ok(self.0.intersects(Self::range_mask(range)?))
+    }
+    /// Returns the number of ones in MSB-0 `range`.
+    ///
+    /// __Panics__ if `range` extends past index 127, or if its start exceeds its end.
+    #[inline]
+    pub fn count_ones_in<R: RangeBounds<usize>>(&self, range: R) -> u32 {
+        self.try_count_ones_in(range).unwrap()
+    }
+    /// Returns the number of ones in MSB-0 `range`. Returns `Err` if `range` extends past index
+    /// 127, or if its start exceeds its end.
+    #[inline]
+    pub fn try_count_ones_in<R: RangeBounds<usize>>(&self, range: R) -> Result<u32, BitFlagsError> {
+        Ok(self.0.intersection(Self::range_mask(range)?).count_ones())
+    }
+    /// Iterates over set bits of the structure by MSB-0 index, starting from the most significant
+    /// bit.
+    ///
+    /// E.g. collecting `0b1001 << 64` into a vector would produce the MSB-0 indexes
+    /// `vec![60, 63]`.
+    #[inline]
+    pub fn iter(&self) -> BitFlagsIter128 {
+        BitFlags128(self.0 .0.reverse_bits()).iter()
+    }
+    /// Converts an MSB-0 index into its LSB-0 counterpart.
+    #[inline]
+    fn lsb0_index(index: usize) -> Result<usize, BitFlagsError> {
+        if index < 128 {
+            Ok(127 - index)
+        } else {
+            Err(BitFlagsError::IndexOutOfRange { index, bits: 128 })
+        }
+    }
+    /// Converts MSB-0 `range` into an (LSB-0) mask of the bits it covers.
+    #[inline]
+    fn range_mask<R: RangeBounds<usize>>(range: R) -> Result<BitFlags128, BitFlagsError> {
+        Ok(BitFlags128(BitFlags128::try_from_range(range)?.0.reverse_bits()))
+    }
+}
+
+impl From<BitFlags128> for Msb0BitFlags128 {
+    fn from(value: BitFlags128) -> Self {
+        Msb0BitFlags128(value)
+    }
+}
+
+impl From<Msb0BitFlags128> for BitFlags128 {
+    fn from(value: Msb0BitFlags128) -> Self {
+        value.0
+    }
+}
+
+/// Writes the MSB-0 indexes of the set bits in increasing order, e.g. `Msb0BitFlags128{0, 1, 2}`
+/// for the three most significant bits.
+impl core::fmt::Display for Msb0BitFlags128 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Msb0BitFlags128{")?;
+        for (i, index) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", index)?;
+        }
+        f.write_str("}")
+    }
+}
+
+/// Writes the bits with MSB-0 index `0` leftmost, which are the same digits as the underlying
+/// `BitFlags128`'s.
+impl core::fmt::Binary for Msb0BitFlags128 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:#0130b}", self.0 .0)
+    }
+}
+
+//  #######   ########   ######   ########  #######
+//  ##    ##  ##        ##        ##        ##    ##
+//  ##    ##  ######     ######   ######    #######
+//  ##    ##  ##              ##  ##        ##   ##
+//  #######   ########  #######   ########  ##    ##
+
+#[cfg(feature = "serde-support")]
+mod impl_serde {
+    use super::{BitFlags128, NonEmptyBitFlags128};
+    use serde::de::Error;
+    use serde::{Deserialize, Serialize};
+
+    impl<'de> Deserialize<'de> for BitFlags128 {
+        fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<BitFlags128, D::Error> {
+            let val = u128::deserialize(d)?;
+            Ok(BitFlags128(val))
+        }
+    }
+
+    impl Serialize for BitFlags128 {
+        fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            u128::serialize(&self.0, s)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for NonEmptyBitFlags128 {
+        fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<NonEmptyBitFlags128, D::Error> {
+            let val = BitFlags128::deserialize(d)?;
+            NonEmptyBitFlags128::try_from(val).map_err(D::Error::custom)
+        }
+    }
+
+    impl Serialize for NonEmptyBitFlags128 {
+        fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            u128::serialize(&self.to_u128(), s)
+        }
+    }
+}
+
+#[cfg(feature = "nanoserde-support")]
+mod impl_nanoserde {
+    extern crate std;
+    use super::{BitFlags128, NonEmptyBitFlags128};
+    use nanoserde::{DeBin, DeBinErr, SerBin};
+    use std::prelude::v1::*;
+
+    impl DeBin for BitFlags128 {
+        fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr> {
+            let l = core::mem::size_of::<u128>();
+            if *offset + l > bytes.len() {
+                return Err(DeBinErr { o: *offset, l, s: bytes.len() });
+            }
+            let val = u128::from_le_bytes(bytes[*offset..(*offset + l)].try_into().unwrap());
+            *offset += l;
+
+            Ok(BitFlags128(val))
+        }
+    }
+
+    impl SerBin for BitFlags128 {
+        fn ser_bin(&self, output: &mut Vec<u8>) {
+            let bytes = self.0.to_le_bytes();
+            output.extend_from_slice(&bytes);
+        }
+    }
+
+    /// Zero is rejected as if the input were too short, as `DeBinErr` carries no message.
+    impl DeBin for NonEmptyBitFlags128 {
+        fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr> {
+            let start = *offset;
+            let val = BitFlags128::de_bin(offset, bytes)?;
+
+            NonEmptyBitFlags128::new(val).ok_or_else(|| {
+                *offset = start;
+                DeBinErr { o: start, l: 16, s: bytes.len() }
+            })
+        }
+    }
+
+    impl SerBin for NonEmptyBitFlags128 {
+        fn ser_bin(&self, output: &mut Vec<u8>) {
+            self.get().ser_bin(output)
         }
     }
 }
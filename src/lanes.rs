@@ -0,0 +1,127 @@
+//! Splitting bitflags into (and joining them from) lanes of narrower bitflags.
+//!
+//! Lane `0` holds the lowest bits, e.g. lane `1` of a `BitFlags64` split into `BitFlags16` holds
+//! bits `16..32`.
+
+use crate::{BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8, BitFlagsError};
+
+/// Bitflags that are made up of `LANES` lanes of narrower bitflags, `T`.
+pub trait Lanes<T>: Sized {
+    /// The number of `T` lanes in `Self`.
+    const LANES: usize;
+    /// An array of `LANES` lanes, lowest lane first.
+    type Array;
+
+    /// Splits `self` into its lanes, lowest lane first.
+    fn split(self) -> Self::Array;
+    /// Joins lanes (lowest lane first) into a single instance.
+    fn join(lanes: Self::Array) -> Self;
+    /// Returns lane `k`.
+    ///
+    /// __Panics__ if `k >= LANES`.
+    fn lane(&self, k: usize) -> T;
+    /// Replaces lane `k` with `lane`, leaving all other lanes unchanged.
+    ///
+    /// __Panics__ if `k >= LANES`.
+    fn set_lane(&mut self, k: usize, lane: T);
+    /// Returns lane `k`. Returns `Err` if `k >= LANES`.
+    #[inline]
+    fn try_lane(&self, k: usize) -> Result<T, BitFlagsError> {
+        if k < Self::LANES {
+            Ok(self.lane(k))
+        } else {
+            Err(BitFlagsError::LaneOutOfRange { lane: k, lanes: Self::LANES })
+        }
+    }
+    /// Replaces lane `k` with `lane`. Returns `Err` (leaving `self` unchanged) if `k >= LANES`.
+    #[inline]
+    fn try_set_lane(&mut self, k: usize, lane: T) -> Result<(), BitFlagsError> {
+        if k < Self::LANES {
+            self.set_lane(k, lane);
+            Ok(())
+        } else {
+            Err(BitFlagsError::LaneOutOfRange { lane: k, lanes: Self::LANES })
+        }
+    }
+}
+
+macro_rules! impl_lanes {
+    ($wide:ident, $wide_int:ty, $narrow:ident, $narrow_int:ty, $lanes:literal) => {
+        impl Lanes<$narrow> for $wide {
+            const LANES: usize = $lanes;
+            type Array = [$narrow; $lanes];
+
+            #[inline]
+            fn split(self) -> Self::Array {
+                let mut lanes = [$narrow(0); $lanes];
+                for (k, lane) in lanes.iter_mut().enumerate() {
+                    *lane = Lanes::<$narrow>::lane(&self, k);
+                }
+
+                lanes
+            }
+            #[inline]
+            fn join(lanes: Self::Array) -> Self {
+                let mut flags = $wide(0);
+                for (k, lane) in lanes.iter().enumerate() {
+                    flags.0 |= (lane.0 as $wide_int) << (k as u32 * <$narrow_int>::BITS);
+                }
+
+                flags
+            }
+            #[inline]
+            fn lane(&self, k: usize) -> $narrow {
+                assert!(
+                    k < $lanes,
+                    concat!(
+                        stringify!($wide),
+                        " has ",
+                        stringify!($lanes),
+                        " lanes of ",
+                        stringify!($narrow)
+                    )
+                );
+                $narrow((self.0 >> (k as u32 * <$narrow_int>::BITS)) as $narrow_int)
+            }
+            #[inline]
+            fn set_lane(&mut self, k: usize, lane: $narrow) {
+                assert!(
+                    k < $lanes,
+                    concat!(
+                        stringify!($wide),
+                        " has ",
+                        stringify!($lanes),
+                        " lanes of ",
+                        stringify!($narrow)
+                    )
+                );
+                let shift = k as u32 * <$narrow_int>::BITS;
+                let mask = (<$narrow_int>::MAX as $wide_int) << shift;
+                self.0 = (self.0 & !mask) | ((lane.0 as $wide_int) << shift);
+            }
+        }
+
+        impl From<[$narrow; $lanes]> for $wide {
+            fn from(lanes: [$narrow; $lanes]) -> Self {
+                Lanes::<$narrow>::join(lanes)
+            }
+        }
+
+        impl From<$wide> for [$narrow; $lanes] {
+            fn from(flags: $wide) -> Self {
+                Lanes::<$narrow>::split(flags)
+            }
+        }
+    };
+}
+
+impl_lanes!(BitFlags16, u16, BitFlags8, u8, 2);
+impl_lanes!(BitFlags32, u32, BitFlags8, u8, 4);
+impl_lanes!(BitFlags32, u32, BitFlags16, u16, 2);
+impl_lanes!(BitFlags64, u64, BitFlags8, u8, 8);
+impl_lanes!(BitFlags64, u64, BitFlags16, u16, 4);
+impl_lanes!(BitFlags64, u64, BitFlags32, u32, 2);
+impl_lanes!(BitFlags128, u128, BitFlags8, u8, 16);
+impl_lanes!(BitFlags128, u128, BitFlags16, u16, 8);
+impl_lanes!(BitFlags128, u128, BitFlags32, u32, 4);
+impl_lanes!(BitFlags128, u128, BitFlags64, u64, 2);
@@ -3,14 +3,52 @@
 //! For more information on the logic involved, refer to the useful wiki on [Set Theory](https://en.wikipedia.org/wiki/Set_theory).
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod any;
+#[cfg(feature = "codegen")]
+mod codegen;
+mod error;
 mod flags_8;
 mod flags_16;
 mod flags_32;
 mod flags_64;
 mod flags_128;
+mod lanes;
+mod macros;
+mod names;
+#[cfg(feature = "alloc")]
+mod parse;
+mod permutation;
+#[cfg(feature = "alloc")]
+mod rank_select;
+#[cfg(feature = "alloc")]
+mod registry;
+mod remap;
+mod schema;
+mod transforms;
+mod typed;
 
+pub use any::*;
+#[cfg(feature = "codegen")]
+pub use codegen::*;
+pub use error::*;
 pub use flags_8::*;
 pub use flags_16::*;
 pub use flags_32::*;
 pub use flags_64::*;
 pub use flags_128::*;
+pub use lanes::*;
+pub use names::*;
+#[cfg(feature = "alloc")]
+pub use parse::*;
+pub use permutation::*;
+#[cfg(feature = "alloc")]
+pub use rank_select::*;
+#[cfg(feature = "alloc")]
+pub use registry::*;
+pub use remap::*;
+pub use schema::*;
+pub use transforms::*;
+pub use typed::*;
@@ -0,0 +1,192 @@
+//! Declarative macros for naming bitflags.
+
+/// Declares named constants of a bitflag type (e.g. `BitFlags64`), either in a new module or in an
+/// inherent `impl` block of a local type.
+///
+/// Each constant is either a bit index (`const BURNING = 0;`) or the union of other constants
+/// declared in the same block (`const FIRE_DAMAGE = [BURNING, SCORCHED];`). Indexes are checked at
+/// compile time: an index past the last bit, or one used by two constants, fails to compile.
+///
+/// Alongside the constants, the block gets:
+/// - `NAMES`, a table of `(name, flags)` for every constant, in declaration order.
+/// - `from_name(&str)`, the constant with the given name, if any.
+/// - `name_of(flags)`, the name of the first constant equal to `flags`, if any.
+///
+/// ```rust
+/// use arctan_bitflags::{flag_constants, BitFlags64};
+///
+/// flag_constants! {
+///     /// Status effects.
+///     pub mod status: BitFlags64 {
+///         /// Takes fire damage each turn.
+///         const BURNING = 0;
+///         const SCORCHED = 1;
+///         const FROZEN = 4;
+///         const FIRE_DAMAGE = [BURNING, SCORCHED];
+///     }
+/// }
+///
+/// pub struct Layer;
+///
+/// flag_constants! {
+///     impl Layer: BitFlags64 {
+///         const WORLD = 0;
+///         const PLAYER = 1;
+///     }
+/// }
+///
+/// fn main() {
+///     assert_eq!(status::FIRE_DAMAGE, BitFlags64(0b0011));
+///     assert_eq!(status::from_name("FROZEN"), Some(BitFlags64(0b1_0000)));
+///     assert_eq!(status::name_of(status::BURNING), Some("BURNING"));
+///     assert_eq!(Layer::NAMES, &[("WORLD", BitFlags64(0b01)), ("PLAYER", BitFlags64(0b10))]);
+/// }
+/// ```
+///
+/// In the module form, the module imports everything from its parent (`use super::*`), so the
+/// bitflag type may be named as it is outside.
+///
+/// An index past the last bit fails to compile:
+///
+/// ```compile_fail
+/// use arctan_bitflags::{flag_constants, BitFlags8};
+///
+/// flag_constants! {
+///     pub mod status: BitFlags8 {
+///         const BURNING = 8;
+///     }
+/// }
+/// ```
+///
+/// So do two constants sharing an index:
+///
+/// ```compile_fail
+/// use arctan_bitflags::{flag_constants, BitFlags8};
+///
+/// flag_constants! {
+///     pub mod status: BitFlags8 {
+///         const BURNING = 0;
+///         const FROZEN = 0;
+///     }
+/// }
+/// ```
+///
+/// And a union naming a constant that is not declared in the block:
+///
+/// ```compile_fail
+/// use arctan_bitflags::{flag_constants, BitFlags8};
+///
+/// flag_constants! {
+///     pub mod status: BitFlags8 {
+///         const BURNING = 0;
+///         const FIRE_DAMAGE = [BURNING, SCORCHED];
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! flag_constants {
+    (
+        $(#[$module_attr:meta])*
+        $vis:vis mod $module:ident : $flags:path {
+            $(
+                $(#[$attr:meta])*
+                const $name:ident = $value:tt;
+            )*
+        }
+    ) => {
+        $(#[$module_attr])*
+        $vis mod $module {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $(
+                $(#[$attr])*
+                pub const $name: $flags =
+                    $crate::flag_constants!(@value $flags, self, $name, $value);
+            )*
+
+            const _: () = $crate::flag_constants!(@check $flags, $($name = $value),*);
+
+            /// Every constant in this module with its name, in declaration order.
+            pub const NAMES: &[(&str, $flags)] = &[$((stringify!($name), $name)),*];
+
+            /// Returns the constant named `name`, if any.
+            #[allow(dead_code)]
+            pub fn from_name(name: &str) -> Option<$flags> {
+                NAMES.iter().find(|(n, _)| *n == name).map(|(_, flags)| *flags)
+            }
+            /// Returns the name of the first constant equal to `flags`, if any.
+            #[allow(dead_code)]
+            pub fn name_of(flags: $flags) -> Option<&'static str> {
+                NAMES.iter().find(|(_, f)| *f == flags).map(|(name, _)| *name)
+            }
+        }
+    };
+    (
+        impl $ty:ident : $flags:path {
+            $(
+                $(#[$attr:meta])*
+                const $name:ident = $value:tt;
+            )*
+        }
+    ) => {
+        #[allow(dead_code)]
+        impl $ty {
+            $(
+                $(#[$attr])*
+                pub const $name: $flags =
+                    $crate::flag_constants!(@value $flags, $ty, $name, $value);
+            )*
+
+            /// Every named constant of this type with its name, in declaration order.
+            pub const NAMES: &'static [(&'static str, $flags)] =
+                &[$((stringify!($name), Self::$name)),*];
+
+            /// Returns the constant named `name`, if any.
+            pub fn from_name(name: &str) -> Option<$flags> {
+                Self::NAMES.iter().find(|(n, _)| *n == name).map(|(_, flags)| *flags)
+            }
+            /// Returns the name of the first constant equal to `flags`, if any.
+            pub fn name_of(flags: $flags) -> Option<&'static str> {
+                Self::NAMES.iter().find(|(_, f)| *f == flags).map(|(name, _)| *name)
+            }
+        }
+
+        const _: () = $crate::flag_constants!(@check $flags, $($name = $value),*);
+    };
+    (@value $flags:path, $scope:ident, $name:ident, [$($part:ident),* $(,)?]) => {
+        $flags(0 $(| $scope::$part.0)*)
+    };
+    (@value $flags:path, $scope:ident, $name:ident, $index:tt) => {
+        $flags(1 << $index)
+    };
+    (@index [$($part:tt)*]) => {
+        ::core::option::Option::<usize>::None
+    };
+    (@index $index:tt) => {
+        ::core::option::Option::Some($index as usize)
+    };
+    (@check $flags:path, $($name:ident = $value:tt),*) => {{
+        $(
+            if let Some(index) = $crate::flag_constants!(@index $value) {
+                assert!(
+                    index < 8 * ::core::mem::size_of::<$flags>(),
+                    concat!("index of `", stringify!($name), "` is out of range")
+                );
+            }
+        )*
+        let indexes: &[::core::option::Option<usize>] =
+            &[$($crate::flag_constants!(@index $value)),*];
+        let mut i = 0;
+        while i < indexes.len() {
+            let mut j = i + 1;
+            while j < indexes.len() {
+                if let (Some(a), Some(b)) = (indexes[i], indexes[j]) {
+                    assert!(a != b, "two flag constants share an index");
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+    }};
+}
@@ -0,0 +1,141 @@
+//! Writing bitflags by flag name, e.g. `burning | stunned | 0x400`.
+//!
+//! Names come from any [`FlagNames`] table: a `FlagRegistry`, a generated `(name, index)` table,
+//! or a `flag_constants!` `NAMES` table. Nothing is allocated, so this works without `alloc`.
+
+use crate::{BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8};
+use core::fmt;
+
+/// A table of flag names, by bit index.
+pub trait FlagNames {
+    /// Returns the name of the flag at bit `index`, if any.
+    fn name_at(&self, index: usize) -> Option<&str>;
+}
+
+/// A `(name, index)` table, as generated by `Codegen`. The first name for an index is used.
+impl FlagNames for [(&str, usize)] {
+    fn name_at(&self, index: usize) -> Option<&str> {
+        self.iter().find(|(_, i)| *i == index).map(|(name, _)| *name)
+    }
+}
+
+impl<const N: usize> FlagNames for [(&str, usize); N] {
+    #[inline]
+    fn name_at(&self, index: usize) -> Option<&str> {
+        self[..].name_at(index)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FlagNames for crate::FlagRegistry {
+    #[inline]
+    fn name_at(&self, index: usize) -> Option<&str> {
+        self.name_of(index)
+    }
+}
+
+macro_rules! impl_flag_names {
+    ($flags:ident) => {
+        /// A `(name, flags)` table, as declared by `flag_constants!`. Only constants with a
+        /// single bit set name that bit.
+        impl FlagNames for [(&str, $flags)] {
+            fn name_at(&self, index: usize) -> Option<&str> {
+                self.iter()
+                    .find(|(_, flags)| flags.single_index() == Some(index))
+                    .map(|(name, _)| *name)
+            }
+        }
+    };
+}
+
+impl_flag_names!(BitFlags8);
+impl_flag_names!(BitFlags16);
+impl_flag_names!(BitFlags32);
+impl_flag_names!(BitFlags64);
+impl_flag_names!(BitFlags128);
+
+/// Writes bitflags by name, returned by e.g. `BitFlags64::display_with`.
+///
+/// Each set bit with a name is written by name, in index order, followed by any remaining
+/// (unnamed) bits as a single hex value, e.g. `burning | stunned | 0x400`. Empty flags are written
+/// as `0x0`.
+#[derive(Clone, Copy)]
+pub struct FlagsDisplay<'a, N: ?Sized> {
+    bits:      u128,
+    names:     &'a N,
+    separator: &'a str,
+    braced:    bool,
+}
+
+impl<'a, N: FlagNames + ?Sized> FlagsDisplay<'a, N> {
+    /// Returns an adapter writing `bits` with `names`, separated by `" | "`.
+    #[inline]
+    pub fn new(bits: u128, names: &'a N) -> Self {
+        Self {
+            bits,
+            names,
+            separator: " | ",
+            braced: false,
+        }
+    }
+    /// Separates flags with `separator` instead of `" | "`.
+    #[inline]
+    pub fn separator(mut self, separator: &'a str) -> Self {
+        self.separator = separator;
+        self
+    }
+    /// Writes flags as a set, e.g. `{burning, stunned}`, with `", "` as the separator. Empty flags
+    /// are written as `{}`.
+    #[inline]
+    pub fn braced(mut self) -> Self {
+        self.separator = ", ";
+        self.braced = true;
+        self
+    }
+}
+
+impl<N: FlagNames + ?Sized> fmt::Display for FlagsDisplay<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.braced {
+            f.write_str("{")?;
+        }
+
+        let mut first = true;
+        let mut unnamed: u128 = 0;
+        let mut bits = self.bits;
+        while bits != 0 {
+            let index = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+
+            match self.names.name_at(index) {
+                Some(name) => {
+                    if !first {
+                        f.write_str(self.separator)?;
+                    }
+                    f.write_str(name)?;
+                    first = false;
+                }
+                None => unnamed |= 1 << index,
+            }
+        }
+
+        if unnamed != 0 || (first && !self.braced) {
+            if !first {
+                f.write_str(self.separator)?;
+            }
+            write!(f, "{:#x}", unnamed)?;
+        }
+
+        if self.braced {
+            f.write_str("}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<N: FlagNames + ?Sized> fmt::Debug for FlagsDisplay<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
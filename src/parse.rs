@@ -0,0 +1,285 @@
+//! Parsing flag expressions by name over a [`FlagRegistry`], e.g. `burning | stunned | 12`.
+//!
+//! An expression is a list of terms separated by `|` or `,`, optionally wrapped in `{...}`. Each
+//! term is one of:
+//! - a flag name, alias, or group name, e.g. `burning`.
+//! - a decimal bit index, e.g. `12`.
+//! - a `0x` (or `0b`) literal of raw bits, e.g. `0x400`.
+//! - a range of bit indexes, e.g. `4..8` or `4..=7`.
+//! - a negated term, e.g. `!burning`: every known bit (defined or deprecated) except its bits.
+//!
+//! Anything written by `display_with` (with the same registry) parses back to the same flags.
+
+use crate::{BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8, FlagRegistry};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// A problem found while parsing a flag expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFlagsError {
+    /// The byte offset (in the input) of the term with the problem.
+    pub position: usize,
+    /// What the problem is.
+    pub kind:     ParseFlagsErrorKind,
+}
+
+/// The kind of a [`ParseFlagsError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseFlagsErrorKind {
+    /// A term was missing, e.g. in `a || b`, or in empty input.
+    MissingTerm,
+    /// An opening `{` had no closing `}`.
+    UnclosedBrace,
+    /// A name is not a flag, alias, or group. `suggestion` is the closest known name, if any is
+    /// close enough.
+    UnknownName {
+        name:       String,
+        suggestion: Option<String>,
+    },
+    /// A number could not be parsed.
+    InvalidNumber { text: String },
+    /// A bit index does not fit in the width.
+    IndexOutOfRange { index: usize, width: u32 },
+    /// A range's start exceeds its end.
+    InvalidRange { start: usize, end: usize },
+    /// A literal has bits set beyond the width.
+    BitsOutOfRange { bits: u128, width: u32 },
+}
+
+impl core::fmt::Display for ParseFlagsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "at {}: ", self.position)?;
+        match &self.kind {
+            ParseFlagsErrorKind::MissingTerm => write!(f, "expected a flag"),
+            ParseFlagsErrorKind::UnclosedBrace => write!(f, "unclosed `{{`"),
+            ParseFlagsErrorKind::UnknownName { name, suggestion: Some(suggestion) } => {
+                write!(f, "unknown flag {:?} (did you mean {:?}?)", name, suggestion)
+            }
+            ParseFlagsErrorKind::UnknownName { name, suggestion: None } => {
+                write!(f, "unknown flag {:?}", name)
+            }
+            ParseFlagsErrorKind::InvalidNumber { text } => write!(f, "invalid number {:?}", text),
+            ParseFlagsErrorKind::IndexOutOfRange { index, width } => {
+                write!(f, "index {} does not fit in {} bits", index, width)
+            }
+            ParseFlagsErrorKind::InvalidRange { start, end } => {
+                write!(f, "range start {} exceeds its end {}", start, end)
+            }
+            ParseFlagsErrorKind::BitsOutOfRange { bits, width } => {
+                write!(f, "bits {:#x} do not fit in {} bits", bits, width)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseFlagsError {}
+
+impl FlagRegistry {
+    /// Parses a flag expression into bits within the registry's width: names, aliases, group
+    /// names, bit indexes, `0x`/`0b` literals, ranges (`4..8`), and negations (`!burning`),
+    /// separated by `|` or `,` and optionally wrapped in `{...}`.
+    pub fn parse(&self, s: &str) -> Result<u128, ParseFlagsError> {
+        Parser {
+            registry: self,
+            width:    self.width(),
+        }
+        .expression(s)
+    }
+}
+
+macro_rules! impl_parse_with {
+    ($flags:ident, $int:ty, $bits:literal) => {
+        impl $flags {
+            /// Parses a flag expression by name over `registry`, e.g. `burning | stunned | 12`.
+            /// Returns `Err` with the position of the first problem, including any bits beyond the
+            /// narrower of the registry's width and this type's.
+            pub fn parse_with(s: &str, registry: &FlagRegistry) -> Result<Self, ParseFlagsError> {
+                let width = registry.width().min($bits);
+                let bits = Parser { registry, width }.expression(s)?;
+                Ok($flags(bits as $int))
+            }
+        }
+    };
+}
+
+impl_parse_with!(BitFlags8, u8, 8);
+impl_parse_with!(BitFlags16, u16, 16);
+impl_parse_with!(BitFlags32, u32, 32);
+impl_parse_with!(BitFlags64, u64, 64);
+impl_parse_with!(BitFlags128, u128, 128);
+
+struct Parser<'a> {
+    registry: &'a FlagRegistry,
+    width:    u32,
+}
+
+impl Parser<'_> {
+    fn expression(&self, s: &str) -> Result<u128, ParseFlagsError> {
+        let start = s.len() - s.trim_start().len();
+        let trimmed = s.trim();
+
+        let (list, offset) = match trimmed.strip_prefix('{') {
+            Some(inner) => match inner.strip_suffix('}') {
+                Some(inner) if inner.trim().is_empty() => return Ok(0),
+                Some(inner) => (inner, start + 1),
+                None => return Err(self.error(start, ParseFlagsErrorKind::UnclosedBrace)),
+            },
+            None => (trimmed, start),
+        };
+
+        let mut bits = 0;
+        let mut term_start = 0;
+        for (i, c) in list.char_indices().chain(Some((list.len(), ','))) {
+            if c == '|' || c == ',' {
+                bits |= self.term(&list[term_start..i], offset + term_start)?;
+                term_start = i + 1;
+            }
+        }
+
+        Ok(bits)
+    }
+    fn term(&self, text: &str, position: usize) -> Result<u128, ParseFlagsError> {
+        let position = position + text.len() - text.trim_start().len();
+        let text = text.trim();
+
+        if let Some(negated) = text.strip_prefix('!') {
+            let known = self.registry.defined_bits() | self.registry.deprecated_bits();
+            return Ok(known & self.mask() & !self.term(negated, position + 1)?);
+        }
+
+        match text.chars().next() {
+            None => Err(self.error(position, ParseFlagsErrorKind::MissingTerm)),
+            Some(c) if c.is_ascii_digit() => self.number(text, position),
+            Some(_) => self.name(text, position),
+        }
+    }
+    fn number(&self, text: &str, position: usize) -> Result<u128, ParseFlagsError> {
+        let literal = |digits: &str, radix: u32| {
+            let bits =
+                u128::from_str_radix(digits, radix).map_err(|_| self.invalid(text, position))?;
+            if bits & !self.mask() != 0 {
+                let kind = ParseFlagsErrorKind::BitsOutOfRange { bits, width: self.width };
+                return Err(self.error(position, kind));
+            }
+            Ok(bits)
+        };
+
+        if let Some(hex) = text.strip_prefix("0x") {
+            literal(hex, 16)
+        } else if let Some(bin) = text.strip_prefix("0b") {
+            literal(bin, 2)
+        } else if let Some((start, end)) = text.split_once("..") {
+            let (end, inclusive) = match end.strip_prefix('=') {
+                Some(end) => (end, true),
+                None => (end, false),
+            };
+            let parse = |digits: &str| {
+                digits
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| self.invalid(text, position))
+            };
+            let (start, end) = (parse(start)?, parse(end)?);
+
+            let stop = if inclusive { end.saturating_add(1) } else { end };
+            if start > stop {
+                let kind = ParseFlagsErrorKind::InvalidRange { start, end };
+                return Err(self.error(position, kind));
+            }
+            if stop > self.width as usize {
+                let kind =
+                    ParseFlagsErrorKind::IndexOutOfRange { index: stop - 1, width: self.width };
+                return Err(self.error(position, kind));
+            }
+            Ok(low_mask(stop) & !low_mask(start))
+        } else {
+            Ok(1 << self.index(text, position)?)
+        }
+    }
+    fn index(&self, text: &str, position: usize) -> Result<usize, ParseFlagsError> {
+        let index = text.parse::<usize>().map_err(|_| self.invalid(text, position))?;
+        if index >= self.width as usize {
+            let kind = ParseFlagsErrorKind::IndexOutOfRange { index, width: self.width };
+            return Err(self.error(position, kind));
+        }
+        Ok(index)
+    }
+    fn name(&self, name: &str, position: usize) -> Result<u128, ParseFlagsError> {
+        let bits = match (self.registry.flag(name), self.registry.group(name)) {
+            (Some(flag), _) => 1 << flag.index(),
+            (None, Some(group)) => group.bits(),
+            (None, None) => {
+                let kind = ParseFlagsErrorKind::UnknownName {
+                    name:       name.to_string(),
+                    suggestion: self.suggest(name),
+                };
+                return Err(self.error(position, kind));
+            }
+        };
+
+        if bits & !self.mask() != 0 {
+            let index = 127 - bits.leading_zeros() as usize;
+            let kind = ParseFlagsErrorKind::IndexOutOfRange { index, width: self.width };
+            return Err(self.error(position, kind));
+        }
+        Ok(bits)
+    }
+    /// Returns the known name closest to `name` (ignoring case), if within a third of its length.
+    fn suggest(&self, name: &str) -> Option<String> {
+        let flags = self.registry.flags().iter();
+        let names = flags
+            .flat_map(|flag| {
+                Some(flag.name())
+                    .into_iter()
+                    .chain(flag.aliases().iter().map(String::as_str))
+            })
+            .chain(self.registry.groups().iter().map(|group| group.name()));
+
+        let limit = (name.len() / 3).max(1);
+        names
+            .map(|candidate| (edit_distance(name, candidate), candidate))
+            .filter(|(distance, _)| *distance <= limit)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, candidate)| candidate.to_string())
+    }
+    #[inline]
+    fn mask(&self) -> u128 {
+        low_mask(self.width as usize)
+    }
+    #[inline]
+    fn invalid(&self, text: &str, position: usize) -> ParseFlagsError {
+        self.error(position, ParseFlagsErrorKind::InvalidNumber { text: text.to_string() })
+    }
+    #[inline]
+    fn error(&self, position: usize, kind: ParseFlagsErrorKind) -> ParseFlagsError {
+        ParseFlagsError { position, kind }
+    }
+}
+
+/// Returns the bits below index `n`.
+#[inline]
+fn low_mask(n: usize) -> u128 {
+    if n >= 128 {
+        u128::MAX
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// The Levenshtein distance between `a` and `b`, ignoring ASCII case.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<u8> = b.bytes().map(|c| c.to_ascii_lowercase()).collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.bytes().map(|c| c.to_ascii_lowercase()).enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = diagonal + (ca != cb) as usize;
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
@@ -0,0 +1,224 @@
+//! Arbitrary bit permutations, executed as a precomputed Beneš (butterfly) network.
+//!
+//! A permutation of `n = 2^k` bits is routed once, on construction, into `2k - 1` stages. Each
+//! stage is a single delta swap (a mask and a shift), so applying any permutation costs `2k - 1`
+//! delta swaps.
+
+use crate::{BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8, BitFlagsError};
+
+/// A permutation of the `BITS` bits of a `BitFlagsN`, moving bit `i` to bit `targets[i]`.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct BitPermutation<const BITS: usize> {
+    targets: [u8; BITS],
+    /// The input-side stage masks, by depth (shift `BITS >> (depth + 1)`).
+    first:   [u128; 7],
+    /// The output-side stage masks, by depth (the last depth is unused).
+    last:    [u128; 7],
+}
+
+impl<const BITS: usize> BitPermutation<BITS> {
+    /// Returns the target of every bit: bit `i` moves to bit `targets()[i]`.
+    #[inline]
+    pub fn targets(&self) -> &[u8; BITS] {
+        &self.targets
+    }
+    /// Returns the bit that bit `i` moves to.
+    ///
+    /// __Panics__ if `i >= BITS`.
+    #[inline]
+    pub fn target(&self, i: usize) -> usize {
+        self.targets[i] as usize
+    }
+    /// Returns the permutation that undoes `self`.
+    pub fn inverse(&self) -> Self {
+        let mut targets = [0; BITS];
+        for (i, &target) in self.targets.iter().enumerate() {
+            targets[target as usize] = i as u8;
+        }
+        Self::route(targets)
+    }
+    /// Returns the permutation applying `self`, then `other`.
+    pub fn compose(&self, other: &Self) -> Self {
+        let mut targets = [0; BITS];
+        for (target, &mid) in targets.iter_mut().zip(self.targets.iter()) {
+            *target = other.targets[mid as usize];
+        }
+        Self::route(targets)
+    }
+    /// Returns `true` if every bit stays where it is.
+    pub fn is_identity(&self) -> bool {
+        self.targets
+            .iter()
+            .enumerate()
+            .all(|(i, &target)| i == target as usize)
+    }
+    /// Checks that `targets` is a permutation of `0..BITS`.
+    fn validate(targets: &[u8; BITS]) -> Result<(), BitFlagsError> {
+        let mut seen = 0u128;
+        for &target in targets.iter() {
+            let index = target as usize;
+            if index >= BITS {
+                return Err(BitFlagsError::IndexOutOfRange { index, bits: BITS });
+            }
+            if seen & 1 << index != 0 {
+                return Err(BitFlagsError::NotAPermutation { index });
+            }
+            seen |= 1 << index;
+        }
+        Ok(())
+    }
+    /// Routes a (valid) permutation through the Beneš network.
+    fn route(targets: [u8; BITS]) -> Self {
+        let mut permutation = Self { targets, first: [0; 7], last: [0; 7] };
+        route(&targets, 0, 0, &mut permutation.first, &mut permutation.last);
+        permutation
+    }
+    /// Returns the permutation moving bit `i` to `f(i)`, which must be a permutation.
+    fn from_fn(f: impl Fn(usize) -> usize) -> Self {
+        let mut targets = [0; BITS];
+        for (i, target) in targets.iter_mut().enumerate() {
+            *target = f(i) as u8;
+        }
+        Self::route(targets)
+    }
+}
+
+/// Routes the block of `dest.len()` bits at `base`, where the bit at (block) position `x` moves to
+/// (block) position `dest[x]`, using the looping algorithm.
+fn route(dest: &[u8], base: usize, depth: usize, first: &mut [u128; 7], last: &mut [u128; 7]) {
+    let n = dest.len();
+    if n == 2 {
+        if dest[0] == 1 {
+            first[depth] |= 1 << base;
+        }
+        return;
+    }
+    let h = n / 2;
+
+    let mut inverse = [0u8; 128];
+    for (x, &d) in dest.iter().enumerate() {
+        inverse[d as usize] = x as u8;
+    }
+
+    // Each input switch sends one bit through the top (lower) subnetwork, and the other through
+    // the bottom one, as must the two bits bound for each output switch.
+    const UNSET: u8 = 2;
+    let mut bottom = [UNSET; 128];
+    for start in 0..h {
+        let mut x = start;
+        while bottom[x] == UNSET {
+            bottom[x] = 0;
+            bottom[x ^ h] = 1;
+            x = inverse[dest[x ^ h] as usize ^ h] as usize;
+        }
+    }
+
+    let mut top_dest = [0u8; 64];
+    let mut bottom_dest = [0u8; 64];
+    for x in 0..n {
+        let d = dest[x] as usize;
+        if bottom[x] == 1 {
+            bottom_dest[x & (h - 1)] = (d & (h - 1)) as u8;
+            if x < h {
+                first[depth] |= 1 << (base + x);
+            }
+        } else {
+            top_dest[x & (h - 1)] = (d & (h - 1)) as u8;
+            if d >= h {
+                last[depth] |= 1 << (base + (d & (h - 1)));
+            }
+        }
+    }
+
+    route(&top_dest[..h], base, depth + 1, first, last);
+    route(&bottom_dest[..h], base + h, depth + 1, first, last);
+}
+
+/// Swaps the bits of `x` selected by `mask` with those `shift` bits above them.
+macro_rules! delta_swap {
+    ($x:expr, $mask:expr, $shift:expr) => {{
+        let x = $x;
+        let t = ((x >> $shift) ^ x) & $mask;
+        x ^ t ^ (t << $shift)
+    }};
+}
+
+macro_rules! impl_permutation {
+    ($flags:ident, $int:ty, $bits:literal, $depths:literal) => {
+        impl BitPermutation<$bits> {
+            /// Returns the permutation moving bit `i` to bit `targets[i]`.
+            ///
+            #[doc = concat!("__Panics__ if `targets` is not a permutation of `0..", $bits, "`.")]
+            #[inline]
+            pub fn new(targets: [u8; $bits]) -> Self {
+                Self::try_new(targets).unwrap()
+            }
+            /// Returns the permutation moving bit `i` to bit `targets[i]`. Returns `Err` if
+            #[doc = concat!("`targets` is not a permutation of `0..", $bits, "`.")]
+            pub fn try_new(targets: [u8; $bits]) -> Result<Self, BitFlagsError> {
+                Self::validate(&targets)?;
+                Ok(Self::route(targets))
+            }
+            /// Returns the permutation that leaves every bit where it is.
+            pub fn identity() -> Self {
+                Self::from_fn(|i| i)
+            }
+            /// Returns the permutation that reverses the order of the bits.
+            pub fn reverse() -> Self {
+                Self::from_fn(|i| $bits - 1 - i)
+            }
+            /// Returns the permutation that rotates the bits left (towards the highest bit) by `n`.
+            pub fn rotate_left(n: u32) -> Self {
+                Self::from_fn(|i| (i + n as usize) % $bits)
+            }
+            /// Returns the permutation that transposes the bits as a `rows` by
+            #[doc = concat!("`", $bits, " / rows` matrix, stored row by row.")]
+            ///
+            #[doc = concat!("__Panics__ if `rows` is `0` or does not divide ", $bits, ".")]
+            pub fn transpose(rows: usize) -> Self {
+                assert!(rows != 0 && $bits % rows == 0, "rows must divide the number of bits");
+                let cols = $bits / rows;
+                Self::from_fn(|i| (i % cols) * rows + i / cols)
+            }
+            /// Applies the permutation to `flags`.
+            #[inline]
+            pub fn apply(&self, flags: $flags) -> $flags {
+                let mut x = flags.0;
+                for depth in 0..$depths {
+                    x = delta_swap!(x, self.first[depth] as $int, $bits >> (depth + 1));
+                }
+                for depth in (0..$depths - 1).rev() {
+                    x = delta_swap!(x, self.last[depth] as $int, $bits >> (depth + 1));
+                }
+                $flags(x)
+            }
+            /// Applies the permutation to every element of `flags`, in place.
+            #[inline]
+            pub fn apply_slice(&self, flags: &mut [$flags]) {
+                for flags in flags.iter_mut() {
+                    *flags = self.apply(*flags);
+                }
+            }
+        }
+
+        impl Default for BitPermutation<$bits> {
+            /// Returns the identity permutation.
+            fn default() -> Self {
+                Self::identity()
+            }
+        }
+    };
+}
+
+impl_permutation!(BitFlags8, u8, 8, 3);
+impl_permutation!(BitFlags16, u16, 16, 4);
+impl_permutation!(BitFlags32, u32, 32, 5);
+impl_permutation!(BitFlags64, u64, 64, 6);
+impl_permutation!(BitFlags128, u128, 128, 7);
+
+/// Writes the target of every bit.
+impl<const BITS: usize> core::fmt::Debug for BitPermutation<BITS> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("BitPermutation").field(&self.targets).finish()
+    }
+}
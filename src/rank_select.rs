@@ -0,0 +1,318 @@
+//! A succinct rank/select index over a slice of `BitFlags64`.
+//!
+//! Bits are numbered across the whole slice: bit `i` is bit `i % 64` of word `i / 64`.
+//!
+//! The index stores a `u64` count of set bits before every superblock (65,536 bits) and a `u16`
+//! count relative to its superblock before every block (512 bits), for ~3.2% space overhead.
+//! `rank` reads both counts and counts ones in at most 8 words. `select` binary searches the
+//! superblocks, then the (at most 128) blocks within one, then scans at most 8 words.
+
+use crate::BitFlags64;
+use alloc::vec::Vec;
+
+const WORD_BITS: usize = 64;
+const BLOCK_WORDS: usize = 8;
+const BLOCK_BITS: usize = BLOCK_WORDS * WORD_BITS;
+const SUPERBLOCK_BLOCKS: usize = 128;
+const SUPERBLOCK_BITS: usize = SUPERBLOCK_BLOCKS * BLOCK_BITS;
+
+/// Bitflag words with an index for fast rank and select queries across all of them.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RankSelect {
+    words:       Vec<BitFlags64>,
+    superblocks: Vec<u64>,
+    blocks:      Vec<u16>,
+    ones:        usize,
+}
+
+impl RankSelect {
+    /// Builds an index over a copy of `words`.
+    #[inline]
+    pub fn new(words: &[BitFlags64]) -> RankSelect {
+        RankSelect::from_vec(words.to_vec())
+    }
+    /// Builds an index over `words`, taking ownership rather than copying.
+    pub fn from_vec(words: Vec<BitFlags64>) -> RankSelect {
+        let block_count = words.len().div_ceil(BLOCK_WORDS);
+        let mut superblocks = Vec::with_capacity(block_count.div_ceil(SUPERBLOCK_BLOCKS));
+        let mut blocks = Vec::with_capacity(block_count);
+        let mut ones = 0;
+        let mut relative = 0;
+
+        for (b, block) in words.chunks(BLOCK_WORDS).enumerate() {
+            if b % SUPERBLOCK_BLOCKS == 0 {
+                superblocks.push(ones as u64);
+                relative = 0;
+            }
+            blocks.push(relative as u16);
+            let count = block.iter().map(|word| word.0.count_ones() as usize).sum::<usize>();
+            ones += count;
+            relative += count;
+        }
+
+        RankSelect { words, superblocks, blocks, ones }
+    }
+    /// Reassembles an index from its parts without recounting the words, returning `None` unless
+    /// the counts are consistent with the length of `words`: one per block and superblock, each
+    /// superblock (or block) starting at `0`, and never growing by more than the bits in between.
+    ///
+    /// Counts that pass these checks are trusted: wrong (but consistent) counts give wrong answers,
+    /// but never panic.
+    #[cfg(any(feature = "serde-support", feature = "nanoserde-support"))]
+    fn from_parts(
+        words: Vec<BitFlags64>,
+        superblocks: Vec<u64>,
+        blocks: Vec<u16>,
+    ) -> Option<RankSelect> {
+        let block_count = words.len().div_ceil(BLOCK_WORDS);
+        if blocks.len() != block_count
+            || superblocks.len() != block_count.div_ceil(SUPERBLOCK_BLOCKS)
+            || superblocks.first().is_some_and(|&count| count != 0)
+        {
+            return None;
+        }
+        // Consecutive counts must not decrease, nor grow by more than the bits between them.
+        let step_fits =
+            |prev: u64, next: u64, bits: usize| prev <= next && next - prev <= bits as u64;
+        if !superblocks.windows(2).all(|w| step_fits(w[0], w[1], SUPERBLOCK_BITS))
+            || !blocks.chunks(SUPERBLOCK_BLOCKS).all(|chunk| {
+                chunk[0] == 0
+                    && chunk
+                        .windows(2)
+                        .all(|w| step_fits(w[0].into(), w[1].into(), BLOCK_BITS))
+            })
+        {
+            return None;
+        }
+
+        let ones = match blocks.len().checked_sub(1) {
+            Some(last) => {
+                let within = words[last * BLOCK_WORDS..].iter().map(|w| w.0.count_ones() as usize);
+                superblocks[last / SUPERBLOCK_BLOCKS] as usize
+                    + blocks[last] as usize
+                    + within.sum::<usize>()
+            }
+            None => 0,
+        };
+
+        Some(RankSelect { words, superblocks, blocks, ones })
+    }
+    /// Returns the indexed words.
+    #[inline]
+    pub fn words(&self) -> &[BitFlags64] {
+        &self.words
+    }
+    /// Returns the indexed words, discarding the index.
+    #[inline]
+    pub fn into_words(self) -> Vec<BitFlags64> {
+        self.words
+    }
+    /// Returns the total number of bits (`64` per word).
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.words.len() * WORD_BITS
+    }
+    /// Returns `true` if there are no words.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+    /// Returns the total number of set bits.
+    #[inline]
+    pub fn count_ones(&self) -> usize {
+        self.ones
+    }
+    /// Returns the total number of unset bits.
+    #[inline]
+    pub fn count_zeros(&self) -> usize {
+        self.len() - self.ones
+    }
+    /// Returns the value of bit `i`.
+    ///
+    /// __Panics__ if `i >= len()`.
+    #[inline]
+    pub fn bit(&self, i: usize) -> bool {
+        self.words[i / WORD_BITS].bit_at_index(i % WORD_BITS)
+    }
+    /// Returns the number of set bits below bit `i` (i.e. in `0..i`).
+    ///
+    /// __Panics__ if `i > len()`.
+    pub fn rank1(&self, i: usize) -> usize {
+        assert!(i <= self.len(), "rank index {} is out of range for {} bits", i, self.len());
+        if i == self.len() {
+            return self.ones;
+        }
+        let word = i / WORD_BITS;
+        let block = word / BLOCK_WORDS;
+        let before =
+            self.superblocks[block / SUPERBLOCK_BLOCKS] as usize + self.blocks[block] as usize;
+        let within = self.words[block * BLOCK_WORDS..word]
+            .iter()
+            .map(|w| w.0.count_ones() as usize);
+
+        before + within.sum::<usize>() + self.words[word].rank(i % WORD_BITS) as usize
+    }
+    /// Returns the number of unset bits below bit `i` (i.e. in `0..i`).
+    ///
+    /// __Panics__ if `i > len()`.
+    #[inline]
+    pub fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+    /// Returns the index of the `k`-th set bit (counting from `0`), or `None` if fewer than
+    /// `k + 1` bits are set.
+    #[inline]
+    pub fn select1(&self, k: usize) -> Option<usize> {
+        self.select_with(k, true)
+    }
+    /// Returns the index of the `k`-th unset bit (counting from `0`), or `None` if fewer than
+    /// `k + 1` bits are unset.
+    #[inline]
+    pub fn select0(&self, k: usize) -> Option<usize> {
+        self.select_with(k, false)
+    }
+    /// Returns the number of bytes used by the index (excluding the words themselves).
+    #[inline]
+    pub fn index_bytes(&self) -> usize {
+        self.superblocks.len() * core::mem::size_of::<u64>()
+            + self.blocks.len() * core::mem::size_of::<u16>()
+    }
+    /// Finds the `k`-th set (`ones`) or unset (`!ones`) bit.
+    fn select_with(&self, k: usize, ones: bool) -> Option<usize> {
+        let total = if ones { self.ones } else { self.count_zeros() };
+        if k >= total {
+            return None;
+        }
+        // Matching bits before superblock `s`, and before block `b` relative to its superblock.
+        let before_superblock = |s: usize| {
+            let count = self.superblocks[s] as usize;
+            if ones {
+                count
+            } else {
+                s * SUPERBLOCK_BITS - count
+            }
+        };
+        let before_block = |b: usize| {
+            let count = self.blocks[b] as usize;
+            if ones {
+                count
+            } else {
+                (b % SUPERBLOCK_BLOCKS) * BLOCK_BITS - count
+            }
+        };
+
+        let s = partition_point(0, self.superblocks.len(), |s| before_superblock(s) <= k) - 1;
+        let mut remaining = k - before_superblock(s);
+        let first = s * SUPERBLOCK_BLOCKS;
+        let last = self.blocks.len().min(first + SUPERBLOCK_BLOCKS);
+        let b = partition_point(first, last, |b| before_block(b) <= remaining) - 1;
+        remaining -= before_block(b);
+
+        for (w, word) in self.words[b * BLOCK_WORDS..].iter().enumerate().take(BLOCK_WORDS) {
+            let word = if ones { *word } else { !*word };
+            let count = word.0.count_ones() as usize;
+            if remaining < count {
+                return word.select(remaining).map(|i| (b * BLOCK_WORDS + w) * WORD_BITS + i);
+            }
+            remaining -= count;
+        }
+
+        // Only reachable with wrong counts loaded by `from_parts`.
+        None
+    }
+}
+
+impl From<Vec<BitFlags64>> for RankSelect {
+    #[inline]
+    fn from(words: Vec<BitFlags64>) -> Self {
+        RankSelect::from_vec(words)
+    }
+}
+
+impl From<&[BitFlags64]> for RankSelect {
+    #[inline]
+    fn from(words: &[BitFlags64]) -> Self {
+        RankSelect::new(words)
+    }
+}
+
+/// Returns the first index in `lo..hi` for which `pred` is `false` (or `hi` if there is none),
+/// where `pred` is `true` for a prefix of the range.
+fn partition_point<F: Fn(usize) -> bool>(mut lo: usize, mut hi: usize, pred: F) -> usize {
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(mid) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    lo
+}
+
+//  #######   ########   ######   ########  #######
+//  ##    ##  ##        ##        ##        ##    ##
+//  ##    ##  ######     ######   ######    #######
+//  ##    ##  ##              ##  ##        ##   ##
+//  #######   ########  #######   ########  ##    ##
+//
+// The words are saved together with their index, which is reused when loaded (after checking that
+// its shape and counts are consistent, but without recounting the words).
+
+#[cfg(feature = "serde-support")]
+mod impl_serde {
+    use super::RankSelect;
+    use crate::BitFlags64;
+    use alloc::vec::Vec;
+    use serde::de::Error;
+    use serde::{Deserialize, Serialize};
+
+    impl<'de> Deserialize<'de> for RankSelect {
+        fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<RankSelect, D::Error> {
+            let (words, superblocks, blocks) =
+                <(Vec<BitFlags64>, Vec<u64>, Vec<u16>)>::deserialize(d)?;
+            RankSelect::from_parts(words, superblocks, blocks)
+                .ok_or_else(|| D::Error::custom("rank/select index is inconsistent with its words"))
+        }
+    }
+
+    impl Serialize for RankSelect {
+        fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            (&self.words, &self.superblocks, &self.blocks).serialize(s)
+        }
+    }
+}
+
+#[cfg(feature = "nanoserde-support")]
+mod impl_nanoserde {
+    extern crate std;
+    use super::RankSelect;
+    use crate::BitFlags64;
+    use nanoserde::{DeBin, DeBinErr, SerBin};
+    use std::prelude::v1::*;
+
+    /// An inconsistent index is rejected as if the input were too short, as `DeBinErr` carries no
+    /// message.
+    impl DeBin for RankSelect {
+        fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr> {
+            let start = *offset;
+            let (words, superblocks, blocks) =
+                <(Vec<BitFlags64>, Vec<u64>, Vec<u16>)>::de_bin(offset, bytes)?;
+
+            RankSelect::from_parts(words, superblocks, blocks).ok_or_else(|| {
+                let l = *offset - start;
+                *offset = start;
+                DeBinErr { o: start, l, s: bytes.len() }
+            })
+        }
+    }
+
+    impl SerBin for RankSelect {
+        fn ser_bin(&self, output: &mut Vec<u8>) {
+            self.words.ser_bin(output);
+            self.superblocks.ser_bin(output);
+            self.blocks.ser_bin(output);
+        }
+    }
+}
@@ -0,0 +1,463 @@
+//! Named flag registries, loaded from definition files.
+//!
+//! A [`SchemaDefinition`] is the data file designers edit (e.g. in RON or JSON, read with `serde`).
+//! [`FlagRegistry::new`] validates it, assigning an index to every flag without an explicit one,
+//! and reports each problem with its location in the definition, e.g. `flags[3].index`.
+//!
+//! ```ron
+//! (
+//!     width: 16,
+//!     reserved: [15],
+//!     flags: [
+//!         (name: "walk", description: Some("Can walk")),
+//!         (name: "swim", index: Some(4), aliases: ["dive"]),
+//!         (name: "fly", deprecated: true),
+//!     ],
+//!     groups: [(name: "movement", flags: ["walk", "swim"])],
+//! )
+//! ```
+
+use crate::{
+    BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8, BitFlagsError, FlagSchema,
+};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A flag definition file: the target width, reserved indexes, flags, and groups of flags.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde-support", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchemaDefinition {
+    /// The width of the target `BitFlagsN`: `8`, `16`, `32`, `64`, or `128`.
+    pub width:    u32,
+    /// Indexes that no flag may use.
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub reserved: Vec<u32>,
+    /// The flags, in definition order.
+    pub flags:    Vec<FlagDefinition>,
+    /// Named groups of flags.
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub groups:   Vec<GroupDefinition>,
+}
+
+/// A single flag in a [`SchemaDefinition`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde-support", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlagDefinition {
+    /// The flag's name: ASCII letters, digits, and `_`, not starting with a digit.
+    pub name:        String,
+    /// The flag's bit index. If `None`, the lowest index not otherwise taken is assigned.
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub index:       Option<u32>,
+    /// A description for tools and documentation.
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub description: Option<String>,
+    /// Former names of the flag, still accepted but deprecated.
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub aliases:     Vec<String>,
+    /// Whether the flag itself is deprecated.
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub deprecated:  bool,
+}
+
+/// A named group of flags in a [`SchemaDefinition`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde-support", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupDefinition {
+    /// The group's name, sharing the flags' namespace.
+    pub name:        String,
+    /// The names (or aliases) of the flags in the group.
+    pub flags:       Vec<String>,
+    /// A description for tools and documentation.
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    pub description: Option<String>,
+}
+
+/// A problem found while validating a [`SchemaDefinition`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    /// Where the problem is in the definition, e.g. `flags[3].index`.
+    pub path: String,
+    /// What the problem is.
+    pub kind: SchemaErrorKind,
+}
+
+/// The kind of a [`SchemaError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaErrorKind {
+    /// The width is not `8`, `16`, `32`, `64`, or `128`.
+    UnsupportedWidth { width: u32 },
+    /// A name is empty or contains characters other than ASCII letters, digits, and `_`.
+    InvalidName { name: String },
+    /// A name (of a flag, alias, or group) was already used at `first`.
+    DuplicateName { name: String, first: String },
+    /// An index was already taken at `first`.
+    IndexCollision { index: u32, first: String },
+    /// An index does not fit in the width.
+    IndexOverflow { index: u32, width: u32 },
+    /// A flag without an explicit index was left with no free index to take.
+    NoFreeIndex { width: u32 },
+    /// A group refers to a flag that is not defined.
+    UnknownFlag { name: String },
+}
+
+impl core::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: ", self.path)?;
+        match &self.kind {
+            SchemaErrorKind::UnsupportedWidth { width } => {
+                write!(f, "width {} is not one of 8, 16, 32, 64, or 128", width)
+            }
+            SchemaErrorKind::InvalidName { name } => write!(f, "invalid name {:?}", name),
+            SchemaErrorKind::DuplicateName { name, first } => {
+                write!(f, "duplicate name {:?} (first used at {})", name, first)
+            }
+            SchemaErrorKind::IndexCollision { index, first } => {
+                write!(f, "index {} is already taken (at {})", index, first)
+            }
+            SchemaErrorKind::IndexOverflow { index, width } => {
+                write!(f, "index {} does not fit in {} bits", index, width)
+            }
+            SchemaErrorKind::NoFreeIndex { width } => {
+                write!(f, "no free index is left in {} bits", width)
+            }
+            SchemaErrorKind::UnknownFlag { name } => write!(f, "unknown flag {:?}", name),
+        }
+    }
+}
+
+impl core::error::Error for SchemaError {}
+
+/// A validated flag in a [`FlagRegistry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredFlag {
+    name:        String,
+    index:       usize,
+    description: Option<String>,
+    aliases:     Vec<String>,
+    deprecated:  bool,
+}
+
+impl RegisteredFlag {
+    /// Returns the flag's name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Returns the flag's bit index.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+    /// Returns the flag's description, if any.
+    #[inline]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+    /// Returns the flag's deprecated aliases.
+    #[inline]
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+    /// Returns `true` if the flag itself is deprecated.
+    #[inline]
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated
+    }
+}
+
+/// A validated group in a [`FlagRegistry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredGroup {
+    name:        String,
+    bits:        u128,
+    description: Option<String>,
+}
+
+impl RegisteredGroup {
+    /// Returns the group's name.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Returns the bits of the group's flags.
+    #[inline]
+    pub fn bits(&self) -> u128 {
+        self.bits
+    }
+    /// Returns the group's description, if any.
+    #[inline]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+/// What a name refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Entry {
+    Flag(usize),
+    Alias(usize),
+    Group(usize),
+}
+
+/// A validated set of named flags (sorted by index) and groups, for a single width.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagRegistry {
+    width:    u32,
+    reserved: u128,
+    flags:    Vec<RegisteredFlag>,
+    groups:   Vec<RegisteredGroup>,
+    names:    BTreeMap<String, Entry>,
+}
+
+impl FlagRegistry {
+    /// Validates `definition` and builds a registry from it. Returns every problem found if it
+    /// is invalid.
+    pub fn new<'a>(definition: &'a SchemaDefinition) -> Result<FlagRegistry, Vec<SchemaError>> {
+        let width = definition.width;
+        if !matches!(width, 8 | 16 | 32 | 64 | 128) {
+            let kind = SchemaErrorKind::UnsupportedWidth { width };
+            return Err(alloc::vec![SchemaError { path: String::from("width"), kind }]);
+        }
+
+        let mut errors = Vec::new();
+        // The location that took each index, and that first used each name.
+        let mut taken: Vec<Option<String>> = alloc::vec![None; width as usize];
+        let mut first_use: BTreeMap<&str, String> = BTreeMap::new();
+        let mut take = |index: u32, path: String, errors: &mut Vec<SchemaError>| {
+            let kind = match taken.get_mut(index as usize) {
+                None => SchemaErrorKind::IndexOverflow { index, width },
+                Some(Some(first)) => {
+                    SchemaErrorKind::IndexCollision { index, first: first.clone() }
+                }
+                Some(slot) => {
+                    *slot = Some(path);
+                    return;
+                }
+            };
+            errors.push(SchemaError { path, kind });
+        };
+
+        for (i, &index) in definition.reserved.iter().enumerate() {
+            take(index, format!("reserved[{}]", i), &mut errors);
+        }
+        for (i, flag) in definition.flags.iter().enumerate() {
+            if let Some(index) = flag.index {
+                take(index, format!("flags[{}].index", i), &mut errors);
+            }
+        }
+
+        let mut indexes = Vec::with_capacity(definition.flags.len());
+        let mut free = 0;
+        for (i, flag) in definition.flags.iter().enumerate() {
+            let index = match flag.index {
+                Some(index) if index < width => index as usize,
+                Some(_) => continue,
+                None => {
+                    while free < taken.len() && taken[free].is_some() {
+                        free += 1;
+                    }
+                    if free == taken.len() {
+                        let kind = SchemaErrorKind::NoFreeIndex { width };
+                        errors.push(SchemaError { path: format!("flags[{}]", i), kind });
+                        continue;
+                    }
+                    taken[free] = Some(format!("flags[{}]", i));
+                    free
+                }
+            };
+            indexes.push((index, i));
+        }
+
+        let mut check_name = |name: &'a str, path: String, errors: &mut Vec<SchemaError>| {
+            let mut chars = name.chars();
+            let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                let kind = SchemaErrorKind::InvalidName { name: String::from(name) };
+                errors.push(SchemaError { path, kind });
+            } else if let Some(first) = first_use.get(name) {
+                let kind = SchemaErrorKind::DuplicateName {
+                    name:  String::from(name),
+                    first: first.clone(),
+                };
+                errors.push(SchemaError { path, kind });
+            } else {
+                first_use.insert(name, path);
+            }
+        };
+        for (i, flag) in definition.flags.iter().enumerate() {
+            check_name(&flag.name, format!("flags[{}].name", i), &mut errors);
+            for (j, alias) in flag.aliases.iter().enumerate() {
+                check_name(alias, format!("flags[{}].aliases[{}]", i, j), &mut errors);
+            }
+        }
+        for (g, group) in definition.groups.iter().enumerate() {
+            check_name(&group.name, format!("groups[{}].name", g), &mut errors);
+        }
+
+        indexes.sort_unstable();
+        let mut names = BTreeMap::new();
+        let mut flags = Vec::with_capacity(indexes.len());
+        for &(index, i) in &indexes {
+            let flag = &definition.flags[i];
+            names.entry(flag.name.clone()).or_insert(Entry::Flag(flags.len()));
+            for alias in &flag.aliases {
+                names.entry(alias.clone()).or_insert(Entry::Alias(flags.len()));
+            }
+            flags.push(RegisteredFlag {
+                name: flag.name.clone(),
+                index,
+                description: flag.description.clone(),
+                aliases: flag.aliases.clone(),
+                deprecated: flag.deprecated,
+            });
+        }
+
+        let mut groups = Vec::with_capacity(definition.groups.len());
+        for (g, group) in definition.groups.iter().enumerate() {
+            let mut bits = 0;
+            for (m, member) in group.flags.iter().enumerate() {
+                match names.get(member) {
+                    Some(Entry::Flag(k) | Entry::Alias(k)) => bits |= 1 << flags[*k].index,
+                    _ => {
+                        let kind = SchemaErrorKind::UnknownFlag { name: member.clone() };
+                        errors.push(SchemaError {
+                            path: format!("groups[{}].flags[{}]", g, m),
+                            kind,
+                        });
+                    }
+                }
+            }
+            names.entry(group.name.clone()).or_insert(Entry::Group(groups.len()));
+            groups.push(RegisteredGroup {
+                name: group.name.clone(),
+                bits,
+                description: group.description.clone(),
+            });
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let reserved = definition.reserved.iter().fold(0, |bits, &index| bits | 1 << index);
+        Ok(FlagRegistry {
+            width,
+            reserved,
+            flags,
+            groups,
+            names,
+        })
+    }
+    /// Returns the width of the target `BitFlagsN`.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    /// Returns the flags, sorted by index.
+    #[inline]
+    pub fn flags(&self) -> &[RegisteredFlag] {
+        &self.flags
+    }
+    /// Returns the groups, in definition order.
+    #[inline]
+    pub fn groups(&self) -> &[RegisteredGroup] {
+        &self.groups
+    }
+    /// Returns the flag named (or aliased) `name`.
+    pub fn flag(&self, name: &str) -> Option<&RegisteredFlag> {
+        match self.names.get(name)? {
+            Entry::Flag(k) | Entry::Alias(k) => Some(&self.flags[*k]),
+            Entry::Group(_) => None,
+        }
+    }
+    /// Returns the flag at bit `index`.
+    pub fn flag_at(&self, index: usize) -> Option<&RegisteredFlag> {
+        self.flags
+            .binary_search_by_key(&index, |flag| flag.index)
+            .ok()
+            .map(|k| &self.flags[k])
+    }
+    /// Returns the group named `name`.
+    pub fn group(&self, name: &str) -> Option<&RegisteredGroup> {
+        match self.names.get(name)? {
+            Entry::Group(k) => Some(&self.groups[*k]),
+            _ => None,
+        }
+    }
+    /// Returns the bit index of the flag named (or aliased) `name`.
+    #[inline]
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.flag(name).map(|flag| flag.index)
+    }
+    /// Returns the name of the flag at bit `index`.
+    #[inline]
+    pub fn name_of(&self, index: usize) -> Option<&str> {
+        self.flag_at(index).map(|flag| flag.name())
+    }
+    /// Returns `true` if `name` is a deprecated alias of a flag.
+    #[inline]
+    pub fn is_alias(&self, name: &str) -> bool {
+        matches!(self.names.get(name), Some(Entry::Alias(_)))
+    }
+    /// Returns the bits of all flags that are not deprecated.
+    pub fn defined_bits(&self) -> u128 {
+        self.flags
+            .iter()
+            .filter(|flag| !flag.deprecated)
+            .fold(0, |bits, flag| bits | 1 << flag.index)
+    }
+    /// Returns the bits of all deprecated flags.
+    pub fn deprecated_bits(&self) -> u128 {
+        self.flags
+            .iter()
+            .filter(|flag| flag.deprecated)
+            .fold(0, |bits, flag| bits | 1 << flag.index)
+    }
+    /// Returns the reserved bits.
+    #[inline]
+    pub fn reserved_bits(&self) -> u128 {
+        self.reserved
+    }
+}
+
+/// Loads and validates a [`SchemaDefinition`], reporting the first problem found as a `serde`
+/// error.
+#[cfg(feature = "serde-support")]
+impl<'de> serde::Deserialize<'de> for FlagRegistry {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<FlagRegistry, D::Error> {
+        use serde::de::Error;
+
+        let definition = SchemaDefinition::deserialize(d)?;
+        FlagRegistry::new(&definition).map_err(|errors| D::Error::custom(&errors[0]))
+    }
+}
+
+macro_rules! impl_registry_schema {
+    ($flags:ident, $int:ty, $bits:literal) => {
+        /// Returns `Err` if the registry has flags or reserved bits beyond the width.
+        impl TryFrom<&FlagRegistry> for FlagSchema<$flags> {
+            type Error = BitFlagsError;
+
+            fn try_from(registry: &FlagRegistry) -> Result<Self, Self::Error> {
+                let all =
+                    registry.defined_bits() | registry.deprecated_bits() | registry.reserved_bits();
+                if all >> ($bits - 1) > 1 {
+                    let index = 127 - all.leading_zeros() as usize;
+                    return Err(BitFlagsError::IndexOutOfRange { index, bits: $bits });
+                }
+
+                Ok(FlagSchema::<$flags>::new($flags(registry.defined_bits() as $int))
+                    .with_deprecated($flags(registry.deprecated_bits() as $int))
+                    .with_reserved($flags(registry.reserved_bits() as $int)))
+            }
+        }
+    };
+}
+
+impl_registry_schema!(BitFlags8, u8, 8);
+impl_registry_schema!(BitFlags16, u16, 16);
+impl_registry_schema!(BitFlags32, u32, 32);
+impl_registry_schema!(BitFlags64, u64, 64);
+impl_registry_schema!(BitFlags128, u128, 128);
@@ -0,0 +1,357 @@
+//! Remapping bit indexes between versions of a flag list, e.g. to migrate saved flags after a flag
+//! is inserted, removed, renamed, or merged into another.
+//!
+//! A [`BitRemap`] maps each source index (`0..128`) to a target index, or drops it. Several
+//! sources may map to the same target (merged bits). Remaps can be inverted, chained across
+//! versions, and serialized (as a list of `(from, to)` pairs, omitting dropped bits).
+
+use crate::{BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8, TypedFlags};
+
+/// Marks a dropped source index.
+const DROPPED: u8 = u8::MAX;
+
+/// Bitflags that a [`BitRemap`] can be applied to.
+pub trait Remappable: Copy {
+    /// The number of bits.
+    const BITS: usize;
+
+    /// Returns the bits, widened to a `u128`.
+    fn to_bits(self) -> u128;
+    /// Returns flags with the low `BITS` bits of `bits`.
+    fn from_bits(bits: u128) -> Self;
+}
+
+macro_rules! impl_remappable {
+    ($flags:ident, $int:ty, $bits:literal) => {
+        impl Remappable for $flags {
+            const BITS: usize = $bits;
+
+            #[inline]
+            fn to_bits(self) -> u128 {
+                self.0 as u128
+            }
+            #[inline]
+            fn from_bits(bits: u128) -> Self {
+                $flags(bits as $int)
+            }
+        }
+    };
+}
+
+impl_remappable!(BitFlags8, u8, 8);
+impl_remappable!(BitFlags16, u16, 16);
+impl_remappable!(BitFlags32, u32, 32);
+impl_remappable!(BitFlags64, u64, 64);
+impl_remappable!(BitFlags128, u128, 128);
+
+impl<Tag, F: Remappable> Remappable for TypedFlags<Tag, F> {
+    const BITS: usize = F::BITS;
+
+    #[inline]
+    fn to_bits(self) -> u128 {
+        self.get().to_bits()
+    }
+    #[inline]
+    fn from_bits(bits: u128) -> Self {
+        TypedFlags::from_flags(F::from_bits(bits))
+    }
+}
+
+/// The result of applying a [`BitRemap`]: the remapped flags, and the source bits that were lost.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Remapped<F> {
+    /// The remapped flags.
+    pub flags: F,
+    /// The set source bits that were dropped, or whose target index doesn't fit in `F`.
+    pub lost:  F,
+}
+
+/// A mapping from source bit indexes to target bit indexes, for migrating flags between versions.
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct BitRemap {
+    targets: [u8; 128],
+}
+
+impl BitRemap {
+    /// Returns a remap that keeps every bit where it is.
+    pub const fn identity() -> Self {
+        let mut targets = [0; 128];
+        let mut i = 0;
+        while i < 128 {
+            targets[i] = i as u8;
+            i += 1;
+        }
+        Self { targets }
+    }
+    /// Returns a remap that drops every bit.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self { targets: [DROPPED; 128] }
+    }
+    /// Returns a remap from the flags named in `old` (by index) to the flags of the same name in
+    /// `new`. Flags not in `new`, and indexes past the end of `old`, are dropped.
+    ///
+    /// __Panics__ if either list has more than 128 names.
+    pub fn from_names<S: AsRef<str>>(old: &[S], new: &[S]) -> Self {
+        assert!(old.len() <= 128 && new.len() <= 128, "name lists are limited to 128 names");
+
+        let mut remap = Self::empty();
+        for (from, name) in old.iter().enumerate() {
+            if let Some(to) = new.iter().position(|n| n.as_ref() == name.as_ref()) {
+                remap.targets[from] = to as u8;
+            }
+        }
+        remap
+    }
+    /// Returns a remap from the flags of `old` to the flags of `new` with the same name, or with
+    /// the old name as an alias (a renamed flag). Flags not in `new`, and all other indexes, are
+    /// dropped.
+    #[cfg(feature = "alloc")]
+    pub fn between(old: &crate::FlagRegistry, new: &crate::FlagRegistry) -> Self {
+        let mut remap = Self::empty();
+        for flag in old.flags() {
+            if let Some(to) = new.index_of(flag.name()) {
+                remap.targets[flag.index()] = to as u8;
+            }
+        }
+        remap
+    }
+    /// Maps source index `from` to target index `to`, replacing any previous mapping. Mapping a
+    /// second source to the same target merges them.
+    ///
+    /// __Panics__ if `from > 127` or `to > 127`.
+    #[inline]
+    pub fn with(mut self, from: usize, to: usize) -> Self {
+        assert!(from < 128 && to < 128, "BitRemap indexes are from 0 to 127");
+        self.targets[from] = to as u8;
+        self
+    }
+    /// Drops source index `from`.
+    ///
+    /// __Panics__ if `from > 127`.
+    #[inline]
+    pub fn without(mut self, from: usize) -> Self {
+        assert!(from < 128, "BitRemap indexes are from 0 to 127");
+        self.targets[from] = DROPPED;
+        self
+    }
+    /// Returns the target index of source index `from`, or `None` if it is dropped (or past 127).
+    #[inline]
+    pub fn target(&self, from: usize) -> Option<usize> {
+        match self.targets.get(from) {
+            Some(&to) if to != DROPPED => Some(to as usize),
+            _ => None,
+        }
+    }
+    /// Returns the source bits that are dropped.
+    pub fn dropped(&self) -> u128 {
+        (0..128)
+            .filter(|&from| self.targets[from] == DROPPED)
+            .fold(0, |bits, from| bits | 1 << from)
+    }
+    /// Returns the target bits that two or more source bits map to.
+    pub fn merged(&self) -> u128 {
+        let mut seen = 0u128;
+        let mut merged = 0;
+        for &to in self.targets.iter().filter(|&&to| to != DROPPED) {
+            merged |= seen & 1 << to;
+            seen |= 1 << to;
+        }
+        merged
+    }
+    /// Returns the remap from targets back to sources. Bits merged into one target map back to the
+    /// lowest of their sources, and target bits that no source maps to are dropped.
+    pub fn inverse(&self) -> Self {
+        let mut inverse = Self::empty();
+        for (from, &to) in self.targets.iter().enumerate().rev() {
+            if to != DROPPED {
+                inverse.targets[to as usize] = from as u8;
+            }
+        }
+        inverse
+    }
+    /// Returns the remap applying `self`, then `next`, e.g. to migrate across several versions.
+    pub fn then(&self, next: &BitRemap) -> Self {
+        let mut chained = Self::empty();
+        for (from, &to) in self.targets.iter().enumerate() {
+            if to != DROPPED {
+                chained.targets[from] = next.targets[to as usize];
+            }
+        }
+        chained
+    }
+    /// Remaps `flags`, returning the remapped flags and the source bits that were lost.
+    pub fn apply<F: Remappable>(&self, flags: F) -> Remapped<F> {
+        let mut bits = flags.to_bits();
+        let mut remapped = 0;
+        let mut lost = 0;
+        while bits != 0 {
+            let from = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+
+            match self.targets[from] {
+                to if (to as usize) < F::BITS => remapped |= 1 << to,
+                _ => lost |= 1 << from,
+            }
+        }
+
+        Remapped {
+            flags: F::from_bits(remapped),
+            lost:  F::from_bits(lost),
+        }
+    }
+    /// Remaps every element of `flags` in place, returning the union of the source bits lost.
+    pub fn apply_slice<F: Remappable>(&self, flags: &mut [F]) -> F {
+        let lost = flags.iter_mut().fold(0, |lost, flags| {
+            let remapped = self.apply(*flags);
+            *flags = remapped.flags;
+            lost | remapped.lost.to_bits()
+        });
+        F::from_bits(lost)
+    }
+    /// Iterates over the `(from, to)` pairs of all source indexes that are not dropped.
+    fn pairs(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        (0..128u8)
+            .zip(self.targets.iter().copied())
+            .filter(|&(_, to)| to != DROPPED)
+    }
+    /// Returns a remap from `(from, to)` pairs, or `None` if an index is past 127.
+    #[cfg(feature = "nanoserde-support")]
+    fn from_pairs(pairs: impl IntoIterator<Item = (u8, u8)>) -> Option<Self> {
+        let mut remap = Self::empty();
+        for (from, to) in pairs {
+            if from >= 128 || to >= 128 {
+                return None;
+            }
+            remap.targets[from as usize] = to;
+        }
+        Some(remap)
+    }
+}
+
+impl Default for BitRemap {
+    /// Returns the identity remap.
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Writes the `(from, to)` pairs of all source indexes that are not dropped.
+impl core::fmt::Debug for BitRemap {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.pairs()).finish()
+    }
+}
+
+//  #######   ########   ######   ########  #######
+//  ##    ##  ##        ##        ##        ##    ##
+//  ##    ##  ######     ######   ######    #######
+//  ##    ##  ##              ##  ##        ##   ##
+//  #######   ########  #######   ########  ##    ##
+
+#[cfg(feature = "serde-support")]
+mod impl_serde {
+    use super::BitRemap;
+    use serde::de::{Error, SeqAccess, Visitor};
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Serialize};
+
+    impl Serialize for BitRemap {
+        fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            let mut seq = s.serialize_seq(Some(self.pairs().count()))?;
+            for pair in self.pairs() {
+                seq.serialize_element(&pair)?;
+            }
+            seq.end()
+        }
+    }
+
+    struct PairsVisitor;
+
+    impl<'de> Visitor<'de> for PairsVisitor {
+        type Value = BitRemap;
+
+        fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            write!(f, "a sequence of (from, to) bit index pairs, each from 0 to 127")
+        }
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<BitRemap, A::Error> {
+            let mut remap = BitRemap::empty();
+            while let Some((from, to)) = seq.next_element::<(u8, u8)>()? {
+                if from >= 128 || to >= 128 {
+                    return Err(A::Error::custom("bit index out of range: must be 0 to 127"));
+                }
+                remap.targets[from as usize] = to;
+            }
+            Ok(remap)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for BitRemap {
+        fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<BitRemap, D::Error> {
+            d.deserialize_seq(PairsVisitor)
+        }
+    }
+}
+
+#[cfg(feature = "nanoserde-support")]
+mod impl_nanoserde {
+    extern crate std;
+    use super::BitRemap;
+    use nanoserde::{
+        DeBin, DeBinErr, DeJson, DeJsonErr, DeJsonState, DeRon, DeRonErr, DeRonState, SerBin,
+        SerJson, SerJsonState, SerRon, SerRonState,
+    };
+    use std::prelude::v1::*;
+
+    impl SerBin for BitRemap {
+        fn ser_bin(&self, output: &mut Vec<u8>) {
+            self.pairs().collect::<Vec<_>>().ser_bin(output)
+        }
+    }
+
+    /// Indexes past 127 are rejected as if the input were too short, as `DeBinErr` carries no
+    /// message.
+    impl DeBin for BitRemap {
+        fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr> {
+            let start = *offset;
+            let pairs = Vec::<(u8, u8)>::de_bin(offset, bytes)?;
+
+            BitRemap::from_pairs(pairs).ok_or_else(|| {
+                let l = *offset - start;
+                *offset = start;
+                DeBinErr { o: start, l, s: bytes.len() }
+            })
+        }
+    }
+
+    impl SerJson for BitRemap {
+        fn ser_json(&self, indent_level: usize, state: &mut SerJsonState) {
+            self.pairs().collect::<Vec<_>>().ser_json(indent_level, state)
+        }
+    }
+
+    impl DeJson for BitRemap {
+        fn de_json(
+            state: &mut DeJsonState,
+            input: &mut core::str::Chars,
+        ) -> Result<Self, DeJsonErr> {
+            let pairs = Vec::<(u8, u8)>::de_json(state, input)?;
+
+            BitRemap::from_pairs(pairs).ok_or_else(|| state.err_range("index (must be 0 to 127)"))
+        }
+    }
+
+    impl SerRon for BitRemap {
+        fn ser_ron(&self, indent_level: usize, state: &mut SerRonState) {
+            self.pairs().collect::<Vec<_>>().ser_ron(indent_level, state)
+        }
+    }
+
+    impl DeRon for BitRemap {
+        fn de_ron(state: &mut DeRonState, input: &mut core::str::Chars) -> Result<Self, DeRonErr> {
+            let pairs = Vec::<(u8, u8)>::de_ron(state, input)?;
+
+            BitRemap::from_pairs(pairs).ok_or_else(|| state.err_range("index (must be 0 to 127)"))
+        }
+    }
+}
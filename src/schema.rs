@@ -0,0 +1,218 @@
+//! Schemas describing which bits of a bitflag are defined, reserved, or deprecated.
+//!
+//! A schema decides what to do with _unknown_ bits (those neither defined nor deprecated, or
+//! reserved) when loading flags, e.g. flags saved by a newer build. Its [`UnknownBitPolicy`] also
+//! applies when deserializing through the schema with `serde` (as a `DeserializeSeed`) or with
+//! `nanoserde` (through its `de_bin`, `de_json`, and `de_ron` methods).
+
+use crate::{BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8, BitFlagsError};
+
+/// What to do with unknown bits when loading flags through a [`FlagSchema`].
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
+pub enum UnknownBitPolicy {
+    /// Fail with `BitFlagsError::UnknownBits`.
+    #[default]
+    Reject,
+    /// Clear the unknown bits.
+    Truncate,
+    /// Keep the unknown bits as they are.
+    Retain,
+}
+
+/// The defined, reserved, and deprecated bits of a bitflag type `F` (e.g. `BitFlags32`).
+///
+/// _Known_ bits are those that are defined or deprecated, and not reserved. All other bits are
+/// _unknown_.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct FlagSchema<F> {
+    defined:    F,
+    reserved:   F,
+    deprecated: F,
+    policy:     UnknownBitPolicy,
+}
+
+macro_rules! impl_schema {
+    ($flags:ident, $int:ty) => {
+        impl FlagSchema<$flags> {
+            /// Returns a schema where `defined` bits are known, and all other bits are unknown
+            /// and rejected.
+            #[inline]
+            pub const fn new(defined: $flags) -> Self {
+                FlagSchema {
+                    defined,
+                    reserved: $flags(0),
+                    deprecated: $flags(0),
+                    policy: UnknownBitPolicy::Reject,
+                }
+            }
+            /// Returns the schema with `reserved` bits, which are never known (even if also
+            /// defined or deprecated).
+            #[inline]
+            pub const fn with_reserved(self, reserved: $flags) -> Self {
+                FlagSchema { reserved, ..self }
+            }
+            /// Returns the schema with `deprecated` bits, which are still known but are left
+            /// untouched by `complement_within`.
+            #[inline]
+            pub const fn with_deprecated(self, deprecated: $flags) -> Self {
+                FlagSchema { deprecated, ..self }
+            }
+            /// Returns the schema with the given policy for unknown bits.
+            #[inline]
+            pub const fn with_policy(self, policy: UnknownBitPolicy) -> Self {
+                FlagSchema { policy, ..self }
+            }
+            /// Returns the defined bits.
+            #[inline]
+            pub const fn defined(&self) -> $flags {
+                self.defined
+            }
+            /// Returns the reserved bits.
+            #[inline]
+            pub const fn reserved(&self) -> $flags {
+                self.reserved
+            }
+            /// Returns the deprecated bits.
+            #[inline]
+            pub const fn deprecated(&self) -> $flags {
+                self.deprecated
+            }
+            /// Returns the policy for unknown bits.
+            #[inline]
+            pub const fn policy(&self) -> UnknownBitPolicy {
+                self.policy
+            }
+            /// Returns the known bits: those defined or deprecated, and not reserved.
+            #[inline]
+            pub const fn known(&self) -> $flags {
+                $flags((self.defined.0 | self.deprecated.0) & !self.reserved.0)
+            }
+            /// Returns the bits of `flags` that are unknown.
+            #[inline]
+            pub const fn unknown_bits(&self, flags: $flags) -> $flags {
+                $flags(flags.0 & !self.known().0)
+            }
+            /// Returns `true` if `flags` has no unknown bits set.
+            #[inline]
+            pub const fn is_valid(&self, flags: $flags) -> bool {
+                self.unknown_bits(flags).0 == 0
+            }
+            /// Returns bitflags from `bits`. Returns `Err` if any unknown bits are set.
+            #[inline]
+            pub fn from_bits_checked(&self, bits: $int) -> Result<$flags, BitFlagsError> {
+                match self.unknown_bits($flags(bits)).0 {
+                    0 => Ok($flags(bits)),
+                    unknown => Err(BitFlagsError::UnknownBits { bits: unknown as u128 }),
+                }
+            }
+            /// Returns bitflags from `bits`, with any unknown bits cleared.
+            #[inline]
+            pub const fn from_bits_truncate(&self, bits: $int) -> $flags {
+                $flags(bits & self.known().0)
+            }
+            /// Returns bitflags from `bits`, keeping any unknown bits.
+            #[inline]
+            pub const fn from_bits_retain(&self, bits: $int) -> $flags {
+                $flags(bits)
+            }
+            /// Returns bitflags from `bits`, handling unknown bits according to the schema's
+            /// policy.
+            #[inline]
+            pub fn from_bits(&self, bits: $int) -> Result<$flags, BitFlagsError> {
+                match self.policy {
+                    UnknownBitPolicy::Reject => self.from_bits_checked(bits),
+                    UnknownBitPolicy::Truncate => Ok(self.from_bits_truncate(bits)),
+                    UnknownBitPolicy::Retain => Ok(self.from_bits_retain(bits)),
+                }
+            }
+            /// Returns `flags` with its defined bits flipped, its deprecated bits unchanged, and
+            /// its unknown bits cleared.
+            ///
+            /// Unlike `!flags`, this never sets reserved or unknown bits.
+            #[inline]
+            pub const fn complement_within(&self, flags: $flags) -> $flags {
+                $flags((flags.0 ^ self.defined.0) & self.known().0)
+            }
+        }
+
+        #[cfg(feature = "serde-support")]
+        impl<'de> serde::de::DeserializeSeed<'de> for FlagSchema<$flags> {
+            type Value = $flags;
+
+            fn deserialize<D: serde::Deserializer<'de>>(self, d: D) -> Result<$flags, D::Error> {
+                use serde::de::Error;
+                use serde::Deserialize;
+
+                let bits = <$int>::deserialize(d)?;
+                self.from_bits(bits).map_err(D::Error::custom)
+            }
+        }
+
+        #[cfg(feature = "nanoserde-support")]
+        impl FlagSchema<$flags> {
+            /// Deserializes bitflags from `nanoserde` BIN, handling unknown bits according to the
+            /// schema's policy.
+            ///
+            /// Rejected bits are reported as if the input were too short, as `DeBinErr` carries
+            /// no message.
+            pub fn de_bin(
+                &self,
+                offset: &mut usize,
+                bytes: &[u8],
+            ) -> Result<$flags, nanoserde::DeBinErr> {
+                let start = *offset;
+                let flags = <$flags as nanoserde::DeBin>::de_bin(offset, bytes)?;
+
+                self.from_bits(flags.0).map_err(|_| {
+                    *offset = start;
+                    nanoserde::DeBinErr {
+                        o: start,
+                        l: core::mem::size_of::<$int>(),
+                        s: bytes.len(),
+                    }
+                })
+            }
+        }
+    };
+}
+
+/// `nanoserde` supports JSON and RON for up to 64 bits.
+macro_rules! impl_schema_nanoserde_text {
+    ($flags:ident) => {
+        #[cfg(feature = "nanoserde-support")]
+        impl FlagSchema<$flags> {
+            /// Deserializes bitflags from `nanoserde` JSON, handling unknown bits according to
+            /// the schema's policy.
+            pub fn de_json(
+                &self,
+                state: &mut nanoserde::DeJsonState,
+                input: &mut core::str::Chars,
+            ) -> Result<$flags, nanoserde::DeJsonErr> {
+                let flags = <$flags as nanoserde::DeJson>::de_json(state, input)?;
+                self.from_bits(flags.0)
+                    .map_err(|_| state.err_range("unknown bits set"))
+            }
+            /// Deserializes bitflags from `nanoserde` RON, handling unknown bits according to the
+            /// schema's policy.
+            pub fn de_ron(
+                &self,
+                state: &mut nanoserde::DeRonState,
+                input: &mut core::str::Chars,
+            ) -> Result<$flags, nanoserde::DeRonErr> {
+                let flags = <$flags as nanoserde::DeRon>::de_ron(state, input)?;
+                self.from_bits(flags.0)
+                    .map_err(|_| state.err_range("unknown bits set"))
+            }
+        }
+    };
+}
+
+impl_schema!(BitFlags8, u8);
+impl_schema!(BitFlags16, u16);
+impl_schema!(BitFlags32, u32);
+impl_schema!(BitFlags64, u64);
+impl_schema!(BitFlags128, u128);
+impl_schema_nanoserde_text!(BitFlags8);
+impl_schema_nanoserde_text!(BitFlags16);
+impl_schema_nanoserde_text!(BitFlags32);
+impl_schema_nanoserde_text!(BitFlags64);
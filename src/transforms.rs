@@ -0,0 +1,429 @@
+//! Sum-over-subsets (zeta and Möbius) transforms and subset convolution.
+//!
+//! Tables hold one entry per submask of an `n`-bit universe: `table[flags.0 as usize]` is the
+//! entry for `flags` (e.g. a `BitFlags16`), so a table's length must be `2^n`. Sums and products
+//! are taken in a [`Semiring`], and the Möbius (inverse) transforms also need a [`Ring`].
+//!
+//! With `alloc`, a [`SubsetTable`] holds such a table indexed directly by `BitFlags8`,
+//! `BitFlags16`, or `BitFlags32`.
+
+use crate::BitFlagsError;
+#[cfg(feature = "alloc")]
+use crate::{BitFlags16, BitFlags32, BitFlags8};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::num::Wrapping;
+
+/// A set with an associative, commutative `add` (identity `ZERO`) and an associative `mul`
+/// (identity `ONE`) that distributes over `add`.
+pub trait Semiring: Copy {
+    /// The identity of `add`.
+    const ZERO: Self;
+    /// The identity of `mul`.
+    const ONE: Self;
+
+    /// Returns `self + rhs`.
+    fn add(self, rhs: Self) -> Self;
+    /// Returns `self * rhs`.
+    fn mul(self, rhs: Self) -> Self;
+}
+
+/// A [`Semiring`] whose `add` can be undone.
+pub trait Ring: Semiring {
+    /// Returns `self - rhs`.
+    fn sub(self, rhs: Self) -> Self;
+}
+
+/// Integers wrap on overflow, so unsigned integers are the ring of integers modulo `2^BITS`.
+macro_rules! impl_ring_int {
+    ($($int:ty),*) => {
+        $(
+            impl Semiring for $int {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                #[inline]
+                fn add(self, rhs: Self) -> Self {
+                    self.wrapping_add(rhs)
+                }
+                #[inline]
+                fn mul(self, rhs: Self) -> Self {
+                    self.wrapping_mul(rhs)
+                }
+            }
+
+            impl Ring for $int {
+                #[inline]
+                fn sub(self, rhs: Self) -> Self {
+                    self.wrapping_sub(rhs)
+                }
+            }
+
+            impl Semiring for Wrapping<$int> {
+                const ZERO: Self = Wrapping(0);
+                const ONE: Self = Wrapping(1);
+
+                #[inline]
+                fn add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+                #[inline]
+                fn mul(self, rhs: Self) -> Self {
+                    self * rhs
+                }
+            }
+
+            impl Ring for Wrapping<$int> {
+                #[inline]
+                fn sub(self, rhs: Self) -> Self {
+                    self - rhs
+                }
+            }
+        )*
+    };
+}
+
+impl_ring_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_ring_float {
+    ($($float:ty),*) => {
+        $(
+            impl Semiring for $float {
+                const ZERO: Self = 0.0;
+                const ONE: Self = 1.0;
+
+                #[inline]
+                fn add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+                #[inline]
+                fn mul(self, rhs: Self) -> Self {
+                    self * rhs
+                }
+            }
+
+            impl Ring for $float {
+                #[inline]
+                fn sub(self, rhs: Self) -> Self {
+                    self - rhs
+                }
+            }
+        )*
+    };
+}
+
+impl_ring_float!(f32, f64);
+
+/// The boolean semiring (`add` is _or_, `mul` is _and_), e.g. for reachability.
+impl Semiring for bool {
+    const ZERO: Self = false;
+    const ONE: Self = true;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        self | rhs
+    }
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        self & rhs
+    }
+}
+
+/// Returns `n` for a table of length `2^n`, or `Err` if `len` is not a power of two.
+#[inline]
+fn table_bits(len: usize) -> Result<usize, BitFlagsError> {
+    if !len.is_power_of_two() {
+        return Err(BitFlagsError::InvalidTableLength { len });
+    }
+    Ok(len.trailing_zeros() as usize)
+}
+
+/// Returns `Err` unless `len` equals `expected`.
+#[inline]
+fn check_len(len: usize, expected: usize) -> Result<(), BitFlagsError> {
+    if len != expected {
+        return Err(BitFlagsError::TableLengthMismatch { len, expected });
+    }
+    Ok(())
+}
+
+/// Replaces each `table[s]` with the sum of `table[t]` over all subsets `t` of `s` (the zeta
+/// transform). Returns `Err` (leaving the table unchanged) if its length is not a power of two.
+pub fn subset_zeta<T: Semiring>(table: &mut [T]) -> Result<(), BitFlagsError> {
+    for bit in 0..table_bits(table.len())? {
+        let step = 1 << bit;
+        for s in 0..table.len() {
+            if s & step != 0 {
+                table[s] = table[s].add(table[s ^ step]);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Undoes [`subset_zeta`]: replaces each `table[s]` with the value whose subset sums were given
+/// (the Möbius transform). Returns `Err` (leaving the table unchanged) if its length is not a
+/// power of two.
+pub fn subset_mobius<T: Ring>(table: &mut [T]) -> Result<(), BitFlagsError> {
+    for bit in 0..table_bits(table.len())? {
+        let step = 1 << bit;
+        for s in 0..table.len() {
+            if s & step != 0 {
+                table[s] = table[s].sub(table[s ^ step]);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Replaces each `table[s]` with the sum of `table[t]` over all supersets `t` of `s` within the
+/// table's universe. Returns `Err` (leaving the table unchanged) if its length is not a power of
+/// two.
+pub fn superset_zeta<T: Semiring>(table: &mut [T]) -> Result<(), BitFlagsError> {
+    for bit in 0..table_bits(table.len())? {
+        let step = 1 << bit;
+        for s in 0..table.len() {
+            if s & step == 0 {
+                table[s] = table[s].add(table[s | step]);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Undoes [`superset_zeta`]. Returns `Err` (leaving the table unchanged) if its length is not a
+/// power of two.
+pub fn superset_mobius<T: Ring>(table: &mut [T]) -> Result<(), BitFlagsError> {
+    for bit in 0..table_bits(table.len())? {
+        let step = 1 << bit;
+        for s in 0..table.len() {
+            if s & step == 0 {
+                table[s] = table[s].sub(table[s | step]);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Sets each `out[s]` to the sum of `f[t] * g[s ^ t]` over all subsets `t` of `s` (the subset
+/// convolution), by enumerating every submask in `O(3^n)` time.
+///
+/// Works in any [`Semiring`]; for a [`Ring`], `ranked_subset_convolution` takes `O(n^2 2^n)`.
+/// Returns `Err` if the tables' lengths differ or are not a power of two.
+pub fn subset_convolution<T: Semiring>(
+    f: &[T],
+    g: &[T],
+    out: &mut [T],
+) -> Result<(), BitFlagsError> {
+    table_bits(f.len())?;
+    check_len(g.len(), f.len())?;
+    check_len(out.len(), f.len())?;
+
+    for (s, out) in out.iter_mut().enumerate() {
+        let mut sum = f[0].mul(g[s]);
+        let mut t = s;
+        while t != 0 {
+            sum = sum.add(f[t].mul(g[s ^ t]));
+            t = (t - 1) & s;
+        }
+        *out = sum;
+    }
+    Ok(())
+}
+
+/// Returns the subset convolution of `f` and `g` (see [`subset_convolution`]), computed with
+/// ranked zeta and Möbius transforms in `O(n^2 2^n)` time and `O(n 2^n)` space.
+///
+/// Returns `Err` if the tables' lengths differ or are not a power of two.
+#[cfg(feature = "alloc")]
+pub fn ranked_subset_convolution<T: Ring>(f: &[T], g: &[T]) -> Result<Vec<T>, BitFlagsError> {
+    use alloc::vec;
+
+    let n = table_bits(f.len())?;
+    let len = f.len();
+    check_len(g.len(), len)?;
+
+    // Rank `k` holds the entries of `f` (or `g`) whose submask has exactly `k` bits set.
+    let mut f_ranked = vec![T::ZERO; (n + 1) * len];
+    let mut g_ranked = vec![T::ZERO; (n + 1) * len];
+    for s in 0..len {
+        let k = s.count_ones() as usize;
+        f_ranked[k * len + s] = f[s];
+        g_ranked[k * len + s] = g[s];
+    }
+    for k in 0..=n {
+        subset_zeta(&mut f_ranked[k * len..(k + 1) * len])?;
+        subset_zeta(&mut g_ranked[k * len..(k + 1) * len])?;
+    }
+
+    let mut h_ranked = vec![T::ZERO; (n + 1) * len];
+    for k in 0..=n {
+        let h = &mut h_ranked[k * len..(k + 1) * len];
+        for j in 0..=k {
+            let f = &f_ranked[j * len..(j + 1) * len];
+            let g = &g_ranked[(k - j) * len..(k - j + 1) * len];
+            for s in 0..len {
+                h[s] = h[s].add(f[s].mul(g[s]));
+            }
+        }
+        subset_mobius(h)?;
+    }
+
+    Ok((0..len)
+        .map(|s| h_ranked[s.count_ones() as usize * len + s])
+        .collect())
+}
+
+/// A table with one entry per submask of an `n`-bit universe (`n <= 32`), indexed by
+/// `BitFlags8`, `BitFlags16`, or `BitFlags32`.
+///
+/// Indexing by a mask outside the universe panics, as for a slice; `get` returns `None` instead.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SubsetTable<T> {
+    entries: Vec<T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> SubsetTable<T> {
+    /// Returns a table over an `n`-bit universe, with `f(mask)` as the entry for each submask.
+    ///
+    /// __Panics__ if `n > 32`.
+    pub fn from_fn<F: FnMut(BitFlags32) -> T>(n: u32, f: F) -> Self {
+        assert!(n <= 32, "subset tables are indexed by at most 32 bits");
+        SubsetTable {
+            entries: (0..1u64 << n).map(|s| BitFlags32(s as u32)).map(f).collect(),
+        }
+    }
+    /// Returns a table of `entries`, where `entries[mask.0 as usize]` is the entry for `mask`.
+    /// Returns `Err` if the length is not a power of two (at most `2^32`).
+    pub fn from_vec(entries: Vec<T>) -> Result<Self, BitFlagsError> {
+        if table_bits(entries.len())? > 32 {
+            return Err(BitFlagsError::InvalidTableLength { len: entries.len() });
+        }
+        Ok(SubsetTable { entries })
+    }
+    /// Returns the number of bits in the universe.
+    #[inline]
+    pub fn bits(&self) -> u32 {
+        self.entries.len().trailing_zeros()
+    }
+    /// Returns the universe: the mask with every one of its bits set.
+    #[inline]
+    pub fn universe(&self) -> BitFlags32 {
+        BitFlags32((self.entries.len() - 1) as u32)
+    }
+    /// Returns the number of entries (`2^bits`).
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Always returns `false`: a table has at least the entry for the empty mask.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+    /// Returns the entry for `mask`, or `None` if it is not within the universe.
+    #[inline]
+    pub fn get(&self, mask: BitFlags32) -> Option<&T> {
+        self.entries.get(mask.0 as usize)
+    }
+    /// Returns the entry for `mask` mutably, or `None` if it is not within the universe.
+    #[inline]
+    pub fn get_mut(&mut self, mask: BitFlags32) -> Option<&mut T> {
+        self.entries.get_mut(mask.0 as usize)
+    }
+    /// Iterates over every submask of the universe and its entry, in increasing order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (BitFlags32, &T)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(s, entry)| (BitFlags32(s as u32), entry))
+    }
+    /// Returns the entries, indexed by `mask.0 as usize`.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.entries
+    }
+    /// Returns the entries mutably, indexed by `mask.0 as usize`.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.entries
+    }
+    /// Returns the entries, discarding the table.
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.entries
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Semiring> SubsetTable<T> {
+    /// Applies [`subset_zeta`]: each entry becomes the sum over the submasks of its mask.
+    #[inline]
+    pub fn zeta(&mut self) {
+        subset_zeta(&mut self.entries).unwrap()
+    }
+    /// Applies [`superset_zeta`]: each entry becomes the sum over the supersets of its mask.
+    #[inline]
+    pub fn superset_zeta(&mut self) {
+        superset_zeta(&mut self.entries).unwrap()
+    }
+    /// Returns the subset convolution of `self` and `other` in `O(3^n)` time (see
+    /// [`subset_convolution`]). Returns `Err` if their universes differ.
+    pub fn convolve(&self, other: &Self) -> Result<Self, BitFlagsError> {
+        let mut entries = self.entries.clone();
+        subset_convolution(&self.entries, &other.entries, &mut entries)?;
+        Ok(SubsetTable { entries })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Ring> SubsetTable<T> {
+    /// Applies [`subset_mobius`], undoing [`SubsetTable::zeta`].
+    #[inline]
+    pub fn mobius(&mut self) {
+        subset_mobius(&mut self.entries).unwrap()
+    }
+    /// Applies [`superset_mobius`], undoing [`SubsetTable::superset_zeta`].
+    #[inline]
+    pub fn superset_mobius(&mut self) {
+        superset_mobius(&mut self.entries).unwrap()
+    }
+    /// Returns the subset convolution of `self` and `other` in `O(n^2 2^n)` time (see
+    /// [`ranked_subset_convolution`]). Returns `Err` if their universes differ.
+    pub fn ranked_convolve(&self, other: &Self) -> Result<Self, BitFlagsError> {
+        let entries = ranked_subset_convolution(&self.entries, &other.entries)?;
+        Ok(SubsetTable { entries })
+    }
+}
+
+macro_rules! impl_subset_table_index {
+    ($flags:ident) => {
+        /// Returns the entry for a mask.
+        ///
+        /// __Panics__ if the mask is not within the table's universe.
+        #[cfg(feature = "alloc")]
+        impl<T> core::ops::Index<$flags> for SubsetTable<T> {
+            type Output = T;
+
+            #[inline]
+            fn index(&self, mask: $flags) -> &T {
+                &self.entries[mask.0 as usize]
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        impl<T> core::ops::IndexMut<$flags> for SubsetTable<T> {
+            #[inline]
+            fn index_mut(&mut self, mask: $flags) -> &mut T {
+                &mut self.entries[mask.0 as usize]
+            }
+        }
+    };
+}
+
+impl_subset_table_index!(BitFlags8);
+impl_subset_table_index!(BitFlags16);
+impl_subset_table_index!(BitFlags32);
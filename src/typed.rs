@@ -0,0 +1,1105 @@
+//! Bitflags tagged with a domain, so that flags from different domains can't be mixed up.
+//!
+//! `TypedFlags<Tag, F>` wraps bitflags `F` (e.g. `BitFlags32`), with `Tag` as a zero-sized marker
+//! of what the bits mean. A domain is usually declared as an alias over an empty enum:
+//! ```ignore
+//! pub enum Collision {}
+//! pub type CollisionLayers = TypedFlags<Collision, BitFlags32>;
+//! ```
+//!
+//! Methods of `F` are forwarded with `Self` in place of `F`, so that passing `RenderLayers` where
+//! `CollisionLayers` is expected fails to compile. `TypedFlags` does not deref to `F`, as that
+//! would let `F`'s own methods accept untagged (or differently tagged) flags. Views of the bits as
+//! other types (lanes, and the MSB-0 view) are untagged.
+
+use crate::{
+    BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8, BitFlagsError, BitFlagsIter128,
+    BitFlagsIter16, BitFlagsIter32, BitFlagsIter64, BitFlagsIter8, BitMut128, BitMut16, BitMut32,
+    BitMut64, BitMut8, Combinations128, Combinations16, Combinations32, Combinations64,
+    Combinations8, FlagNames, FlagsDisplay, GrayCode128, GrayCode16, GrayCode32, GrayCode64,
+    GrayCode8, Index128, Index16, Index32, Index64, Index8, Lanes, Msb0BitFlags128,
+    Msb0BitFlags16, Msb0BitFlags32, Msb0BitFlags64, Msb0BitFlags8, Subsets128, Subsets16,
+    Subsets32, Subsets64, Subsets8,
+};
+use core::iter::Map;
+use core::marker::PhantomData;
+use core::ops::{RangeBounds, RangeInclusive};
+
+/// Bitflags `F` (e.g. `BitFlags32`) of the domain `Tag`.
+///
+/// `Tag` is only a marker: it is never constructed, and needn't implement any traits.
+///
+/// ```compile_fail
+/// use arctan_bitflags::{BitFlags32, TypedFlags};
+///
+/// enum Collision {}
+/// enum Render {}
+///
+/// let collision = TypedFlags::<Collision, BitFlags32>::full();
+/// let mut render = TypedFlags::<Render, BitFlags32>::empty();
+/// render.insert(collision);
+/// ```
+///
+/// Nor can untagged flags be mixed in by dereferencing to `F`:
+///
+/// ```compile_fail
+/// use arctan_bitflags::{BitFlags32, TypedFlags};
+///
+/// enum Render {}
+///
+/// let mut render = TypedFlags::<Render, BitFlags32>::empty();
+/// (*render).insert(BitFlags32::full());
+/// ```
+pub struct TypedFlags<Tag, F> {
+    flags: F,
+    tag:   PhantomData<fn() -> Tag>,
+}
+
+impl<Tag, F> TypedFlags<Tag, F> {
+    /// Returns `flags` tagged as `Tag`.
+    #[inline]
+    pub const fn from_flags(flags: F) -> Self {
+        Self { flags, tag: PhantomData }
+    }
+    /// Returns the untagged flags.
+    #[inline]
+    pub fn into_flags(self) -> F {
+        self.flags
+    }
+    /// Returns the same flags, tagged as `U` instead.
+    #[inline]
+    pub fn retag<U>(self) -> TypedFlags<U, F> {
+        TypedFlags::from_flags(self.flags)
+    }
+}
+
+impl<Tag, F: Copy> TypedFlags<Tag, F> {
+    /// Returns a copy of the untagged flags.
+    #[inline]
+    pub const fn get(&self) -> F {
+        self.flags
+    }
+}
+
+macro_rules! impl_typed {
+    (
+        $flags:ident,
+        $int:ty,
+        $from_int:ident,
+        $to_int:ident,
+        $iter:ident,
+        $index:ident,
+        $bit_mut:ident,
+        $msb0:ident,
+        $subsets:ident,
+        $combinations:ident,
+        $gray:ident
+    ) => {
+        impl<Tag> TypedFlags<Tag, $flags> {
+            /// Returns a new instance with all bits set to `0`.
+            #[inline]
+            pub fn new() -> Self {
+                Self::from_flags($flags::new())
+            }
+            /// Returns a new instance with all bits set to `0`.
+            #[inline]
+            pub fn empty() -> Self {
+                Self::from_flags($flags::empty())
+            }
+            /// Returns a new instance with all bits set to `1`.
+            #[inline]
+            pub fn full() -> Self {
+                Self::from_flags($flags::full())
+            }
+            #[doc = concat!("Returns a new instance from a `", stringify!($int), "`.")]
+            #[inline]
+            pub fn $from_int(val: $int) -> Self {
+                Self::from_flags($flags(val))
+            }
+            /// Returns a new instance with bits in range `(start..=end)` set.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::with_set_bit_range`.")]
+            #[inline]
+            pub fn with_set_bit_range(start: u8, end: u8) -> Self {
+                Self::from_flags($flags::with_set_bit_range(start, end))
+            }
+            /// Returns a new instance with bits in range `(start..=end)` set. Returns `Err` if the
+            /// range is invalid.
+            #[inline]
+            pub fn try_with_set_bit_range(start: u8, end: u8) -> Result<Self, BitFlagsError> {
+                $flags::try_with_set_bit_range(start, end).map(Self::from_flags)
+            }
+            /// Converts an index into an instance with that bit set.
+            ///
+            /// __Panics__ if `index` is out of range.
+            #[inline]
+            pub fn from_index(index: usize) -> Self {
+                Self::from_flags($flags::from_index(index))
+            }
+            /// Converts an index into an instance with that bit set. Returns `Err` if `index` is
+            /// out of range.
+            #[inline]
+            pub fn try_from_index(index: usize) -> Result<Self, BitFlagsError> {
+                $flags::try_from_index(index).map(Self::from_flags)
+            }
+            /// Converts a slice of indexes into an instance with those bits set.
+            ///
+            /// __Panics__ if any index is out of range.
+            #[inline]
+            pub fn from_slice(s: &[usize]) -> Self {
+                Self::from_flags($flags::from_slice(s))
+            }
+            /// Converts a slice of indexes into an instance with those bits set. Returns `Err` on
+            /// the first index out of range.
+            #[inline]
+            pub fn try_from_slice(s: &[usize]) -> Result<Self, BitFlagsError> {
+                $flags::try_from_slice(s).map(Self::from_flags)
+            }
+            /// Returns a new instance with the bits in `range` set.
+            ///
+            /// __Panics__ if `range` is out of bounds.
+            #[inline]
+            pub fn from_range<R: RangeBounds<usize>>(range: R) -> Self {
+                Self::from_flags($flags::from_range(range))
+            }
+            /// Returns a new instance with the bits in `range` set. Returns `Err` if `range` is out
+            /// of bounds.
+            #[inline]
+            pub fn try_from_range<R: RangeBounds<usize>>(range: R) -> Result<Self, BitFlagsError> {
+                $flags::try_from_range(range).map(Self::from_flags)
+            }
+            /// Returns the number of bits.
+            #[inline]
+            pub fn num_bits() -> usize {
+                $flags::num_bits()
+            }
+            /// Returns `true` if any bit is set in both `self` and `other`.
+            #[inline]
+            pub fn intersects(&self, other: Self) -> bool {
+                self.flags.intersects(other.flags)
+            }
+            /// Returns `true` if all bits set in `other` are also set in `self`.
+            #[inline]
+            pub fn contains(&self, other: Self) -> bool {
+                self.flags.contains(other.flags)
+            }
+            /// Returns the bits set in both `self` and `other`.
+            #[inline]
+            pub fn intersection(&self, other: Self) -> Self {
+                Self::from_flags(self.flags.intersection(other.flags))
+            }
+            /// Returns the bits set in `self`, but not in `other`.
+            #[inline]
+            pub fn difference(&self, other: Self) -> Self {
+                Self::from_flags(self.flags.difference(other.flags))
+            }
+            /// Returns the bits set in exactly one of `self` and `other`.
+            #[inline]
+            pub fn symmetric_difference(&self, other: Self) -> Self {
+                Self::from_flags(self.flags.symmetric_difference(other.flags))
+            }
+            /// Returns the bits set in either `self` or `other`.
+            #[inline]
+            pub fn union(&self, other: Self) -> Self {
+                Self::from_flags(self.flags.union(other.flags))
+            }
+            /// Returns the bits not set in `self`.
+            #[inline]
+            pub fn complement(&self) -> Self {
+                Self::from_flags(self.flags.complement())
+            }
+            /// Sets the bits set in `other`.
+            #[inline]
+            pub fn insert(&mut self, other: Self) {
+                self.flags.insert(other.flags)
+            }
+            /// Clears the bits set in `other`.
+            #[inline]
+            pub fn remove(&mut self, other: Self) {
+                self.flags.remove(other.flags)
+            }
+            /// Toggles the bits set in `mask`.
+            #[inline]
+            pub fn toggle(&mut self, mask: Self) {
+                self.flags.toggle(mask.flags)
+            }
+            /// Sets (if `value`) or clears the bits set in `other`.
+            #[inline]
+            pub fn set(&mut self, other: Self, value: bool) {
+                self.flags.set(other.flags, value)
+            }
+            /// Returns only the highest set bit.
+            #[inline]
+            pub fn highest_set_bit(&self) -> Self {
+                Self::from_flags(self.flags.highest_set_bit())
+            }
+            /// Returns the bits rotated left by `n`.
+            #[inline]
+            pub fn rotate_left(&self, n: u32) -> Self {
+                Self::from_flags(self.flags.rotate_left(n))
+            }
+            /// Returns the bits rotated right by `n`.
+            #[inline]
+            pub fn rotate_right(&self, n: u32) -> Self {
+                Self::from_flags(self.flags.rotate_right(n))
+            }
+            /// Returns only the lowest set bit.
+            #[inline]
+            pub fn isolate_lowest(&self) -> Self {
+                Self::from_flags(self.flags.isolate_lowest())
+            }
+            /// Returns the bits with the lowest set bit cleared.
+            #[inline]
+            pub fn clear_lowest(&self) -> Self {
+                Self::from_flags(self.flags.clear_lowest())
+            }
+            /// Returns a mask of all bits up to and including the lowest set bit.
+            #[inline]
+            pub fn mask_up_to_lowest(&self) -> Self {
+                Self::from_flags(self.flags.mask_up_to_lowest())
+            }
+            /// Returns the bits of `a` where `mask` is set, and of `b` elsewhere (a bitwise select,
+            /// named `blend` as `select` returns the index of the `k`-th set bit).
+            #[inline]
+            pub fn blend(mask: Self, a: Self, b: Self) -> Self {
+                Self::from_flags($flags::blend(mask.flags, a.flags, b.flags))
+            }
+            /// Returns the next larger value with the same number of set bits, if any.
+            #[inline]
+            pub fn next_same_popcount(&self) -> Option<Self> {
+                self.flags.next_same_popcount().map(Self::from_flags)
+            }
+            /// Returns the flags with `k` bits set at position `rank` in colexicographic order, if
+            /// any.
+            #[inline]
+            pub fn from_combinatorial_rank(k: u32, rank: $int) -> Option<Self> {
+                $flags::from_combinatorial_rank(k, rank).map(Self::from_flags)
+            }
+            /// Returns the bits with every bit below the highest set bit also set.
+            #[inline]
+            pub fn smear_right(&self) -> Self {
+                Self::from_flags(self.flags.smear_right())
+            }
+            /// Returns the bits with every bit above the lowest set bit also set.
+            #[inline]
+            pub fn smear_left(&self) -> Self {
+                Self::from_flags(self.flags.smear_left())
+            }
+            /// Returns the bits in reverse order.
+            #[inline]
+            pub fn reverse_bits(&self) -> Self {
+                Self::from_flags(self.flags.reverse_bits())
+            }
+            /// Gathers the bits selected by `mask` into the low bits.
+            #[inline]
+            pub fn extract(&self, mask: Self) -> Self {
+                Self::from_flags(self.flags.extract(mask.flags))
+            }
+            /// Scatters the low bits into the positions selected by `mask`.
+            #[inline]
+            pub fn deposit(&self, mask: Self) -> Self {
+                Self::from_flags(self.flags.deposit(mask.flags))
+            }
+            /// Returns an iterator over every subset of the set bits, starting with the empty set.
+            #[inline]
+            pub fn submasks(&self) -> Map<$subsets, fn($flags) -> Self> {
+                self.flags.submasks().map(Self::from_flags)
+            }
+            /// Returns an iterator over every non-empty subset of the set bits.
+            #[inline]
+            pub fn nonempty_submasks(&self) -> Map<$subsets, fn($flags) -> Self> {
+                self.flags.nonempty_submasks().map(Self::from_flags)
+            }
+            /// Returns an iterator over every superset of `self` within `universe`.
+            #[inline]
+            pub fn supersets_within(&self, universe: Self) -> Map<$subsets, fn($flags) -> Self> {
+                self.flags.supersets_within(universe.flags).map(Self::from_flags)
+            }
+            /// Returns an iterator over every subset of the set bits with exactly `k` bits set.
+            #[inline]
+            pub fn combinations(&self, k: u32) -> Map<$combinations, fn($flags) -> Self> {
+                self.flags.combinations(k).map(Self::from_flags)
+            }
+            /// Returns an iterator over every subset of the set bits in Gray-code order.
+            #[inline]
+            pub fn gray_code(&self) -> Map<$gray, fn($flags) -> Self> {
+                self.flags.gray_code().map(Self::from_flags)
+            }
+            /// Returns the underlying integer value.
+            #[inline]
+            pub fn $to_int(&self) -> $int {
+                self.flags.$to_int()
+            }
+            /// Returns `true` if _no_ bits are set.
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.flags.is_empty()
+            }
+            /// Returns `true` if _all_ bits are set.
+            #[inline]
+            pub fn is_full(&self) -> bool {
+                self.flags.is_full()
+            }
+            /// Sets bit at given index.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::insert_at_index`.")]
+            #[inline]
+            pub fn insert_at_index(&mut self, index: usize) {
+                self.flags.insert_at_index(index)
+            }
+            /// Sets bit at given index. Returns `Err` if `index` is out of range.
+            #[inline]
+            pub fn try_insert_at_index(&mut self, index: usize) -> Result<(), BitFlagsError> {
+                self.flags.try_insert_at_index(index)
+            }
+            /// Sets bit at given index to specific value (`true` = `1`; `false` = `0`).
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::set_at_index`.")]
+            #[inline]
+            pub fn set_at_index(&mut self, index: usize, value: bool) {
+                self.flags.set_at_index(index, value)
+            }
+            /// Sets bit at given index to specific value. Returns `Err` if `index` is out of range.
+            #[inline]
+            pub fn try_set_at_index(
+                &mut self,
+                index: usize,
+                value: bool,
+            ) -> Result<(), BitFlagsError> {
+                self.flags.try_set_at_index(index, value)
+            }
+            /// Sets bits in range `(start..=end)`.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::set_bit_range`.")]
+            #[inline]
+            pub fn set_bit_range(&mut self, start: u8, end: u8) {
+                self.flags.set_bit_range(start, end)
+            }
+            /// Sets bits in range `(start..=end)`. Returns `Err` if the range is invalid.
+            #[inline]
+            pub fn try_set_bit_range(&mut self, start: u8, end: u8) -> Result<(), BitFlagsError> {
+                self.flags.try_set_bit_range(start, end)
+            }
+            /// Toggles bit at given index.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::toggle_at_index`.")]
+            #[inline]
+            pub fn toggle_at_index(&mut self, index: usize) {
+                self.flags.toggle_at_index(index)
+            }
+            /// Toggles bit at given index. Returns `Err` if `index` is out of range.
+            #[inline]
+            pub fn try_toggle_at_index(&mut self, index: usize) -> Result<(), BitFlagsError> {
+                self.flags.try_toggle_at_index(index)
+            }
+            /// Unsets bit at given index.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::remove_at_index`.")]
+            #[inline]
+            pub fn remove_at_index(&mut self, index: usize) {
+                self.flags.remove_at_index(index)
+            }
+            /// Unsets bit at given index. Returns `Err` if `index` is out of range.
+            #[inline]
+            pub fn try_remove_at_index(&mut self, index: usize) -> Result<(), BitFlagsError> {
+                self.flags.try_remove_at_index(index)
+            }
+            /// Returns value of bit at given index (`0` is `false`; `1` is `true`).
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::bit_at_index`.")]
+            #[inline]
+            pub fn bit_at_index(&self, index: usize) -> bool {
+                self.flags.bit_at_index(index)
+            }
+            /// Returns value of bit at given index. Returns `Err` if `index` is out of range.
+            #[inline]
+            pub fn try_bit_at_index(&self, index: usize) -> Result<bool, BitFlagsError> {
+                self.flags.try_bit_at_index(index)
+            }
+            /// Returns value of bit at given index, or `None` if `index` is out of range.
+            #[inline]
+            pub fn get_bit_at_index(&self, index: usize) -> Option<bool> {
+                self.flags.get_bit_at_index(index)
+            }
+            /// Returns the index of the highest set bit, if any.
+            #[inline]
+            pub fn highest_set_bit_index(&self) -> Option<usize> {
+                self.flags.highest_set_bit_index()
+            }
+            /// Returns the number of ones.
+            #[inline]
+            pub fn count_ones(&self) -> u32 {
+                self.flags.count_ones()
+            }
+            /// Returns the number of zeroes.
+            #[inline]
+            pub fn count_zeros(&self) -> u32 {
+                self.flags.count_zeros()
+            }
+            /// Returns the number of leading ones.
+            #[inline]
+            pub fn leading_ones(&self) -> u32 {
+                self.flags.leading_ones()
+            }
+            /// Returns the number of leading zeroes.
+            #[inline]
+            pub fn leading_zeros(&self) -> u32 {
+                self.flags.leading_zeros()
+            }
+            /// Returns the number of trailing ones.
+            #[inline]
+            pub fn trailing_ones(&self) -> u32 {
+                self.flags.trailing_ones()
+            }
+            /// Returns the number of trailing zeroes.
+            #[inline]
+            pub fn trailing_zeros(&self) -> u32 {
+                self.flags.trailing_zeros()
+            }
+            /// Iterates over the indexes of the set bits.
+            #[inline]
+            pub fn iter(&self) -> $iter {
+                self.flags.iter()
+            }
+            /// Returns the value of the packed field in range `(start..=end)`.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::get_field`.")]
+            #[inline]
+            pub fn get_field(&self, range: RangeInclusive<u8>) -> $int {
+                self.flags.get_field(range)
+            }
+            /// Returns the value of the packed field in range `(start..=end)`. Returns `Err` if the
+            /// range is invalid.
+            #[inline]
+            pub fn try_get_field(&self, range: RangeInclusive<u8>) -> Result<$int, BitFlagsError> {
+                self.flags.try_get_field(range)
+            }
+            /// Sets the packed field in range `(start..=end)` to `value`.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::set_field`.")]
+            #[inline]
+            pub fn set_field(&mut self, range: RangeInclusive<u8>, value: $int) {
+                self.flags.set_field(range, value)
+            }
+            /// Sets the packed field in range `(start..=end)` to `value`. Returns `Err` (leaving
+            /// `self` unchanged) if the range is invalid or `value` does not fit.
+            #[inline]
+            pub fn try_set_field(
+                &mut self,
+                range: RangeInclusive<u8>,
+                value: $int,
+            ) -> Result<(), BitFlagsError> {
+                self.flags.try_set_field(range, value)
+            }
+            /// Sets bits in `range`.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::insert_range`.")]
+            #[inline]
+            pub fn insert_range<R: RangeBounds<usize>>(&mut self, range: R) {
+                self.flags.insert_range(range)
+            }
+            /// Sets bits in `range`. Returns `Err` if `range` is out of bounds.
+            #[inline]
+            pub fn try_insert_range<R: RangeBounds<usize>>(
+                &mut self,
+                range: R,
+            ) -> Result<(), BitFlagsError> {
+                self.flags.try_insert_range(range)
+            }
+            /// Unsets bits in `range`.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::remove_range`.")]
+            #[inline]
+            pub fn remove_range<R: RangeBounds<usize>>(&mut self, range: R) {
+                self.flags.remove_range(range)
+            }
+            /// Unsets bits in `range`. Returns `Err` if `range` is out of bounds.
+            #[inline]
+            pub fn try_remove_range<R: RangeBounds<usize>>(
+                &mut self,
+                range: R,
+            ) -> Result<(), BitFlagsError> {
+                self.flags.try_remove_range(range)
+            }
+            /// Toggles bits in `range`.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::toggle_range`.")]
+            #[inline]
+            pub fn toggle_range<R: RangeBounds<usize>>(&mut self, range: R) {
+                self.flags.toggle_range(range)
+            }
+            /// Toggles bits in `range`. Returns `Err` if `range` is out of bounds.
+            #[inline]
+            pub fn try_toggle_range<R: RangeBounds<usize>>(
+                &mut self,
+                range: R,
+            ) -> Result<(), BitFlagsError> {
+                self.flags.try_toggle_range(range)
+            }
+            /// Returns `true` if _all_ bits in `range` are set.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::contains_range`.")]
+            #[inline]
+            pub fn contains_range<R: RangeBounds<usize>>(&self, range: R) -> bool {
+                self.flags.contains_range(range)
+            }
+            /// Returns `true` if _all_ bits in `range` are set. Returns `Err` if `range` is out of
+            /// bounds.
+            #[inline]
+            pub fn try_contains_range<R: RangeBounds<usize>>(
+                &self,
+                range: R,
+            ) -> Result<bool, BitFlagsError> {
+                self.flags.try_contains_range(range)
+            }
+            /// Returns `true` if _at least one_ bit in `range` is set.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::intersects_range`.")]
+            #[inline]
+            pub fn intersects_range<R: RangeBounds<usize>>(&self, range: R) -> bool {
+                self.flags.intersects_range(range)
+            }
+            /// Returns `true` if _at least one_ bit in `range` is set. Returns `Err` if `range` is
+            /// out of bounds.
+            #[inline]
+            pub fn try_intersects_range<R: RangeBounds<usize>>(
+                &self,
+                range: R,
+            ) -> Result<bool, BitFlagsError> {
+                self.flags.try_intersects_range(range)
+            }
+            /// Returns the number of ones in `range`.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::count_ones_in`.")]
+            #[inline]
+            pub fn count_ones_in<R: RangeBounds<usize>>(&self, range: R) -> u32 {
+                self.flags.count_ones_in(range)
+            }
+            /// Returns the number of ones in `range`. Returns `Err` if `range` is out of bounds.
+            #[inline]
+            pub fn try_count_ones_in<R: RangeBounds<usize>>(
+                &self,
+                range: R,
+            ) -> Result<u32, BitFlagsError> {
+                self.flags.try_count_ones_in(range)
+            }
+            /// Sets bit at given index.
+            #[inline]
+            pub fn insert_at(&mut self, index: $index) {
+                self.flags.insert_at(index)
+            }
+            /// Unsets bit at given index.
+            #[inline]
+            pub fn remove_at(&mut self, index: $index) {
+                self.flags.remove_at(index)
+            }
+            /// Toggles bit at given index.
+            #[inline]
+            pub fn toggle_at(&mut self, index: $index) {
+                self.flags.toggle_at(index)
+            }
+            /// Sets bit at given index to specific value (`true` = `1`; `false` = `0`).
+            #[inline]
+            pub fn set_at(&mut self, index: $index, value: bool) {
+                self.flags.set_at(index, value)
+            }
+            /// Returns value of bit at given index (`0` is `false`; `1` is `true`).
+            #[inline]
+            pub fn get_at(&self, index: $index) -> bool {
+                self.flags.get_at(index)
+            }
+            /// Sets bit at given index. Returns `true` if the bit was _not_ already set.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::add`.")]
+            #[inline]
+            pub fn add(&mut self, index: usize) -> bool {
+                self.flags.add(index)
+            }
+            /// Sets bit at given index. Returns `Ok(true)` if the bit was _not_ already set, or
+            /// `Err` if `index` is out of range.
+            #[inline]
+            pub fn try_add(&mut self, index: usize) -> Result<bool, BitFlagsError> {
+                self.flags.try_add(index)
+            }
+            /// Unsets bit at given index. Returns `true` if the bit was set.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::take`.")]
+            #[inline]
+            pub fn take(&mut self, index: usize) -> bool {
+                self.flags.take(index)
+            }
+            /// Unsets bit at given index. Returns `Ok(true)` if the bit was set, or `Err` if
+            /// `index` is out of range.
+            #[inline]
+            pub fn try_take(&mut self, index: usize) -> Result<bool, BitFlagsError> {
+                self.flags.try_take(index)
+            }
+            /// Sets bit at given index to specific value. Returns the previous value.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::replace`.")]
+            #[inline]
+            pub fn replace(&mut self, index: usize, value: bool) -> bool {
+                self.flags.replace(index, value)
+            }
+            /// Sets bit at given index to specific value. Returns the previous value, or `Err` if
+            /// `index` is out of range.
+            #[inline]
+            pub fn try_replace(
+                &mut self,
+                index: usize,
+                value: bool,
+            ) -> Result<bool, BitFlagsError> {
+                self.flags.try_replace(index, value)
+            }
+            /// Retains only the set bits whose index satisfies the predicate `f`.
+            #[inline]
+            pub fn retain<F: FnMut(usize) -> bool>(&mut self, f: F) {
+                self.flags.retain(f)
+            }
+            /// Unsets _all_ bits, returning an iterator over the indexes that were set.
+            #[inline]
+            pub fn drain(&mut self) -> $iter {
+                self.flags.drain()
+            }
+            /// Unsets _all_ bits.
+            #[inline]
+            pub fn clear(&mut self) {
+                self.flags.clear()
+            }
+            /// Returns the number of set bits.
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.flags.len()
+            }
+            /// Returns a mutable proxy for the bit at given index.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::bit_mut`.")]
+            #[inline]
+            pub fn bit_mut(&mut self, index: usize) -> $bit_mut<'_> {
+                self.flags.bit_mut(index)
+            }
+            /// Returns a mutable proxy for the bit at given index. Returns `Err` if `index` is out
+            /// of range.
+            #[inline]
+            pub fn try_bit_mut(&mut self, index: usize) -> Result<$bit_mut<'_>, BitFlagsError> {
+                self.flags.try_bit_mut(index)
+            }
+            /// Splits into lanes of narrower (untagged) bitflags `T`, lowest lane first.
+            #[inline]
+            pub fn split<T>(self) -> <$flags as Lanes<T>>::Array
+            where
+                $flags: Lanes<T>,
+            {
+                self.flags.split()
+            }
+            /// Joins lanes of narrower (untagged) bitflags `T`, lowest lane first.
+            #[inline]
+            pub fn join<T>(lanes: <$flags as Lanes<T>>::Array) -> Self
+            where
+                $flags: Lanes<T>,
+            {
+                Self::from_flags($flags::join(lanes))
+            }
+            /// Returns lane `k` of narrower (untagged) bitflags `T`, where lane `0` holds the
+            /// lowest bits.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::lane`.")]
+            #[inline]
+            pub fn lane<T>(&self, k: usize) -> T
+            where
+                $flags: Lanes<T>,
+            {
+                self.flags.lane(k)
+            }
+            /// Returns lane `k` of narrower (untagged) bitflags `T`. Returns `Err` if `k` is not
+            /// less than the number of `T` lanes.
+            #[inline]
+            pub fn try_lane<T>(&self, k: usize) -> Result<T, BitFlagsError>
+            where
+                $flags: Lanes<T>,
+            {
+                self.flags.try_lane(k)
+            }
+            /// Replaces lane `k` of narrower (untagged) bitflags `T` with `lane`.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::set_lane`.")]
+            #[inline]
+            pub fn set_lane<T>(&mut self, k: usize, lane: T)
+            where
+                $flags: Lanes<T>,
+            {
+                self.flags.set_lane(k, lane)
+            }
+            /// Replaces lane `k` of narrower (untagged) bitflags `T` with `lane`. Returns `Err`
+            /// (leaving `self` unchanged) if `k` is not less than the number of `T` lanes.
+            #[inline]
+            pub fn try_set_lane<T>(&mut self, k: usize, lane: T) -> Result<(), BitFlagsError>
+            where
+                $flags: Lanes<T>,
+            {
+                self.flags.try_set_lane(k, lane)
+            }
+            /// Returns an (untagged) MSB-0 view of the flags, numbering bits from the most
+            /// significant end.
+            #[inline]
+            pub fn msb0(self) -> $msb0 {
+                self.flags.msb0()
+            }
+            /// Returns `true` if an _odd_ number of bits are set.
+            #[inline]
+            pub fn parity(&self) -> bool {
+                self.flags.parity()
+            }
+            /// Returns `true` if _exactly one_ bit is set.
+            #[inline]
+            pub fn is_single_bit(&self) -> bool {
+                self.flags.is_single_bit()
+            }
+            /// Returns the index of the set bit if _exactly one_ bit is set, otherwise `None`.
+            #[inline]
+            pub fn single_index(&self) -> Option<usize> {
+                self.flags.single_index()
+            }
+            /// Returns the index of `self` among all flags with the same number of set bits, in
+            /// colexicographic order.
+            #[inline]
+            pub fn combinatorial_rank(&self) -> $int {
+                self.flags.combinatorial_rank()
+            }
+            /// Returns the number of set bits _below_ index `i`.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::rank`.")]
+            #[inline]
+            pub fn rank(&self, i: usize) -> u32 {
+                self.flags.rank(i)
+            }
+            /// Returns the number of set bits _below_ index `i`. Returns `Err` if `i` is out of
+            /// range.
+            #[inline]
+            pub fn try_rank(&self, i: usize) -> Result<u32, BitFlagsError> {
+                self.flags.try_rank(i)
+            }
+            /// Returns the number of set bits _at or below_ index `i`.
+            ///
+            #[doc = concat!("__Panics__ as `", stringify!($flags), "::rank_inclusive`.")]
+            #[inline]
+            pub fn rank_inclusive(&self, i: usize) -> u32 {
+                self.flags.rank_inclusive(i)
+            }
+            /// Returns the number of set bits _at or below_ index `i`. Returns `Err` if `i` is out
+            /// of range.
+            #[inline]
+            pub fn try_rank_inclusive(&self, i: usize) -> Result<u32, BitFlagsError> {
+                self.flags.try_rank_inclusive(i)
+            }
+            /// Returns the index of the `k`-th set bit (counting from `0`), if any.
+            #[inline]
+            pub fn select(&self, k: usize) -> Option<usize> {
+                self.flags.select(k)
+            }
+            /// Returns the index of the `k`-th unset bit (counting from `0`), if any.
+            #[inline]
+            pub fn select_unset(&self, k: usize) -> Option<usize> {
+                self.flags.select_unset(k)
+            }
+            /// Returns an adapter that writes the set bits by name from `names`.
+            #[inline]
+            pub fn display_with<'a, N: FlagNames + ?Sized>(
+                &self,
+                names: &'a N,
+            ) -> FlagsDisplay<'a, N> {
+                self.flags.display_with(names)
+            }
+        }
+
+        impl<Tag> From<$int> for TypedFlags<Tag, $flags> {
+            fn from(value: $int) -> Self {
+                Self::from_flags($flags(value))
+            }
+        }
+
+        impl<Tag> From<TypedFlags<Tag, $flags>> for $int {
+            fn from(value: TypedFlags<Tag, $flags>) -> Self {
+                value.flags.0
+            }
+        }
+
+        impl<Tag> From<TypedFlags<Tag, $flags>> for $flags {
+            fn from(value: TypedFlags<Tag, $flags>) -> Self {
+                value.flags
+            }
+        }
+    };
+}
+
+impl_typed!(
+    BitFlags8,
+    u8,
+    from_u8,
+    to_u8,
+    BitFlagsIter8,
+    Index8,
+    BitMut8,
+    Msb0BitFlags8,
+    Subsets8,
+    Combinations8,
+    GrayCode8
+);
+impl_typed!(
+    BitFlags16,
+    u16,
+    from_u16,
+    to_u16,
+    BitFlagsIter16,
+    Index16,
+    BitMut16,
+    Msb0BitFlags16,
+    Subsets16,
+    Combinations16,
+    GrayCode16
+);
+impl_typed!(
+    BitFlags32,
+    u32,
+    from_u32,
+    to_u32,
+    BitFlagsIter32,
+    Index32,
+    BitMut32,
+    Msb0BitFlags32,
+    Subsets32,
+    Combinations32,
+    GrayCode32
+);
+impl_typed!(
+    BitFlags64,
+    u64,
+    from_u64,
+    to_u64,
+    BitFlagsIter64,
+    Index64,
+    BitMut64,
+    Msb0BitFlags64,
+    Subsets64,
+    Combinations64,
+    GrayCode64
+);
+impl_typed!(
+    BitFlags128,
+    u128,
+    from_u128,
+    to_u128,
+    BitFlagsIter128,
+    Index128,
+    BitMut128,
+    Msb0BitFlags128,
+    Subsets128,
+    Combinations128,
+    GrayCode128
+);
+
+impl<Tag, F> From<F> for TypedFlags<Tag, F> {
+    fn from(flags: F) -> Self {
+        Self::from_flags(flags)
+    }
+}
+
+impl<Tag, F: Clone> Clone for TypedFlags<Tag, F> {
+    fn clone(&self) -> Self {
+        Self::from_flags(self.flags.clone())
+    }
+}
+
+impl<Tag, F: Copy> Copy for TypedFlags<Tag, F> {}
+
+impl<Tag, F: Default> Default for TypedFlags<Tag, F> {
+    fn default() -> Self {
+        Self::from_flags(F::default())
+    }
+}
+
+impl<Tag, F: PartialEq> PartialEq for TypedFlags<Tag, F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.flags == other.flags
+    }
+}
+
+impl<Tag, F: Eq> Eq for TypedFlags<Tag, F> {}
+
+impl<Tag, F: PartialOrd> PartialOrd for TypedFlags<Tag, F> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.flags.partial_cmp(&other.flags)
+    }
+}
+
+impl<Tag, F: Ord> Ord for TypedFlags<Tag, F> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.flags.cmp(&other.flags)
+    }
+}
+
+impl<Tag, F: core::hash::Hash> core::hash::Hash for TypedFlags<Tag, F> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.flags.hash(state)
+    }
+}
+
+impl<Tag, F: core::fmt::Debug> core::fmt::Debug for TypedFlags<Tag, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("TypedFlags").field(&self.flags).finish()
+    }
+}
+
+impl<Tag, F: core::fmt::Display> core::fmt::Display for TypedFlags<Tag, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.flags.fmt(f)
+    }
+}
+
+impl<Tag, F: core::fmt::Binary> core::fmt::Binary for TypedFlags<Tag, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.flags.fmt(f)
+    }
+}
+
+impl<Tag, F: core::str::FromStr> core::str::FromStr for TypedFlags<Tag, F> {
+    type Err = F::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self::from_flags)
+    }
+}
+
+impl<Tag, F: core::ops::Index<usize>> core::ops::Index<usize> for TypedFlags<Tag, F> {
+    type Output = F::Output;
+
+    /// Returns value of bit at given index, as `F` does.
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.flags[index]
+    }
+}
+
+impl<Tag, F: core::ops::BitOr<Output = F>> core::ops::BitOr for TypedFlags<Tag, F> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self::from_flags(self.flags | rhs.flags)
+    }
+}
+
+impl<Tag, F: core::ops::BitOrAssign> core::ops::BitOrAssign for TypedFlags<Tag, F> {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.flags |= rhs.flags
+    }
+}
+
+impl<Tag, F: core::ops::BitAnd<Output = F>> core::ops::BitAnd for TypedFlags<Tag, F> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self::from_flags(self.flags & rhs.flags)
+    }
+}
+
+impl<Tag, F: core::ops::BitAndAssign> core::ops::BitAndAssign for TypedFlags<Tag, F> {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.flags &= rhs.flags
+    }
+}
+
+impl<Tag, F: core::ops::BitXor<Output = F>> core::ops::BitXor for TypedFlags<Tag, F> {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self::from_flags(self.flags ^ rhs.flags)
+    }
+}
+
+impl<Tag, F: core::ops::BitXorAssign> core::ops::BitXorAssign for TypedFlags<Tag, F> {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.flags ^= rhs.flags
+    }
+}
+
+impl<Tag, F: core::ops::Not<Output = F>> core::ops::Not for TypedFlags<Tag, F> {
+    type Output = Self;
+
+    /// Toggles _all_ bits.
+    fn not(self) -> Self::Output {
+        Self::from_flags(!self.flags)
+    }
+}
+
+//  #######   ########   ######   ########  #######
+//  ##    ##  ##        ##        ##        ##    ##
+//  ##    ##  ######     ######   ######    #######
+//  ##    ##  ##              ##  ##        ##   ##
+//  #######   ########  #######   ########  ##    ##
+
+#[cfg(feature = "serde-support")]
+mod impl_serde {
+    use super::TypedFlags;
+    use serde::{Deserialize, Serialize};
+
+    impl<'de, Tag, F: Deserialize<'de>> Deserialize<'de> for TypedFlags<Tag, F> {
+        fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            F::deserialize(d).map(Self::from_flags)
+        }
+    }
+
+    impl<Tag, F: Serialize> Serialize for TypedFlags<Tag, F> {
+        fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            self.flags.serialize(s)
+        }
+    }
+}
+
+#[cfg(feature = "nanoserde-support")]
+mod impl_nanoserde {
+    extern crate std;
+    use super::TypedFlags;
+    use nanoserde::{
+        DeBin, DeBinErr, DeJson, DeJsonErr, DeJsonState, DeRon, DeRonErr, DeRonState, SerBin,
+        SerJson, SerJsonState, SerRon, SerRonState,
+    };
+    use std::prelude::v1::*;
+
+    impl<Tag, F: DeBin> DeBin for TypedFlags<Tag, F> {
+        fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr> {
+            F::de_bin(offset, bytes).map(Self::from_flags)
+        }
+    }
+
+    impl<Tag, F: SerBin> SerBin for TypedFlags<Tag, F> {
+        fn ser_bin(&self, output: &mut Vec<u8>) {
+            self.flags.ser_bin(output)
+        }
+    }
+
+    impl<Tag, F: DeJson> DeJson for TypedFlags<Tag, F> {
+        fn de_json(
+            state: &mut DeJsonState,
+            input: &mut core::str::Chars,
+        ) -> Result<Self, DeJsonErr> {
+            F::de_json(state, input).map(Self::from_flags)
+        }
+    }
+
+    impl<Tag, F: SerJson> SerJson for TypedFlags<Tag, F> {
+        fn ser_json(&self, indent_level: usize, state: &mut SerJsonState) {
+            self.flags.ser_json(indent_level, state)
+        }
+    }
+
+    impl<Tag, F: DeRon> DeRon for TypedFlags<Tag, F> {
+        fn de_ron(state: &mut DeRonState, input: &mut core::str::Chars) -> Result<Self, DeRonErr> {
+            F::de_ron(state, input).map(Self::from_flags)
+        }
+    }
+
+    impl<Tag, F: SerRon> SerRon for TypedFlags<Tag, F> {
+        fn ser_ron(&self, indent_level: usize, state: &mut SerRonState) {
+            self.flags.ser_ron(indent_level, state)
+        }
+    }
+}
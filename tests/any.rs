@@ -0,0 +1,200 @@
+//! Tests for `AnyBitFlags` runtime-width bitflags.
+
+use arctan_bitflags::{
+    AnyBitFlags, BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8, BitFlagsError,
+};
+use nanoserde::{DeBin, DeJson, DeRon, SerBin, SerJson, SerRon};
+
+#[test]
+fn any_width_promotion() {
+    let eight = AnyBitFlags::from(BitFlags8(0b0011));
+    let sixty_four = AnyBitFlags::from(BitFlags64(0b0110 | 1 << 40));
+
+    assert_eq!(eight | sixty_four, AnyBitFlags::SixtyFour(BitFlags64(0b0111 | 1 << 40)));
+    assert_eq!(sixty_four & eight, AnyBitFlags::SixtyFour(BitFlags64(0b0010)));
+    assert_eq!(eight ^ sixty_four, AnyBitFlags::SixtyFour(BitFlags64(0b0101 | 1 << 40)));
+    assert_eq!(eight.difference(sixty_four), AnyBitFlags::SixtyFour(BitFlags64(0b0001)));
+    assert_eq!(!eight, AnyBitFlags::Eight(BitFlags8(!0b0011)));
+
+    let mut flags = AnyBitFlags::default();
+    flags |= AnyBitFlags::from(BitFlags16(0x100));
+    assert_eq!(flags, AnyBitFlags::Sixteen(BitFlags16(0x100)));
+    flags &= eight;
+    assert_eq!(flags, AnyBitFlags::empty(16));
+    flags ^= AnyBitFlags::full(32);
+    assert_eq!(flags.width(), 32);
+    assert!(flags.is_full());
+}
+
+#[test]
+fn any_queries() {
+    let flags = AnyBitFlags::from_bits(128, 0b1010);
+    assert_eq!(flags.width(), 128);
+    assert_eq!(flags.min_width(), 8);
+    assert_eq!(flags.bits(), 0b1010);
+    assert_eq!(flags.count_ones(), 2);
+    assert_eq!(flags.iter().collect::<Vec<_>>(), vec![1, 3]);
+    assert!(flags.contains(AnyBitFlags::from(BitFlags8(0b0010))));
+    assert!(!flags.intersects(AnyBitFlags::from(BitFlags32(0b0101))));
+    assert!(AnyBitFlags::empty(64).is_empty());
+    assert!(!AnyBitFlags::full(128).is_empty());
+    assert_eq!(AnyBitFlags::from_bits(16, 0x100).min_width(), 16);
+    assert_eq!(AnyBitFlags::from_bits(128, 1 << 64).min_width(), 128);
+
+    // Equality is width-sensitive.
+    assert_ne!(flags, AnyBitFlags::from_bits(8, 0b1010));
+    assert_eq!(flags.shrink_to_fit(), AnyBitFlags::from_bits(8, 0b1010));
+}
+
+#[test]
+fn any_indexes() {
+    let mut flags = AnyBitFlags::empty(16);
+    flags.insert_at_index(15);
+    assert!(flags.bit_at_index(15));
+    assert_eq!(flags.get_bit_at_index(16), None);
+    assert_eq!(
+        flags.try_insert_at_index(16),
+        Err(BitFlagsError::IndexOutOfRange { index: 16, bits: 16 })
+    );
+    flags.remove_at_index(15);
+    assert!(flags.try_remove_at_index(20).is_err());
+    assert!(flags.is_empty());
+}
+
+#[test]
+fn any_resize() {
+    let flags = AnyBitFlags::from(BitFlags32(0x1ff));
+    assert_eq!(flags.widen(8), flags);
+    assert_eq!(flags.widen(64), AnyBitFlags::SixtyFour(BitFlags64(0x1ff)));
+    assert_eq!(flags.resize(16), AnyBitFlags::Sixteen(BitFlags16(0x1ff)));
+    assert_eq!(flags.try_resize(8), Err(BitFlagsError::UnknownBits { bits: 0x100 }));
+    assert_eq!(flags.try_resize(24), Err(BitFlagsError::InvalidWidth { width: 24 }));
+    assert_eq!(
+        AnyBitFlags::try_from_bits(8, 0x300),
+        Err(BitFlagsError::UnknownBits { bits: 0x300 })
+    );
+}
+
+#[test]
+#[should_panic]
+fn any_invalid_width_panics() {
+    AnyBitFlags::empty(12);
+}
+
+#[test]
+fn any_conversions() {
+    let flags = AnyBitFlags::from(BitFlags16(0x80));
+    assert_eq!(BitFlags8::try_from(flags), Ok(BitFlags8(0x80)));
+    assert_eq!(BitFlags128::try_from(flags), Ok(BitFlags128(0x80)));
+
+    let flags = AnyBitFlags::from(BitFlags128(1 << 100 | 1));
+    assert_eq!(BitFlags64::try_from(flags), Err(BitFlagsError::UnknownBits { bits: 1 << 100 }));
+    assert_eq!(BitFlags128::try_from(flags), Ok(BitFlags128(1 << 100 | 1)));
+}
+
+#[test]
+fn any_display_and_parse() {
+    let flags = AnyBitFlags::from(BitFlags16(0x10));
+    assert_eq!(flags.to_string(), "BitFlags16(16)");
+    assert_eq!(format!("{:b}", AnyBitFlags::from(BitFlags8(5))), "0b00000101");
+    assert_eq!(flags.display_with(&[("burning", 4)]).to_string(), "burning");
+
+    for flags in [
+        AnyBitFlags::from(BitFlags8(0)),
+        AnyBitFlags::from(BitFlags16(0x10)),
+        AnyBitFlags::from(BitFlags32(7)),
+        AnyBitFlags::from(BitFlags64(u64::MAX)),
+        AnyBitFlags::from(BitFlags128(1 << 127)),
+    ] {
+        assert_eq!(flags.to_string().parse::<AnyBitFlags>(), Ok(flags));
+    }
+    assert_eq!("0x100".parse::<AnyBitFlags>(), Ok(AnyBitFlags::from(BitFlags16(0x100))));
+    assert!("BitFlags8(256)".parse::<AnyBitFlags>().is_err());
+}
+
+#[test]
+fn any_serde() {
+    let mixed = vec![
+        AnyBitFlags::from(BitFlags8(5)),
+        AnyBitFlags::from(BitFlags64(5)),
+        AnyBitFlags::from(BitFlags128(1 << 100)),
+    ];
+
+    let json = serde_json::to_string(&mixed).unwrap();
+    assert_eq!(
+        json,
+        r#"[{"Eight":5},{"SixtyFour":5},{"OneTwentyEight":1267650600228229401496703205376}]"#
+    );
+    assert_eq!(serde_json::from_str::<Vec<AnyBitFlags>>(&json).unwrap(), mixed);
+
+    let ron = ron::to_string(&mixed).unwrap();
+    assert_eq!(ron::from_str::<Vec<AnyBitFlags>>(&ron).unwrap(), mixed);
+    assert_eq!(
+        ron::from_str::<AnyBitFlags>("Sixteen(3)").unwrap(),
+        AnyBitFlags::from(BitFlags16(3))
+    );
+    assert!(serde_json::from_str::<AnyBitFlags>(r#"{"Eight":256}"#).is_err());
+}
+
+#[test]
+fn any_nanoserde() {
+    let mixed = vec![
+        AnyBitFlags::from(BitFlags8(5)),
+        AnyBitFlags::from(BitFlags32(5)),
+        AnyBitFlags::from(BitFlags128(1 << 100)),
+    ];
+
+    let bytes = SerBin::serialize_bin(&mixed);
+    assert_eq!(Vec::<AnyBitFlags>::deserialize_bin(&bytes).unwrap(), mixed);
+
+    let bytes = SerBin::serialize_bin(&AnyBitFlags::from(BitFlags16(0x102)));
+    assert_eq!(bytes, vec![16, 0x02, 0x01]);
+    assert!(AnyBitFlags::deserialize_bin(&[24, 0]).is_err());
+    assert!(AnyBitFlags::deserialize_bin(&[64, 0]).is_err());
+}
+
+#[test]
+fn any_nanoserde_text() {
+    let mixed = vec![
+        AnyBitFlags::from(BitFlags8(5)),
+        AnyBitFlags::from(BitFlags64(u64::MAX)),
+        AnyBitFlags::from(BitFlags128(1 << 100)),
+    ];
+
+    let json = mixed.serialize_json();
+    assert_eq!(
+        json,
+        concat!(
+            r#"[{"width":8,"bits":5},{"width":64,"bits":18446744073709551615},"#,
+            r#"{"width":128,"bits":"1267650600228229401496703205376"}]"#
+        )
+    );
+    assert_eq!(Vec::<AnyBitFlags>::deserialize_json(&json).unwrap(), mixed);
+    assert_eq!(
+        AnyBitFlags::deserialize_json(r#"{"bits": 3, "width": 16}"#).unwrap(),
+        AnyBitFlags::from(BitFlags16(3))
+    );
+    assert_eq!(
+        AnyBitFlags::deserialize_json(r#"{"width":32,"bits":"7"}"#).unwrap(),
+        AnyBitFlags::from(BitFlags32(7))
+    );
+
+    let ron = mixed.serialize_ron();
+    assert_eq!(Vec::<AnyBitFlags>::deserialize_ron(&ron).unwrap(), mixed);
+    assert_eq!(
+        AnyBitFlags::deserialize_ron("(width: 16, bits: 3)").unwrap(),
+        AnyBitFlags::from(BitFlags16(3))
+    );
+    let full = format!("(width: 128, bits: \"{}\")", u128::MAX);
+    assert_eq!(AnyBitFlags::deserialize_ron(&full).unwrap(), AnyBitFlags::full(128));
+    assert_eq!(
+        AnyBitFlags::from(BitFlags16(3)).serialize_ron(),
+        "(\n    width:16,\n    bits:3,\n)"
+    );
+
+    assert!(AnyBitFlags::deserialize_json(r#"{"width":8,"bits":256}"#).is_err());
+    assert!(AnyBitFlags::deserialize_json(r#"{"width":24,"bits":1}"#).is_err());
+    assert!(AnyBitFlags::deserialize_json(r#"{"width":8}"#).is_err());
+    assert!(AnyBitFlags::deserialize_json(r#"{"width":8,"bits":1,"extra":2}"#).is_err());
+    assert!(AnyBitFlags::deserialize_ron("(width: 8, bits: -1)").is_err());
+}
@@ -0,0 +1,149 @@
+//! Tests for generating flag constants, checked against (and compiled from) `codegen/status.rs`.
+
+use arctan_bitflags::{BitFlags64, Codegen, CodegenError, SchemaErrorKind};
+use std::path::PathBuf;
+
+// Deprecated flags must not make the generated code itself warn, so only `dead_code` is allowed.
+#[allow(dead_code)]
+mod status {
+    include!("codegen/status.rs");
+}
+
+use status::{Status, BURNING, ELEMENTAL, POISONED, STATUS_NAMES};
+
+/// A directory in the temporary directory, unique to one test, removed (with its files) on drop.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(test: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("arctan-bitflags-{}-{}", std::process::id(), test));
+        std::fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+    fn path(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+    /// Writes `text` to a file named `name` in the directory.
+    fn file(&self, name: &str, text: &str) -> PathBuf {
+        let path = self.path(name);
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn codegen_matches_fixture() {
+    let generated = Codegen::new("tests/codegen/status.ron")
+        .type_name("Status")
+        .generate()
+        .unwrap();
+    assert_eq!(generated, std::fs::read_to_string("tests/codegen/status.rs").unwrap());
+}
+
+#[test]
+#[allow(deprecated)]
+fn codegen_generated_api() {
+    assert_eq!(BURNING, BitFlags64(0b0001));
+    assert_eq!(POISONED, BitFlags64(0b1_0000));
+    assert_eq!(ELEMENTAL, BitFlags64(0b0011));
+    assert_eq!(STATUS_NAMES[3], ("poisoned", 4));
+
+    assert_eq!(Status::Poisoned.index(), 4);
+    assert_eq!(Status::Poisoned.flag(), POISONED);
+    assert_eq!(BitFlags64::from(Status::Frozen), BitFlags64(0b0010));
+    assert_eq!(Status::from_index(2), Some(Status::Stunned));
+    assert_eq!(Status::from_index(3), None);
+    assert_eq!(Status::ALL.len(), 4);
+
+    assert_eq!(Status::Burning.to_string(), "burning");
+    assert_eq!("toxic".parse::<Status>(), Ok(Status::Poisoned));
+    assert_eq!("frozen".parse::<Status>(), Ok(Status::Frozen));
+    assert_eq!("FROZEN".parse::<Status>(), Err(status::ParseStatusError));
+}
+
+#[test]
+fn codegen_name_list() {
+    let dir = TempDir::new("name_list");
+    let path = dir.file("list.txt", "# Status effects\nonFire\n\nwet = 5  # explicit\nbad_luck\n");
+    let generated = Codegen::new(&path).type_name("Effect").width(8).generate().unwrap();
+    assert!(generated.contains(
+        "pub const ON_FIRE: ::arctan_bitflags::BitFlags8 = ::arctan_bitflags::BitFlags8(0x1);"
+    ));
+    assert!(generated.contains(
+        "pub const WET: ::arctan_bitflags::BitFlags8 = ::arctan_bitflags::BitFlags8(0x20);"
+    ));
+    assert!(generated.contains("    BadLuck = 1,"));
+    assert!(generated.contains("pub const EFFECT_NAMES: [(&str, usize); 3]"));
+
+    let path = dir.file("collide.txt", "a\nb\na\nc = 9\n");
+    match Codegen::new(&path).width(8).generate() {
+        Err(CodegenError::Schema(errors)) => {
+            let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+            assert_eq!(paths, vec!["line 4", "line 3"]);
+            assert_eq!(errors[0].kind, SchemaErrorKind::IndexOverflow { index: 9, width: 8 });
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let path = dir.file("bad_index.txt", "a\nb = x\n");
+    let err = Codegen::new(&path).generate().unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("failed to parse flags: line 2: invalid index \"x\""));
+}
+
+#[test]
+fn codegen_ident_errors() {
+    let dir = TempDir::new("ident_errors");
+    let path = dir.file("idents.txt", "fooBar\nfoo_bar\n");
+    match Codegen::new(&path).generate() {
+        Err(CodegenError::IdentCollision { ident, first, second }) => {
+            assert_eq!(
+                (ident.as_str(), first.as_str(), second.as_str()),
+                ("FOO_BAR", "fooBar", "foo_bar")
+            );
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let path = dir.file("keyword.txt", "self\n");
+    assert!(matches!(Codegen::new(&path).generate(), Err(CodegenError::InvalidIdent { .. })));
+    let path = dir.file("match.txt", "match\nloop\n");
+    match Codegen::new(&path).type_name("type").generate() {
+        Err(CodegenError::InvalidIdent { name }) => assert_eq!(name, "type"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(matches!(
+        Codegen::new(&path).type_name("Self").generate(),
+        Err(CodegenError::InvalidIdent { .. })
+    ));
+    // Flag names become `MATCH` and `Match`, which are not keywords.
+    assert!(Codegen::new(&path).type_name("Keyword").generate().is_ok());
+    let path = dir.file("empty.txt", "# nothing\n");
+    assert!(matches!(Codegen::new(&path).generate(), Err(CodegenError::NoFlags)));
+    assert!(matches!(
+        Codegen::new("tests/codegen/missing.ron").generate(),
+        Err(CodegenError::Io(_))
+    ));
+}
+
+#[test]
+fn codegen_write_only_on_change() {
+    let dir = TempDir::new("write_only_on_change");
+    let input = dir.file("write.txt", "a\nb\n");
+    let output = dir.path("write.rs");
+
+    let codegen = Codegen::new(&input);
+    assert!(codegen.write_to(&output).unwrap());
+    assert!(!codegen.write_to(&output).unwrap());
+    std::fs::write(&input, "a\nb\nc\n").unwrap();
+    assert!(codegen.write_to(&output).unwrap());
+    assert!(std::fs::read_to_string(&output).unwrap().contains("pub const C:"));
+}
@@ -0,0 +1,116 @@
+// @generated by arctan-bitflags from `status.ron`. Do not edit by hand.
+
+/// Takes fire damage each turn.
+pub const BURNING: ::arctan_bitflags::BitFlags64 = ::arctan_bitflags::BitFlags64(0x1);
+
+pub const FROZEN: ::arctan_bitflags::BitFlags64 = ::arctan_bitflags::BitFlags64(0x2);
+
+#[deprecated]
+pub const STUNNED: ::arctan_bitflags::BitFlags64 = ::arctan_bitflags::BitFlags64(0x4);
+
+pub const POISONED: ::arctan_bitflags::BitFlags64 = ::arctan_bitflags::BitFlags64(0x10);
+/// Deprecated alias (`toxic`) of `POISONED`.
+#[deprecated(note = "renamed to `POISONED`")]
+pub const TOXIC: ::arctan_bitflags::BitFlags64 = ::arctan_bitflags::BitFlags64(0x10);
+
+/// The `elemental` group.
+pub const ELEMENTAL: ::arctan_bitflags::BitFlags64 = ::arctan_bitflags::BitFlags64(0x3);
+
+/// The name and bit index of every flag, in index order.
+pub const STATUS_NAMES: [(&str, usize); 4] = [
+    ("burning", 0),
+    ("frozen", 1),
+    ("stunned", 2),
+    ("poisoned", 4),
+];
+
+/// The bit index of each flag.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Status {
+    /// Takes fire damage each turn.
+    Burning = 0,
+    Frozen = 1,
+    #[deprecated]
+    Stunned = 2,
+    Poisoned = 4,
+}
+
+#[allow(deprecated)]
+impl Status {
+    /// Every flag, in index order.
+    pub const ALL: [Status; 4] = [
+        Status::Burning,
+        Status::Frozen,
+        Status::Stunned,
+        Status::Poisoned,
+    ];
+
+    /// Returns the flag's bit index.
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+    /// Returns the flag as bitflags.
+    pub const fn flag(self) -> ::arctan_bitflags::BitFlags64 {
+        ::arctan_bitflags::BitFlags64(1 << (self as u32))
+    }
+    /// Returns the flag's name.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Status::Burning => "burning",
+            Status::Frozen => "frozen",
+            Status::Stunned => "stunned",
+            Status::Poisoned => "poisoned",
+        }
+    }
+    /// Returns the flag at bit `index`.
+    pub const fn from_index(index: usize) -> Option<Status> {
+        match index {
+            0 => Some(Status::Burning),
+            1 => Some(Status::Frozen),
+            2 => Some(Status::Stunned),
+            4 => Some(Status::Poisoned),
+            _ => None,
+        }
+    }
+    /// Returns the flag named (or aliased) `name`.
+    pub fn from_name(name: &str) -> Option<Status> {
+        match name {
+            "burning" => Some(Status::Burning),
+            "frozen" => Some(Status::Frozen),
+            "stunned" => Some(Status::Stunned),
+            "poisoned" | "toxic" => Some(Status::Poisoned),
+            _ => None,
+        }
+    }
+}
+
+impl From<Status> for ::arctan_bitflags::BitFlags64 {
+    fn from(flag: Status) -> Self {
+        flag.flag()
+    }
+}
+
+impl ::core::fmt::Display for Status {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The error returned when parsing a name that is not a `Status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseStatusError;
+
+impl ::core::fmt::Display for ParseStatusError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str("unknown Status name")
+    }
+}
+
+impl ::core::str::FromStr for Status {
+    type Err = ParseStatusError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Status::from_name(s).ok_or(ParseStatusError)
+    }
+}
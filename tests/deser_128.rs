@@ -5,7 +5,7 @@
 //! _Note_: (de)serializing `BitFlags128` with `serde` + `ron` requires the `ron` crate's 
 //! `"integer128"` feature.
 
-use arctan_bitflags::BitFlags128;
+use arctan_bitflags::{BitFlags128, NonEmptyBitFlags128};
 use nanoserde::{DeBin, SerBin};
 
 #[rustfmt::skip]
@@ -36,7 +36,7 @@ fn bitflags128_serde() {
     assert_eq!(json_actual, json_expected);
 
     // Deserialize (JSON)
-    let array_actual: [BitFlags128; 4] = serde_json::from_str(&FLAG_JSON).unwrap();
+    let array_actual: [BitFlags128; 4] = serde_json::from_str(FLAG_JSON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
@@ -48,7 +48,7 @@ fn bitflags128_serde() {
     assert_eq!(ron_actual, ron_expected);
 
     // Deserialize (RON)
-    let array_actual: [BitFlags128; 4] = ron::from_str(&FLAG_RON).unwrap();
+    let array_actual: [BitFlags128; 4] = ron::from_str(FLAG_RON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);      
@@ -57,7 +57,7 @@ fn bitflags128_serde() {
 #[test]
 fn bitflags128_nanoserde_de() {
     // Deserialize (BIN)
-    let array_actual = <[BitFlags128; 4]>::deserialize_bin(&FLAG_BIN).unwrap();
+    let array_actual = <[BitFlags128; 4]>::deserialize_bin(FLAG_BIN).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
@@ -71,3 +71,28 @@ fn bitflags128_nanoserde_ser() {
 
     assert_eq!(bin_actual, bin_expected);
 }
+
+#[test]
+fn bitflags128_non_empty_serde() {
+    let array = [NonEmptyBitFlags128::from_index(0), NonEmptyBitFlags128::full()];
+    let json = serde_json::to_string(&array).unwrap();
+    let ron = ron::to_string(&array).unwrap();
+
+    assert_eq!(serde_json::from_str::<[NonEmptyBitFlags128; 2]>(&json).unwrap(), array);
+    assert_eq!(ron::from_str::<[NonEmptyBitFlags128; 2]>(&ron).unwrap(), array);
+
+    // Zero is rejected
+    assert!(serde_json::from_str::<NonEmptyBitFlags128>("0").is_err());
+    assert!(ron::from_str::<NonEmptyBitFlags128>("0").is_err());
+}
+
+#[test]
+fn bitflags128_non_empty_nanoserde() {
+    let array = [NonEmptyBitFlags128::from_index(0), NonEmptyBitFlags128::full()];
+    let bin = <[NonEmptyBitFlags128; 2]>::serialize_bin(&array);
+
+    assert_eq!(<[NonEmptyBitFlags128; 2]>::deserialize_bin(&bin).unwrap(), array);
+
+    // Zero is rejected
+    assert!(NonEmptyBitFlags128::deserialize_bin(&[0; 16]).is_err());
+}
@@ -4,7 +4,7 @@
 //! 
 //! _Note_: `nanoserde` serializes RON with spaces while `serde`+`ron` does not.
 
-use arctan_bitflags::BitFlags16;
+use arctan_bitflags::{BitFlags16, NonEmptyBitFlags16};
 use nanoserde::{DeBin, DeJson, DeRon, SerBin, SerJson, SerRon};
 
 #[rustfmt::skip]
@@ -37,7 +37,7 @@ fn bitflags16_serde() {
     assert_eq!(json_actual, json_expected);
 
     // Deserialize (JSON)
-    let array_actual: [BitFlags16; 4] = serde_json::from_str(&FLAG_JSON).unwrap();
+    let array_actual: [BitFlags16; 4] = serde_json::from_str(FLAG_JSON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
@@ -49,7 +49,7 @@ fn bitflags16_serde() {
     assert_eq!(ron_actual, ron_expected);
 
     // Deserialize (RON)
-    let array_actual: [BitFlags16; 4] = ron::from_str(&FLAG_RON).unwrap();
+    let array_actual: [BitFlags16; 4] = ron::from_str(FLAG_RON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);    
@@ -58,19 +58,19 @@ fn bitflags16_serde() {
 #[test]
 fn bitflags16_nanoserde_de() {
     // Deserialize (BIN)
-    let array_actual = <[BitFlags16; 4]>::deserialize_bin(&FLAG_BIN).unwrap();
+    let array_actual = <[BitFlags16; 4]>::deserialize_bin(FLAG_BIN).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
 
     // Deserialize (JSON)
-    let array_actual = <[BitFlags16; 4]>::deserialize_json(&FLAG_JSON).unwrap();
+    let array_actual = <[BitFlags16; 4]>::deserialize_json(FLAG_JSON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
 
     // Deserialize (RON)
-    let array_actual = <[BitFlags16; 4]>::deserialize_ron(&FLAG_RON_NS).unwrap();
+    let array_actual = <[BitFlags16; 4]>::deserialize_ron(FLAG_RON_NS).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
@@ -97,3 +97,34 @@ fn bitflags16_nanoserde_ser() {
 
     assert_eq!(ron_actual, ron_expected);
 }
+
+#[test]
+fn bitflags16_non_empty_serde() {
+    let array = [NonEmptyBitFlags16::from_index(0), NonEmptyBitFlags16::full()];
+    let json = serde_json::to_string(&array).unwrap();
+    let ron = ron::to_string(&array).unwrap();
+
+    assert_eq!(serde_json::from_str::<[NonEmptyBitFlags16; 2]>(&json).unwrap(), array);
+    assert_eq!(ron::from_str::<[NonEmptyBitFlags16; 2]>(&ron).unwrap(), array);
+
+    // Zero is rejected
+    assert!(serde_json::from_str::<NonEmptyBitFlags16>("0").is_err());
+    assert!(ron::from_str::<NonEmptyBitFlags16>("0").is_err());
+}
+
+#[test]
+fn bitflags16_non_empty_nanoserde() {
+    let array = [NonEmptyBitFlags16::from_index(0), NonEmptyBitFlags16::full()];
+    let bin = <[NonEmptyBitFlags16; 2]>::serialize_bin(&array);
+    let json = <[NonEmptyBitFlags16; 2]>::serialize_json(&array);
+    let ron = <[NonEmptyBitFlags16; 2]>::serialize_ron(&array);
+
+    assert_eq!(<[NonEmptyBitFlags16; 2]>::deserialize_bin(&bin).unwrap(), array);
+    assert_eq!(<[NonEmptyBitFlags16; 2]>::deserialize_json(&json).unwrap(), array);
+    assert_eq!(<[NonEmptyBitFlags16; 2]>::deserialize_ron(&ron).unwrap(), array);
+
+    // Zero is rejected
+    assert!(NonEmptyBitFlags16::deserialize_bin(&[0; 2]).is_err());
+    assert!(NonEmptyBitFlags16::deserialize_json("0").is_err());
+    assert!(NonEmptyBitFlags16::deserialize_ron("0").is_err());
+}
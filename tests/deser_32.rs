@@ -4,7 +4,7 @@
 //!
 //! _Note_: `nanoserde` serializes RON with spaces while `serde`+`ron` does not.
 
-use arctan_bitflags::BitFlags32;
+use arctan_bitflags::{BitFlags32, NonEmptyBitFlags32};
 use nanoserde::{DeBin, DeJson, DeRon, SerBin, SerJson, SerRon};
 
 #[rustfmt::skip]
@@ -37,7 +37,7 @@ fn bitflags32_serde() {
     assert_eq!(json_actual, json_expected);
 
     // Deserialize (JSON)
-    let array_actual: [BitFlags32; 4] = serde_json::from_str(&FLAG_JSON).unwrap();
+    let array_actual: [BitFlags32; 4] = serde_json::from_str(FLAG_JSON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
@@ -49,7 +49,7 @@ fn bitflags32_serde() {
     assert_eq!(ron_actual, ron_expected);
 
     // Deserialize (RON)
-    let array_actual: [BitFlags32; 4] = ron::from_str(&FLAG_RON).unwrap();
+    let array_actual: [BitFlags32; 4] = ron::from_str(FLAG_RON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
@@ -58,19 +58,19 @@ fn bitflags32_serde() {
 #[test]
 fn bitflags32_nanoserde_de() {
     // Deserialize (BIN)
-    let array_actual = <[BitFlags32; 4]>::deserialize_bin(&FLAG_BIN).unwrap();
+    let array_actual = <[BitFlags32; 4]>::deserialize_bin(FLAG_BIN).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
 
     // Deserialize (JSON)
-    let array_actual = <[BitFlags32; 4]>::deserialize_json(&FLAG_JSON).unwrap();
+    let array_actual = <[BitFlags32; 4]>::deserialize_json(FLAG_JSON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
 
     // Deserialize (RON)
-    let array_actual = <[BitFlags32; 4]>::deserialize_ron(&FLAG_RON_NS).unwrap();
+    let array_actual = <[BitFlags32; 4]>::deserialize_ron(FLAG_RON_NS).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
@@ -96,3 +96,34 @@ fn bitflags32_nanoserde_ser() {
 
     assert_eq!(ron_actual, ron_expected);
 }
+
+#[test]
+fn bitflags32_non_empty_serde() {
+    let array = [NonEmptyBitFlags32::from_index(0), NonEmptyBitFlags32::full()];
+    let json = serde_json::to_string(&array).unwrap();
+    let ron = ron::to_string(&array).unwrap();
+
+    assert_eq!(serde_json::from_str::<[NonEmptyBitFlags32; 2]>(&json).unwrap(), array);
+    assert_eq!(ron::from_str::<[NonEmptyBitFlags32; 2]>(&ron).unwrap(), array);
+
+    // Zero is rejected
+    assert!(serde_json::from_str::<NonEmptyBitFlags32>("0").is_err());
+    assert!(ron::from_str::<NonEmptyBitFlags32>("0").is_err());
+}
+
+#[test]
+fn bitflags32_non_empty_nanoserde() {
+    let array = [NonEmptyBitFlags32::from_index(0), NonEmptyBitFlags32::full()];
+    let bin = <[NonEmptyBitFlags32; 2]>::serialize_bin(&array);
+    let json = <[NonEmptyBitFlags32; 2]>::serialize_json(&array);
+    let ron = <[NonEmptyBitFlags32; 2]>::serialize_ron(&array);
+
+    assert_eq!(<[NonEmptyBitFlags32; 2]>::deserialize_bin(&bin).unwrap(), array);
+    assert_eq!(<[NonEmptyBitFlags32; 2]>::deserialize_json(&json).unwrap(), array);
+    assert_eq!(<[NonEmptyBitFlags32; 2]>::deserialize_ron(&ron).unwrap(), array);
+
+    // Zero is rejected
+    assert!(NonEmptyBitFlags32::deserialize_bin(&[0; 4]).is_err());
+    assert!(NonEmptyBitFlags32::deserialize_json("0").is_err());
+    assert!(NonEmptyBitFlags32::deserialize_ron("0").is_err());
+}
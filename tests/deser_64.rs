@@ -4,7 +4,7 @@
 //! 
 //! _Note_: `nanoserde` serializes RON with spaces while `serde`+`ron` does not.
 
-use arctan_bitflags::BitFlags64;
+use arctan_bitflags::{BitFlags64, NonEmptyBitFlags64};
 use nanoserde::{DeBin, DeJson, DeRon, SerBin, SerJson, SerRon};
 
 #[rustfmt::skip]
@@ -37,7 +37,7 @@ fn bitflags64_serde() {
     assert_eq!(json_actual, json_expected);
 
     // Deserialize (JSON)
-    let array_actual: [BitFlags64; 4] = serde_json::from_str(&FLAG_JSON).unwrap();
+    let array_actual: [BitFlags64; 4] = serde_json::from_str(FLAG_JSON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
@@ -49,7 +49,7 @@ fn bitflags64_serde() {
     assert_eq!(ron_actual, ron_expected);
 
     // Deserialize (RON)
-    let array_actual: [BitFlags64; 4] = ron::from_str(&FLAG_RON).unwrap();
+    let array_actual: [BitFlags64; 4] = ron::from_str(FLAG_RON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);      
@@ -58,19 +58,19 @@ fn bitflags64_serde() {
 #[test]
 fn bitflags64_nanoserde_de() {
     // Deserialize (BIN)
-    let array_actual = <[BitFlags64; 4]>::deserialize_bin(&FLAG_BIN).unwrap();
+    let array_actual = <[BitFlags64; 4]>::deserialize_bin(FLAG_BIN).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
 
     // Deserialize (JSON)
-    let array_actual = <[BitFlags64; 4]>::deserialize_json(&FLAG_JSON).unwrap();
+    let array_actual = <[BitFlags64; 4]>::deserialize_json(FLAG_JSON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
 
     // Deserialize (RON)
-    let array_actual = <[BitFlags64; 4]>::deserialize_ron(&FLAG_RON_NS).unwrap();
+    let array_actual = <[BitFlags64; 4]>::deserialize_ron(FLAG_RON_NS).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
@@ -96,3 +96,34 @@ fn bitflags64_nanoserde_ser() {
 
     assert_eq!(ron_actual, ron_expected);
 }
+
+#[test]
+fn bitflags64_non_empty_serde() {
+    let array = [NonEmptyBitFlags64::from_index(0), NonEmptyBitFlags64::full()];
+    let json = serde_json::to_string(&array).unwrap();
+    let ron = ron::to_string(&array).unwrap();
+
+    assert_eq!(serde_json::from_str::<[NonEmptyBitFlags64; 2]>(&json).unwrap(), array);
+    assert_eq!(ron::from_str::<[NonEmptyBitFlags64; 2]>(&ron).unwrap(), array);
+
+    // Zero is rejected
+    assert!(serde_json::from_str::<NonEmptyBitFlags64>("0").is_err());
+    assert!(ron::from_str::<NonEmptyBitFlags64>("0").is_err());
+}
+
+#[test]
+fn bitflags64_non_empty_nanoserde() {
+    let array = [NonEmptyBitFlags64::from_index(0), NonEmptyBitFlags64::full()];
+    let bin = <[NonEmptyBitFlags64; 2]>::serialize_bin(&array);
+    let json = <[NonEmptyBitFlags64; 2]>::serialize_json(&array);
+    let ron = <[NonEmptyBitFlags64; 2]>::serialize_ron(&array);
+
+    assert_eq!(<[NonEmptyBitFlags64; 2]>::deserialize_bin(&bin).unwrap(), array);
+    assert_eq!(<[NonEmptyBitFlags64; 2]>::deserialize_json(&json).unwrap(), array);
+    assert_eq!(<[NonEmptyBitFlags64; 2]>::deserialize_ron(&ron).unwrap(), array);
+
+    // Zero is rejected
+    assert!(NonEmptyBitFlags64::deserialize_bin(&[0; 8]).is_err());
+    assert!(NonEmptyBitFlags64::deserialize_json("0").is_err());
+    assert!(NonEmptyBitFlags64::deserialize_ron("0").is_err());
+}
@@ -4,7 +4,7 @@
 //! 
 //! _Note_: `nanoserde` serializes RON with spaces while `serde`+`ron` does not.
 
-use arctan_bitflags::BitFlags8;
+use arctan_bitflags::{BitFlags8, NonEmptyBitFlags8};
 use nanoserde::{DeBin, DeJson, DeRon, SerBin, SerJson, SerRon};
 
 const FLAG_BIN: &[u8] = &[0, 1, 2, 3, 4, 8, 16, 32, 64, 128, 255];
@@ -38,7 +38,7 @@ fn bitflags8_serde() {
     assert_eq!(json_actual, json_expected);
 
     // Deserialize (JSON)
-    let array_actual: [BitFlags8; 11] = serde_json::from_str(&FLAG_JSON).unwrap();
+    let array_actual: [BitFlags8; 11] = serde_json::from_str(FLAG_JSON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
@@ -50,7 +50,7 @@ fn bitflags8_serde() {
     assert_eq!(ron_actual, ron_expected);
 
     // Deserialize (RON)
-    let array_actual: [BitFlags8; 11] = ron::from_str(&FLAG_RON).unwrap();
+    let array_actual: [BitFlags8; 11] = ron::from_str(FLAG_RON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);    
@@ -59,19 +59,19 @@ fn bitflags8_serde() {
 #[test]
 fn bitflags8_nanoserde_de() {
     // Deserialize (BIN)
-    let array_actual = <[BitFlags8; 11]>::deserialize_bin(&FLAG_BIN).unwrap();
+    let array_actual = <[BitFlags8; 11]>::deserialize_bin(FLAG_BIN).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
 
     // Deserialize (JSON)
-    let array_actual = <[BitFlags8; 11]>::deserialize_json(&FLAG_JSON).unwrap();
+    let array_actual = <[BitFlags8; 11]>::deserialize_json(FLAG_JSON).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
 
     // Deserialize (RON)
-    let array_actual = <[BitFlags8; 11]>::deserialize_ron(&FLAG_RON_NS).unwrap();
+    let array_actual = <[BitFlags8; 11]>::deserialize_ron(FLAG_RON_NS).unwrap();
     let array_expected = FLAG_ARRAY;
 
     assert_eq!(array_actual, array_expected);
@@ -97,3 +97,34 @@ fn bitflags8_nanoserde_ser() {
 
     assert_eq!(ron_actual, ron_expected);
 }
+
+#[test]
+fn bitflags8_non_empty_serde() {
+    let array = [NonEmptyBitFlags8::from_index(0), NonEmptyBitFlags8::full()];
+    let json = serde_json::to_string(&array).unwrap();
+    let ron = ron::to_string(&array).unwrap();
+
+    assert_eq!(serde_json::from_str::<[NonEmptyBitFlags8; 2]>(&json).unwrap(), array);
+    assert_eq!(ron::from_str::<[NonEmptyBitFlags8; 2]>(&ron).unwrap(), array);
+
+    // Zero is rejected
+    assert!(serde_json::from_str::<NonEmptyBitFlags8>("0").is_err());
+    assert!(ron::from_str::<NonEmptyBitFlags8>("0").is_err());
+}
+
+#[test]
+fn bitflags8_non_empty_nanoserde() {
+    let array = [NonEmptyBitFlags8::from_index(0), NonEmptyBitFlags8::full()];
+    let bin = <[NonEmptyBitFlags8; 2]>::serialize_bin(&array);
+    let json = <[NonEmptyBitFlags8; 2]>::serialize_json(&array);
+    let ron = <[NonEmptyBitFlags8; 2]>::serialize_ron(&array);
+
+    assert_eq!(<[NonEmptyBitFlags8; 2]>::deserialize_bin(&bin).unwrap(), array);
+    assert_eq!(<[NonEmptyBitFlags8; 2]>::deserialize_json(&json).unwrap(), array);
+    assert_eq!(<[NonEmptyBitFlags8; 2]>::deserialize_ron(&ron).unwrap(), array);
+
+    // Zero is rejected
+    assert!(NonEmptyBitFlags8::deserialize_bin(&[0; 1]).is_err());
+    assert!(NonEmptyBitFlags8::deserialize_json("0").is_err());
+    assert!(NonEmptyBitFlags8::deserialize_ron("0").is_err());
+}
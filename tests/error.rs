@@ -0,0 +1,59 @@
+//! Tests for the `BitFlagsError` enum.
+
+use arctan_bitflags::{BitFlags16, BitFlags8, BitFlagsError};
+use std::error::Error;
+
+#[test]
+fn bitflags_error_display() {
+    let err = BitFlags8::try_from_index(8).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "bit index 8 is out of range: BitFlags8 are indexed from 0 to 7"
+    );
+
+    let err = BitFlags16::try_from_range(4..17).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "bit range 4..17 is invalid: BitFlags16 ranges must lie within 0..16, and start must be <= end"
+    );
+
+    let err = BitFlags8::new().try_set_field(0..=1, 4).unwrap_err();
+    assert_eq!(err.to_string(), "value 4 does not fit in field (max 3)");
+
+    let err = BitFlagsError::UnknownBits { bits: 0xf0 };
+    assert_eq!(err.to_string(), "unknown bits set: 0xf0");
+
+    let err = BitFlagsError::NotAPermutation { index: 3 };
+    assert_eq!(err.to_string(), "not a permutation: bit 3 is the target of more than one bit");
+
+    let err = BitFlagsError::InvalidWidth { width: 24 };
+    assert_eq!(
+        err.to_string(),
+        "width 24 is invalid: BitFlags are 8, 16, 32, 64, or 128 bits wide"
+    );
+
+    let err = BitFlagsError::InvalidTableLength { len: 6 };
+    assert_eq!(err.to_string(), "table length 6 is invalid: subset tables have 2^n entries");
+
+    let err = BitFlagsError::TableLengthMismatch { len: 4, expected: 8 };
+    assert_eq!(err.to_string(), "table length 4 does not match the other table's length 8");
+}
+
+#[test]
+fn bitflags_error_display_zero_bounds() {
+    let err = BitFlagsError::IndexOutOfRange { index: 0, bits: 0 };
+    assert!(err.to_string().starts_with("bit index 0 is out of range"));
+
+    let err = BitFlagsError::LaneOutOfRange { lane: 0, lanes: 0 };
+    assert!(err.to_string().starts_with("lane 0 is out of range"));
+}
+
+#[test]
+fn bitflags_error_source() {
+    let err = "nope".parse::<BitFlags8>().unwrap_err();
+    assert!(err.to_string().starts_with("failed to parse bitflags: "));
+    assert!(err.source().is_some());
+
+    let err = BitFlags8::try_from_index(8).unwrap_err();
+    assert!(err.source().is_none());
+}
@@ -0,0 +1,79 @@
+//! Tests for splitting and joining bitflags through the `Lanes` trait.
+
+use arctan_bitflags::{BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8, BitFlagsError};
+
+#[test]
+fn lanes_split_join() {
+    let flags = BitFlags64(0x0123_4567_89ab_cdef);
+
+    let lanes16 = flags.split::<BitFlags16>();
+    assert_eq!(
+        lanes16,
+        [
+            BitFlags16(0xcdef),
+            BitFlags16(0x89ab),
+            BitFlags16(0x4567),
+            BitFlags16(0x0123)
+        ]
+    );
+    assert_eq!(BitFlags64::join::<BitFlags16>(lanes16), flags);
+
+    let lanes8: [BitFlags8; 8] = flags.into();
+    assert_eq!(lanes8[0], BitFlags8(0xef));
+    assert_eq!(lanes8[7], BitFlags8(0x01));
+    assert_eq!(BitFlags64::from(lanes8), flags);
+
+    let lanes32: [BitFlags32; 2] = flags.into();
+    assert_eq!(lanes32, [BitFlags32(0x89ab_cdef), BitFlags32(0x0123_4567)]);
+
+    let wide = BitFlags128(u128::MAX - 1);
+    let lanes64 = wide.split::<BitFlags64>();
+    assert_eq!(lanes64, [BitFlags64(u64::MAX - 1), BitFlags64::full()]);
+    assert_eq!(BitFlags128::from(lanes64), wide);
+    assert_eq!(BitFlags128::from(wide.split::<BitFlags8>()), wide);
+
+    let narrow = BitFlags16(0xbeef);
+    assert_eq!(narrow.split::<BitFlags8>(), [BitFlags8(0xef), BitFlags8(0xbe)]);
+    assert_eq!(BitFlags32::join::<BitFlags16>([narrow, narrow]), BitFlags32(0xbeef_beef));
+}
+
+#[test]
+fn lanes_lane_access() {
+    let mut flags = BitFlags64(0x0123_4567_89ab_cdef);
+
+    assert_eq!(flags.lane::<BitFlags16>(1), BitFlags16(0x89ab));
+    assert_eq!(flags.lane::<BitFlags8>(7), BitFlags8(0x01));
+    assert_eq!(flags.lane::<BitFlags32>(1), BitFlags32(0x0123_4567));
+
+    flags.set_lane(1, BitFlags16(0));
+    assert_eq!(flags, BitFlags64(0x0123_4567_0000_cdef));
+    flags.set_lane(7, BitFlags8(0xff));
+    assert_eq!(flags, BitFlags64(0xff23_4567_0000_cdef));
+
+    let mut wide = BitFlags128::new();
+    wide.set_lane(15, BitFlags8(0x80));
+    assert_eq!(wide, BitFlags128::from_index(127));
+}
+
+#[test]
+fn lanes_try_lane() {
+    let mut flags = BitFlags32(0xffff_0000);
+
+    assert_eq!(flags.try_lane::<BitFlags16>(1), Ok(BitFlags16(0xffff)));
+    assert_eq!(
+        flags.try_lane::<BitFlags16>(2),
+        Err(BitFlagsError::LaneOutOfRange { lane: 2, lanes: 2 })
+    );
+    assert!(flags.try_set_lane(4, BitFlags8(1)).is_err());
+    assert_eq!(flags, BitFlags32(0xffff_0000));
+    assert_eq!(flags.try_set_lane(0, BitFlags8(1)), Ok(()));
+    assert_eq!(flags, BitFlags32(0xffff_0001));
+}
+
+#[test]
+#[should_panic]
+fn lanes_lane_oob() {
+    let flags = BitFlags64::full();
+
+    flags.lane::<BitFlags16>(4);
+}
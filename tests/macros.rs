@@ -0,0 +1,55 @@
+//! Tests for the `flag_constants!` macro.
+
+use arctan_bitflags::{flag_constants, BitFlags128, BitFlags16, BitFlags8};
+
+flag_constants! {
+    /// Status effects.
+    mod status: BitFlags16 {
+        /// Takes fire damage each turn.
+        const BURNING = 0;
+        const SCORCHED = 1;
+        const FROZEN = 15;
+        const FIRE_DAMAGE = [BURNING, SCORCHED];
+        const ANY = [FIRE_DAMAGE, FROZEN];
+    }
+}
+
+struct Layer;
+
+flag_constants! {
+    impl Layer: arctan_bitflags::BitFlags128 {
+        const WORLD = 0;
+        const PLAYER = 127;
+        const SOLID = [WORLD, PLAYER,];
+    }
+}
+
+flag_constants! {
+    mod empty: BitFlags8 {}
+}
+
+#[test]
+fn flag_constants_module() {
+    assert_eq!(status::BURNING, BitFlags16(0b0001));
+    assert_eq!(status::FIRE_DAMAGE, BitFlags16(0b0011));
+    assert_eq!(status::ANY, BitFlags16(0b1000_0000_0000_0011));
+    assert_eq!(status::NAMES.len(), 5);
+    assert_eq!(status::NAMES[2], ("FROZEN", BitFlags16(1 << 15)));
+
+    assert_eq!(status::from_name("SCORCHED"), Some(status::SCORCHED));
+    assert_eq!(status::from_name("scorched"), None);
+    assert_eq!(status::name_of(BitFlags16(0b0011)), Some("FIRE_DAMAGE"));
+    assert_eq!(status::name_of(BitFlags16(0b0101)), None);
+    assert!(empty::NAMES.is_empty());
+}
+
+#[test]
+fn flag_constants_impl() {
+    assert_eq!(Layer::PLAYER, BitFlags128(1 << 127));
+    assert_eq!(Layer::SOLID, BitFlags128(1 << 127 | 1));
+    assert_eq!(Layer::from_name("WORLD"), Some(BitFlags128(1)));
+    assert_eq!(Layer::name_of(Layer::SOLID), Some("SOLID"));
+
+    let names: Vec<&str> = Layer::NAMES.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, vec!["WORLD", "PLAYER", "SOLID"]);
+}
@@ -0,0 +1,86 @@
+//! Tests for writing bitflags by name with `FlagNames` tables.
+
+use arctan_bitflags::{
+    flag_constants, BitFlags128, BitFlags16, BitFlags64, FlagRegistry, FlagsDisplay,
+};
+use core::fmt::Write;
+
+const NAMES: [(&str, usize); 4] = [("burning", 0), ("frozen", 1), ("stunned", 10), ("toxic", 0)];
+
+flag_constants! {
+    mod status: BitFlags16 {
+        const BURNING = 0;
+        const FROZEN = 3;
+        const ELEMENTAL = [BURNING, FROZEN];
+    }
+}
+
+/// A `core::fmt::Write` into a fixed buffer, to check writing without allocating.
+struct Buffer {
+    bytes: [u8; 64],
+    len:   usize,
+}
+
+impl Write for Buffer {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        self.bytes
+            .get_mut(self.len..end)
+            .ok_or(core::fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[test]
+fn display_with_names() {
+    let flags = BitFlags64(0b0100_0000_0011);
+    assert_eq!(flags.display_with(&NAMES).to_string(), "burning | frozen | stunned");
+    assert_eq!(
+        BitFlags64(1 << 10 | 1 << 40 | 1 << 2)
+            .display_with(&NAMES)
+            .to_string(),
+        "stunned | 0x10000000004"
+    );
+    assert_eq!(BitFlags64(0).display_with(&NAMES).to_string(), "0x0");
+    assert_eq!(BitFlags64(0b0100).display_with(&NAMES[..]).to_string(), "0x4");
+    assert_eq!(format!("{:?}", flags.display_with(&NAMES)), "burning | frozen | stunned");
+
+    let wide = BitFlags128(1 << 127 | 1);
+    assert_eq!(
+        wide.display_with(&NAMES).to_string(),
+        "burning | 0x80000000000000000000000000000000"
+    );
+}
+
+#[test]
+fn display_with_style() {
+    let flags = BitFlags64(0b1000_0000_0010);
+    assert_eq!(flags.display_with(&NAMES).separator(",").to_string(), "frozen,0x800");
+    assert_eq!(flags.display_with(&NAMES).braced().to_string(), "{frozen, 0x800}");
+    assert_eq!(BitFlags64(0).display_with(&NAMES).braced().to_string(), "{}");
+    assert_eq!(
+        FlagsDisplay::new(0b0011, &NAMES).braced().separator(" ").to_string(),
+        "{burning frozen}"
+    );
+}
+
+#[test]
+fn display_with_constants_and_registry() {
+    let flags = status::ELEMENTAL | BitFlags16(1 << 15);
+    assert_eq!(flags.display_with(status::NAMES).to_string(), "BURNING | FROZEN | 0x8000");
+
+    let registry: FlagRegistry = ron::from_str(
+        "(width: 16, flags: [(name: \"walk\"), (name: \"swim\", aliases: [\"dive\"])])",
+    )
+    .unwrap();
+    assert_eq!(BitFlags16(0b0111).display_with(&registry).to_string(), "walk | swim | 0x4");
+}
+
+#[test]
+fn display_with_no_alloc() {
+    let mut buffer = Buffer { bytes: [0; 64], len: 0 };
+    write!(buffer, "{}", BitFlags64(0b0011).display_with(&NAMES).braced()).unwrap();
+    assert_eq!(&buffer.bytes[..buffer.len], b"{burning, frozen}");
+}
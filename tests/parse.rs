@@ -0,0 +1,128 @@
+//! Tests for parsing flag expressions by name over a `FlagRegistry`.
+
+use arctan_bitflags::{BitFlags16, BitFlags8, FlagRegistry, ParseFlagsError, ParseFlagsErrorKind};
+
+const REGISTRY_RON: &str = r#"(
+    width: 16,
+    reserved: [15],
+    flags: [
+        (name: "burning"),
+        (name: "frozen"),
+        (name: "stunned", index: Some(10)),
+        (name: "poisoned", aliases: ["toxic"], deprecated: true),
+    ],
+    groups: [(name: "elemental", flags: ["burning", "frozen"])],
+)"#;
+
+fn registry() -> FlagRegistry {
+    ron::from_str(REGISTRY_RON).unwrap()
+}
+
+fn error(position: usize, kind: ParseFlagsErrorKind) -> Result<u128, ParseFlagsError> {
+    Err(ParseFlagsError { position, kind })
+}
+
+#[test]
+fn parse_terms() {
+    let registry = registry();
+    assert_eq!(registry.parse("burning | stunned | 12"), Ok(1 << 12 | 1 << 10 | 1));
+    assert_eq!(registry.parse("frozen,toxic"), Ok(0b0110));
+    assert_eq!(registry.parse("  elemental  "), Ok(0b0011));
+    assert_eq!(registry.parse("0x400 | 0b1"), Ok(0x401));
+    assert_eq!(registry.parse("4..8"), Ok(0b1111_0000));
+    assert_eq!(registry.parse("4..=8 | 0..0"), Ok(0b1_1111_0000));
+    assert_eq!(registry.parse("0..16"), Ok(0xffff));
+    assert_eq!(registry.parse("{burning, frozen}"), Ok(0b0011));
+    assert_eq!(registry.parse("{ }"), Ok(0));
+}
+
+#[test]
+fn parse_negation() {
+    let registry = registry();
+    assert_eq!(registry.parse("!burning"), Ok(1 << 10 | 0b0110));
+    assert_eq!(registry.parse("!elemental | 0"), Ok(1 << 10 | 0b0101));
+    assert_eq!(registry.parse("!!frozen"), Ok(0b0010));
+    assert_eq!(registry.parse("!0x0"), Ok(1 << 10 | 0b0111));
+}
+
+#[test]
+fn parse_errors() {
+    let registry = registry();
+    assert_eq!(
+        registry.parse("burning | Stunned"),
+        error(
+            10,
+            ParseFlagsErrorKind::UnknownName {
+                name:       "Stunned".to_string(),
+                suggestion: Some("stunned".to_string()),
+            }
+        )
+    );
+    assert_eq!(
+        registry.parse("frozen, elementl").unwrap_err().to_string(),
+        "at 8: unknown flag \"elementl\" (did you mean \"elemental\"?)"
+    );
+    assert_eq!(
+        registry.parse("quux"),
+        error(
+            0,
+            ParseFlagsErrorKind::UnknownName {
+                name:       "quux".to_string(),
+                suggestion: None,
+            }
+        )
+    );
+    assert_eq!(
+        registry.parse("1 | 16"),
+        error(4, ParseFlagsErrorKind::IndexOutOfRange { index: 16, width: 16 })
+    );
+    assert_eq!(
+        registry.parse("4..=16"),
+        error(0, ParseFlagsErrorKind::IndexOutOfRange { index: 16, width: 16 })
+    );
+    assert_eq!(
+        registry.parse("8..4"),
+        error(0, ParseFlagsErrorKind::InvalidRange { start: 8, end: 4 })
+    );
+    assert_eq!(
+        registry.parse("0x10000"),
+        error(0, ParseFlagsErrorKind::BitsOutOfRange { bits: 0x10000, width: 16 })
+    );
+    assert_eq!(
+        registry.parse("0xfg"),
+        error(0, ParseFlagsErrorKind::InvalidNumber { text: "0xfg".to_string() })
+    );
+    assert_eq!(registry.parse("burning ||frozen"), error(9, ParseFlagsErrorKind::MissingTerm));
+    assert_eq!(registry.parse(""), error(0, ParseFlagsErrorKind::MissingTerm));
+    assert_eq!(registry.parse(" {burning"), error(1, ParseFlagsErrorKind::UnclosedBrace));
+}
+
+#[test]
+fn parse_with_width() {
+    let registry = registry();
+    assert_eq!(
+        BitFlags16::parse_with("stunned | 15", &registry),
+        Ok(BitFlags16(1 << 15 | 1 << 10))
+    );
+    assert_eq!(BitFlags8::parse_with("elemental", &registry), Ok(BitFlags8(0b0011)));
+    assert_eq!(
+        BitFlags8::parse_with("stunned", &registry),
+        Err(ParseFlagsError {
+            position: 0,
+            kind:     ParseFlagsErrorKind::IndexOutOfRange { index: 10, width: 8 },
+        })
+    );
+    assert!(BitFlags8::parse_with("!burning", &registry).is_ok());
+}
+
+#[test]
+fn parse_display_round_trip() {
+    let registry = registry();
+    for bits in [0, 0b0001, 0b0111, 1 << 10 | 1 << 3, 0xffff, 1 << 15 | 1 << 12] {
+        let flags = BitFlags16(bits);
+        let plain = flags.display_with(&registry).to_string();
+        let braced = flags.display_with(&registry).braced().to_string();
+        assert_eq!(BitFlags16::parse_with(&plain, &registry), Ok(flags), "{}", plain);
+        assert_eq!(BitFlags16::parse_with(&braced, &registry), Ok(flags), "{}", braced);
+    }
+}
@@ -0,0 +1,131 @@
+//! Tests for `BitPermutation` construction and Beneš network execution.
+
+use arctan_bitflags::{
+    BitFlags128, BitFlags16, BitFlags32, BitFlags64, BitFlags8, BitFlagsError, BitPermutation,
+};
+
+/// A small xorshift generator, for reproducible shuffles.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+    fn shuffle<const N: usize>(&mut self) -> [u8; N] {
+        let mut targets = [0u8; N];
+        for (i, target) in targets.iter_mut().enumerate() {
+            *target = i as u8;
+        }
+        for i in (1..N).rev() {
+            targets.swap(i, (self.next() % (i as u64 + 1)) as usize);
+        }
+        targets
+    }
+}
+
+/// Moves each set bit `i` of `bits` to `targets[i]`, one bit at a time.
+fn naive(targets: &[u8], bits: u128) -> u128 {
+    (0..targets.len())
+        .filter(|&i| bits >> i & 1 == 1)
+        .fold(0, |out, i| out | 1 << targets[i])
+}
+
+#[test]
+fn permutation_random() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..200 {
+        let p8 = BitPermutation::<8>::new(rng.shuffle());
+        let p16 = BitPermutation::<16>::new(rng.shuffle());
+        let p32 = BitPermutation::<32>::new(rng.shuffle());
+        let p64 = BitPermutation::<64>::new(rng.shuffle());
+        let p128 = BitPermutation::<128>::new(rng.shuffle());
+        let bits = (rng.next() as u128) << 64 | rng.next() as u128;
+
+        assert_eq!(
+            p8.apply(BitFlags8(bits as u8)).0 as u128,
+            naive(p8.targets(), bits as u8 as u128)
+        );
+        assert_eq!(
+            p16.apply(BitFlags16(bits as u16)).0 as u128,
+            naive(p16.targets(), bits as u16 as u128)
+        );
+        assert_eq!(
+            p32.apply(BitFlags32(bits as u32)).0 as u128,
+            naive(p32.targets(), bits as u32 as u128)
+        );
+        assert_eq!(
+            p64.apply(BitFlags64(bits as u64)).0 as u128,
+            naive(p64.targets(), bits as u64 as u128)
+        );
+        assert_eq!(p128.apply(BitFlags128(bits)).0, naive(p128.targets(), bits));
+    }
+}
+
+#[test]
+fn permutation_exhaustive_8() {
+    let mut rng = Rng(7);
+    for _ in 0..100 {
+        let p = BitPermutation::<8>::new(rng.shuffle());
+        for bits in 0..=u8::MAX {
+            assert_eq!(p.apply(BitFlags8(bits)).0 as u128, naive(p.targets(), bits as u128));
+        }
+    }
+}
+
+#[test]
+fn permutation_inverse_compose() {
+    let mut rng = Rng(42);
+    let p = BitPermutation::<64>::new(rng.shuffle());
+    let q = BitPermutation::<64>::new(rng.shuffle());
+    let flags = BitFlags64(0x0123_4567_89ab_cdef);
+
+    assert_eq!(p.inverse().apply(p.apply(flags)), flags);
+    assert!(p.compose(&p.inverse()).is_identity());
+    assert_eq!(p.compose(&q).apply(flags), q.apply(p.apply(flags)));
+    assert_eq!(p.inverse().target(p.target(5)), 5);
+
+    let mut slice = [flags, BitFlags64(1)];
+    p.apply_slice(&mut slice);
+    assert_eq!(slice, [p.apply(flags), p.apply(BitFlags64(1))]);
+}
+
+#[test]
+fn permutation_common() {
+    let flags = BitFlags32(0x8000_1234);
+    assert_eq!(BitPermutation::<32>::reverse().apply(flags), flags.reverse_bits());
+    assert_eq!(BitPermutation::<32>::rotate_left(5).apply(flags), flags.rotate_left(5));
+    assert_eq!(BitPermutation::<32>::identity().apply(flags), flags);
+    assert!(BitPermutation::<128>::default().is_identity());
+
+    // Row 0 (the low byte) becomes column 0 (bit 0 of every byte).
+    let transpose = BitPermutation::<64>::transpose(8);
+    assert_eq!(transpose.apply(BitFlags64(0xff)), BitFlags64(0x0101_0101_0101_0101));
+    assert_eq!(transpose.apply(BitFlags64(1 << 10)), BitFlags64(1 << 17));
+    assert!(transpose.compose(&transpose).is_identity());
+    assert_eq!(BitPermutation::<16>::transpose(2).apply(BitFlags16(0x00ff)), BitFlags16(0x5555));
+}
+
+#[test]
+fn permutation_invalid() {
+    let mut targets = [0u8; 8];
+    targets.iter_mut().enumerate().for_each(|(i, t)| *t = i as u8);
+    targets[3] = 5;
+    assert_eq!(
+        BitPermutation::<8>::try_new(targets),
+        Err(BitFlagsError::NotAPermutation { index: 5 })
+    );
+    targets[3] = 8;
+    assert_eq!(
+        BitPermutation::<8>::try_new(targets),
+        Err(BitFlagsError::IndexOutOfRange { index: 8, bits: 8 })
+    );
+}
+
+#[test]
+#[should_panic]
+fn permutation_transpose_panics() {
+    BitPermutation::<32>::transpose(3);
+}
@@ -0,0 +1,147 @@
+//! Tests for the `RankSelect` index over `BitFlags64` words.
+
+use arctan_bitflags::{BitFlags64, RankSelect};
+use nanoserde::{DeBin, SerBin};
+
+/// Spans three full superblocks (1,024 words each) and part of a fourth, mixing random words with
+/// runs of empty and full words.
+fn words() -> Vec<BitFlags64> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    (0..3 * 1024 + 13)
+        .map(|i| match i % 97 {
+            0..=9 => BitFlags64(0),
+            10..=14 => BitFlags64(u64::MAX),
+            _ => {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                BitFlags64(state)
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn rank_select_matches_naive() {
+    let words = words();
+    let index = RankSelect::new(&words);
+    let bits: Vec<bool> = (0..words.len() * 64)
+        .map(|i| words[i / 64].bit_at_index(i % 64))
+        .collect();
+    assert_eq!(index.len(), bits.len());
+
+    let mut ones = Vec::new();
+    let mut zeros = Vec::new();
+    for (i, &bit) in bits.iter().enumerate() {
+        assert_eq!(index.rank1(i), ones.len());
+        assert_eq!(index.rank0(i), zeros.len());
+        assert_eq!(index.bit(i), bit);
+        if bit {
+            ones.push(i);
+        } else {
+            zeros.push(i);
+        }
+    }
+    assert_eq!(index.rank1(bits.len()), ones.len());
+    assert_eq!(index.count_ones(), ones.len());
+    assert_eq!(index.count_zeros(), zeros.len());
+
+    for (k, &i) in ones.iter().enumerate() {
+        assert_eq!(index.select1(k), Some(i));
+    }
+    for (k, &i) in zeros.iter().enumerate() {
+        assert_eq!(index.select0(k), Some(i));
+    }
+    assert_eq!(index.select1(ones.len()), None);
+    assert_eq!(index.select0(zeros.len()), None);
+}
+
+#[test]
+fn rank_select_edges() {
+    let empty = RankSelect::new(&[]);
+    assert!(empty.is_empty());
+    assert_eq!(empty.rank1(0), 0);
+    assert_eq!(empty.select1(0), None);
+    assert_eq!(empty.select0(0), None);
+
+    let index = RankSelect::from(vec![BitFlags64(0b1011), BitFlags64(0b0001)]);
+    assert_eq!(index.rank1(64), 3);
+    assert_eq!(index.rank0(128), 124);
+    assert_eq!(index.select1(3), Some(64));
+    assert_eq!(index.select0(0), Some(2));
+    assert_eq!(index.select0(61), Some(65));
+    assert_eq!(index.into_words(), vec![BitFlags64(0b1011), BitFlags64(0b0001)]);
+}
+
+#[test]
+#[should_panic]
+fn rank_select_rank_out_of_range() {
+    RankSelect::new(&[BitFlags64(1)]).rank1(65);
+}
+
+#[test]
+fn rank_select_overhead() {
+    let index = RankSelect::new(&vec![BitFlags64(0); 64 * 1024]);
+    let overhead = index.index_bytes() as f64 / (index.words().len() * 8) as f64;
+    assert!(overhead > 0.03 && overhead < 0.06, "overhead: {}", overhead);
+}
+
+#[test]
+fn rank_select_serde() {
+    let index = RankSelect::new(&words());
+
+    let json = serde_json::to_string(&index).unwrap();
+    let de: RankSelect = serde_json::from_str(&json).unwrap();
+    assert_eq!(de, index);
+
+    let ron = ron::to_string(&index).unwrap();
+    let de: RankSelect = ron::from_str(&ron).unwrap();
+    assert_eq!(de, index);
+
+    assert!(serde_json::from_str::<RankSelect>("[[1,2],[0],[]]").is_err());
+    assert!(serde_json::from_str::<RankSelect>("[[1,2],[0],[0]]").is_ok());
+}
+
+#[test]
+fn rank_select_corrupted_counts() {
+    let words = vec![BitFlags64(0b1011); 20];
+    let load = |superblocks: &str, blocks: &str| {
+        let words = serde_json::to_string(&words).unwrap();
+        serde_json::from_str::<RankSelect>(&format!("[{},{},{}]", words, superblocks, blocks))
+    };
+    assert_eq!(load("[0]", "[0,24,48]").unwrap(), RankSelect::new(&words));
+
+    // Wrong counts, missing or extra counts, a nonzero first count, a decreasing count, and a
+    // count growing by more than the bits before it.
+    assert!(load("[0]", "[0,24]").is_err());
+    assert!(load("[0,0]", "[0,24,48]").is_err());
+    assert!(load("[1]", "[0,24,48]").is_err());
+    assert!(load("[0]", "[1,24,48]").is_err());
+    assert!(load("[0]", "[0,24,20]").is_err());
+    assert!(load("[0]", "[0,24,65535]").is_err());
+
+    // Consistent counts are trusted as saved, rather than recounted.
+    let trusted = load("[0]", "[0,25,48]").unwrap();
+    assert_eq!(trusted.rank1(512), 25);
+    assert_eq!(trusted.count_ones(), 60);
+    assert!((0..100).all(|k| trusted.select1(k).is_none_or(|i| i < trusted.len())));
+    assert!((0..100).all(|k| trusted.select0(k).is_none_or(|i| i < trusted.len())));
+
+    let corrupted = (words, vec![0u64], vec![0u16, 24, 4000]);
+    let bin = SerBin::serialize_bin(&corrupted);
+    assert!(<RankSelect as DeBin>::deserialize_bin(&bin).is_err());
+}
+
+#[test]
+fn rank_select_nanoserde() {
+    let index = RankSelect::new(&words());
+
+    let bin = SerBin::serialize_bin(&index);
+    let de: RankSelect = DeBin::deserialize_bin(&bin).unwrap();
+    assert_eq!(de, index);
+    assert_eq!(de.select1(100), index.select1(100));
+
+    let mismatched = (vec![BitFlags64(1)], Vec::<u64>::new(), Vec::<u16>::new());
+    let bin = SerBin::serialize_bin(&mismatched);
+    assert!(<RankSelect as DeBin>::deserialize_bin(&bin).is_err());
+}
@@ -0,0 +1,163 @@
+//! Tests for loading and validating `FlagRegistry` definitions.
+
+use arctan_bitflags::{
+    BitFlags16, BitFlags8, BitFlagsError, FlagDefinition, FlagRegistry, FlagSchema,
+    SchemaDefinition, SchemaErrorKind,
+};
+
+const DEFINITION_RON: &str = r#"(
+    width: 16,
+    reserved: [15],
+    flags: [
+        (name: "walk", description: Some("Can walk")),
+        (name: "swim", index: Some(4), aliases: ["dive"]),
+        (name: "run"),
+        (name: "fly", deprecated: true),
+    ],
+    groups: [(name: "movement", flags: ["walk", "dive", "run"])],
+)"#;
+
+const DEFINITION_JSON: &str = r#"{
+    "width": 16,
+    "reserved": [15],
+    "flags": [
+        { "name": "walk", "description": "Can walk" },
+        { "name": "swim", "index": 4, "aliases": ["dive"] },
+        { "name": "run" },
+        { "name": "fly", "deprecated": true }
+    ],
+    "groups": [{ "name": "movement", "flags": ["walk", "dive", "run"] }]
+}"#;
+
+fn flag(name: &str, index: Option<u32>) -> FlagDefinition {
+    FlagDefinition {
+        name: name.to_string(),
+        index,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn registry_load() {
+    let definition: SchemaDefinition = ron::from_str(DEFINITION_RON).unwrap();
+    assert_eq!(serde_json::from_str::<SchemaDefinition>(DEFINITION_JSON).unwrap(), definition);
+    let registry = FlagRegistry::new(&definition).unwrap();
+
+    assert_eq!(registry.width(), 16);
+    let names: Vec<(&str, usize)> =
+        registry.flags().iter().map(|f| (f.name(), f.index())).collect();
+    assert_eq!(names, vec![("walk", 0), ("run", 1), ("fly", 2), ("swim", 4)]);
+
+    assert_eq!(registry.index_of("dive"), Some(4));
+    assert!(registry.is_alias("dive"));
+    assert!(!registry.is_alias("swim"));
+    assert_eq!(registry.name_of(1), Some("run"));
+    assert_eq!(registry.name_of(3), None);
+    assert_eq!(registry.flag("walk").unwrap().description(), Some("Can walk"));
+    assert!(registry.flag("fly").unwrap().is_deprecated());
+    assert_eq!(registry.flag("movement"), None);
+    assert_eq!(registry.group("movement").unwrap().bits(), 0b0001_0011);
+
+    assert_eq!(registry.defined_bits(), 0b0001_0011);
+    assert_eq!(registry.deprecated_bits(), 0b0100);
+    assert_eq!(registry.reserved_bits(), 1 << 15);
+    let schema = FlagSchema::<BitFlags16>::try_from(&registry).unwrap();
+    assert_eq!(schema.known(), BitFlags16(0b0001_0111));
+    assert_eq!(schema.reserved(), BitFlags16(1 << 15));
+    assert_eq!(
+        FlagSchema::<BitFlags8>::try_from(&registry),
+        Err(BitFlagsError::IndexOutOfRange { index: 15, bits: 8 })
+    );
+
+    let loaded: FlagRegistry = serde_json::from_str(DEFINITION_JSON).unwrap();
+    assert_eq!(loaded, registry);
+}
+
+#[test]
+fn registry_errors() {
+    let definition = SchemaDefinition {
+        width:    8,
+        reserved: vec![7],
+        flags:    vec![
+            flag("a", Some(1)),
+            flag("b", Some(1)),
+            flag("a", None),
+            flag("c", Some(7)),
+            flag("d", Some(8)),
+            flag("2e", None),
+        ],
+        groups:   vec![],
+    };
+    let errors = FlagRegistry::new(&definition).unwrap_err();
+    let found: Vec<(&str, &SchemaErrorKind)> =
+        errors.iter().map(|e| (e.path.as_str(), &e.kind)).collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                "flags[1].index",
+                &SchemaErrorKind::IndexCollision {
+                    index: 1,
+                    first: "flags[0].index".to_string(),
+                }
+            ),
+            (
+                "flags[3].index",
+                &SchemaErrorKind::IndexCollision {
+                    index: 7,
+                    first: "reserved[0]".to_string(),
+                }
+            ),
+            ("flags[4].index", &SchemaErrorKind::IndexOverflow { index: 8, width: 8 }),
+            (
+                "flags[2].name",
+                &SchemaErrorKind::DuplicateName {
+                    name:  "a".to_string(),
+                    first: "flags[0].name".to_string(),
+                }
+            ),
+            ("flags[5].name", &SchemaErrorKind::InvalidName { name: "2e".to_string() }),
+        ]
+    );
+    assert_eq!(
+        errors[0].to_string(),
+        "flags[1].index: index 1 is already taken (at flags[0].index)"
+    );
+}
+
+#[test]
+fn registry_errors_width_and_groups() {
+    let definition = SchemaDefinition { width: 12, ..Default::default() };
+    let errors = FlagRegistry::new(&definition).unwrap_err();
+    assert_eq!(errors[0].path, "width");
+    assert_eq!(errors[0].kind, SchemaErrorKind::UnsupportedWidth { width: 12 });
+
+    let mut definition: SchemaDefinition = ron::from_str(DEFINITION_RON).unwrap();
+    definition.groups[0].flags.push("jump".to_string());
+    definition.groups[0].name = "walk".to_string();
+    let errors = FlagRegistry::new(&definition).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].path, "groups[0].name");
+    assert_eq!(errors[1].path, "groups[0].flags[3]");
+    assert_eq!(errors[1].kind, SchemaErrorKind::UnknownFlag { name: "jump".to_string() });
+
+    let err = ron::from_str::<FlagRegistry>("(width: 8, flags: [(name: \"a\"), (name: \"a\")])")
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("flags[1].name: duplicate name \"a\" (first used at flags[0].name)"));
+}
+
+#[test]
+fn registry_no_free_index() {
+    let definition = SchemaDefinition {
+        width:    8,
+        reserved: vec![0, 1, 2, 3],
+        flags:    (0..5).map(|i| flag(&format!("f{}", i), None)).collect(),
+        groups:   vec![],
+    };
+    let errors = FlagRegistry::new(&definition).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, "flags[4]");
+    assert_eq!(errors[0].kind, SchemaErrorKind::NoFreeIndex { width: 8 });
+}
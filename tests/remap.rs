@@ -0,0 +1,106 @@
+//! Tests for `BitRemap` migrations between versions of a flag list.
+
+use arctan_bitflags::{
+    BitFlags16, BitFlags64, BitFlags8, BitRemap, FlagRegistry, Remapped, TypedFlags,
+};
+use nanoserde::{DeBin, DeJson, DeRon, SerBin, SerJson, SerRon};
+
+const V1: [&str; 4] = ["burning", "frozen", "stunned", "wet"];
+const V2: [&str; 4] = ["burning", "chilled", "frozen", "stunned"];
+const V3: [&str; 3] = ["stunned", "frozen", "burning"];
+
+#[test]
+fn remap_from_names() {
+    let remap = BitRemap::from_names(&V1, &V2);
+    assert_eq!(remap.target(0), Some(0));
+    assert_eq!(remap.target(1), Some(2));
+    assert_eq!(remap.target(3), None);
+    assert_eq!(remap.target(200), None);
+    assert_eq!(remap.dropped(), !0b0111);
+
+    let saved = BitFlags64(0b1110);
+    assert_eq!(
+        remap.apply(saved),
+        Remapped {
+            flags: BitFlags64(0b1100),
+            lost:  BitFlags64(0b1000),
+        }
+    );
+
+    let mut saves = [BitFlags64(0b0001), BitFlags64(0b0110), BitFlags64(1 << 40)];
+    assert_eq!(remap.apply_slice(&mut saves), BitFlags64(1 << 40));
+    assert_eq!(saves, [BitFlags64(0b0001), BitFlags64(0b1100), BitFlags64(0)]);
+}
+
+#[test]
+fn remap_merge_inverse_chain() {
+    let merge = BitRemap::identity().with(3, 1).without(2);
+    assert_eq!(merge.merged(), 0b0010);
+    assert_eq!(merge.apply(BitFlags8(0b1101)).flags, BitFlags8(0b0011));
+    assert_eq!(merge.inverse().target(1), Some(1));
+    assert_eq!(merge.inverse().target(2), None);
+
+    let shift = BitRemap::empty().with(0, 1).with(1, 2).with(2, 0);
+    assert_eq!(shift.inverse().then(&shift), BitRemap::empty().with(0, 0).with(1, 1).with(2, 2));
+    assert_eq!(shift.then(&shift.inverse()).apply(BitFlags8(0b0111)).flags, BitFlags8(0b0111));
+
+    let chained = BitRemap::from_names(&V1, &V2).then(&BitRemap::from_names(&V2, &V3));
+    assert_eq!(chained.apply(BitFlags16(0b0111)).flags, BitFlags16(0b0111));
+    assert_eq!(chained.apply(BitFlags16(0b0101)).flags, BitFlags16(0b0101));
+    assert_eq!(chained.apply(BitFlags16(0b0001)).flags, BitFlags16(0b0100));
+    assert_eq!(BitRemap::default(), BitRemap::identity());
+}
+
+#[test]
+fn remap_narrow_target() {
+    enum Status {}
+    let remap = BitRemap::identity().with(0, 12);
+    let flags = TypedFlags::<Status, BitFlags8>::from_u8(0b0011);
+    let remapped = remap.apply(flags);
+    assert_eq!(remapped.flags, TypedFlags::from_u8(0b0010));
+    assert_eq!(remapped.lost, TypedFlags::from_u8(0b0001));
+}
+
+#[test]
+fn remap_between_registries() {
+    let old: FlagRegistry =
+        ron::from_str("(width: 8, flags: [(name: \"walk\"), (name: \"swim\"), (name: \"fly\")])")
+            .unwrap();
+    let new: FlagRegistry = ron::from_str(
+        "(width: 8, flags: [(name: \"jump\"), (name: \"walk\"), (name: \"dive\", aliases: [\"swim\"])])",
+    )
+    .unwrap();
+    let remap = BitRemap::between(&old, &new);
+    assert_eq!(
+        remap.apply(BitFlags8(0b0111)),
+        Remapped {
+            flags: BitFlags8(0b0110),
+            lost:  BitFlags8(0b0100),
+        }
+    );
+}
+
+#[test]
+fn remap_serde() {
+    let remap = BitRemap::empty().with(0, 2).with(5, 2).with(7, 0);
+    let json = serde_json::to_string(&remap).unwrap();
+    assert_eq!(json, "[[0,2],[5,2],[7,0]]");
+    assert_eq!(serde_json::from_str::<BitRemap>(&json).unwrap(), remap);
+    assert_eq!(ron::from_str::<BitRemap>("[(0, 2), (5, 2), (7, 0)]").unwrap(), remap);
+    assert!(serde_json::from_str::<BitRemap>("[[0,128]]").is_err());
+    assert_eq!(format!("{:?}", remap), "{0: 2, 5: 2, 7: 0}");
+}
+
+#[test]
+fn remap_nanoserde() {
+    let remap = BitRemap::from_names(&V1, &V2);
+    assert_eq!(BitRemap::deserialize_bin(&remap.serialize_bin()).unwrap(), remap);
+    assert_eq!(BitRemap::deserialize_json(&remap.serialize_json()).unwrap(), remap);
+    assert_eq!(BitRemap::deserialize_ron(&remap.serialize_ron()).unwrap(), remap);
+    assert!(BitRemap::deserialize_json("[[200,1]]").is_err());
+
+    let bad = vec![(1u8, 130u8)].serialize_bin();
+    let mut offset = 0;
+    assert!(BitRemap::de_bin(&mut offset, &bad).is_err());
+    assert_eq!(offset, 0);
+}
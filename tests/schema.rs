@@ -0,0 +1,89 @@
+//! Tests for `FlagSchema` known-bit masks and unknown-bit policies.
+
+use arctan_bitflags::{
+    BitFlags128, BitFlags16, BitFlags32, BitFlags8, BitFlagsError, FlagSchema, UnknownBitPolicy,
+};
+use nanoserde::{DeJsonState, DeRonState, SerBin};
+use serde::de::DeserializeSeed;
+
+const SCHEMA: FlagSchema<BitFlags16> = FlagSchema::<BitFlags16>::new(BitFlags16(0b0000_1111))
+    .with_deprecated(BitFlags16(0b0011_0000))
+    .with_reserved(BitFlags16(0b1000_0001));
+
+#[test]
+fn schema_known_bits() {
+    assert_eq!(SCHEMA.known(), BitFlags16(0b0011_1110));
+    assert_eq!(SCHEMA.policy(), UnknownBitPolicy::Reject);
+    assert!(SCHEMA.is_valid(BitFlags16(0b0010_0110)));
+    assert!(!SCHEMA.is_valid(BitFlags16(0b0000_0001)));
+    assert_eq!(SCHEMA.unknown_bits(BitFlags16(0b1100_0011)), BitFlags16(0b1100_0001));
+}
+
+#[test]
+fn schema_from_bits() {
+    assert_eq!(SCHEMA.from_bits_checked(0b0010), Ok(BitFlags16(0b0010)));
+    assert_eq!(
+        SCHEMA.from_bits_checked(0b0100_0010),
+        Err(BitFlagsError::UnknownBits { bits: 0b0100_0000 })
+    );
+    assert_eq!(SCHEMA.from_bits_truncate(0b1111_1111), BitFlags16(0b0011_1110));
+    assert_eq!(SCHEMA.from_bits_retain(0b1111_1111), BitFlags16(0b1111_1111));
+
+    let bits = 0b1000_0100;
+    assert!(SCHEMA.from_bits(bits).is_err());
+    let truncate = SCHEMA.with_policy(UnknownBitPolicy::Truncate);
+    assert_eq!(truncate.from_bits(bits), Ok(BitFlags16(0b0100)));
+    let retain = SCHEMA.with_policy(UnknownBitPolicy::Retain);
+    assert_eq!(retain.from_bits(bits), Ok(BitFlags16(bits)));
+}
+
+#[test]
+fn schema_complement_within() {
+    let flags = BitFlags16(0b0001_0110);
+    assert_eq!(SCHEMA.complement_within(flags), BitFlags16(0b0001_1000));
+    assert_eq!(SCHEMA.complement_within(SCHEMA.complement_within(flags)), flags);
+    assert_eq!(!flags, BitFlags16(0b1111_1111_1110_1001));
+
+    let schema = FlagSchema::<BitFlags8>::new(BitFlags8(0b0111));
+    assert_eq!(schema.complement_within(BitFlags8(0b1001)), BitFlags8(0b0110));
+    let wide = FlagSchema::<BitFlags128>::new(BitFlags128(u128::MAX >> 1));
+    assert_eq!(wide.complement_within(BitFlags128(0)), BitFlags128(u128::MAX >> 1));
+}
+
+#[test]
+fn schema_serde() {
+    let schema = FlagSchema::<BitFlags32>::new(BitFlags32(0b0111));
+
+    let mut de = serde_json::Deserializer::from_str("5");
+    assert_eq!(schema.deserialize(&mut de).unwrap(), BitFlags32(5));
+    let mut de = serde_json::Deserializer::from_str("13");
+    let err = schema.deserialize(&mut de).unwrap_err();
+    assert_eq!(err.to_string(), "unknown bits set: 0x8");
+
+    let mut de = ron::Deserializer::from_str("13").unwrap();
+    let truncate = schema.with_policy(UnknownBitPolicy::Truncate);
+    assert_eq!(truncate.deserialize(&mut de).unwrap(), BitFlags32(5));
+}
+
+#[test]
+fn schema_nanoserde() {
+    let bin = SerBin::serialize_bin(&BitFlags16(0b0100_0010));
+    let mut offset = 0;
+    assert!(SCHEMA.de_bin(&mut offset, &bin).is_err());
+    assert_eq!(offset, 0);
+    let truncate = SCHEMA.with_policy(UnknownBitPolicy::Truncate);
+    assert_eq!(truncate.de_bin(&mut offset, &bin).unwrap(), BitFlags16(0b0010));
+    assert_eq!(offset, 2);
+
+    let mut state = DeJsonState::default();
+    let mut chars = "66".chars();
+    state.next(&mut chars);
+    state.next_tok(&mut chars).unwrap();
+    assert!(SCHEMA.de_json(&mut state, &mut chars).is_err());
+
+    let mut state = DeRonState::default();
+    let mut chars = "2".chars();
+    state.next(&mut chars);
+    state.next_tok(&mut chars).unwrap();
+    assert_eq!(SCHEMA.de_ron(&mut state, &mut chars).unwrap(), BitFlags16(0b0010));
+}
@@ -1,6 +1,9 @@
 //! Tests for the `BitFlags128` struct.
 
-use arctan_bitflags::BitFlags128;
+use arctan_bitflags::{
+    BitField128, BitFlags128, BitFlagsError, Index128, Msb0BitFlags128, NonEmptyBitFlags128,
+};
+use core::convert::TryFrom;
 
 #[test]
 fn bitflags128_contains() {
@@ -178,13 +181,13 @@ fn bitflags128_ops() {
     assert_eq!(f1 ^ f2, BitFlags128(0b0011));
     assert_eq!(f1 ^ f3, BitFlags128(0b1000));
 
-    assert_eq!(!f1, BitFlags128(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_1111_1110));
-    assert_eq!(!f2,  BitFlags128(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_1111_1101));
-    assert_eq!(!f3,  BitFlags128(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_1111_0110));
+    assert_eq!(!f1, BitFlags128(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111110));
+    assert_eq!(!f2,  BitFlags128(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111101));
+    assert_eq!(!f3,  BitFlags128(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11110110));
 
-    assert_eq!(f1.complement(), BitFlags128(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_1111_1110));
-    assert_eq!(f2.complement(), BitFlags128(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_1111_1101));
-    assert_eq!(f3.complement(), BitFlags128(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_1111_0110));
+    assert_eq!(f1.complement(), BitFlags128(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111110));
+    assert_eq!(f2.complement(), BitFlags128(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111101));
+    assert_eq!(f3.complement(), BitFlags128(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111111_11110110));
 }
 
 #[test]
@@ -236,8 +239,8 @@ fn bitflags128_index() {
     let ix_0 = f1.bit_at_index(0);
     let ix_7 = f1.bit_at_index(7);
 
-    assert_eq!(ix_0, false);
-    assert_eq!(ix_7, true);
+    assert!(!ix_0);
+    assert!(ix_7);
 }
 
 #[test]
@@ -486,4 +489,664 @@ fn bitflags128_with_set_bit_range() {
     ];
 
     assert_eq!(actual, expected);
-}
\ No newline at end of file
+}
+
+#[test]
+fn bitflags128_field() {
+    let mut flags = BitFlags128(0b1000_0001);
+
+    flags.set_field(1..=3, 0b101);
+    assert_eq!(flags, BitFlags128(0b1000_1011));
+    assert_eq!(flags.get_field(1..=3), 0b101);
+    assert_eq!(flags.get_field(0..=0), 1);
+    assert_eq!(flags.get_field(7..=7), 1);
+
+    flags.set_field(1..=3, 0);
+    assert_eq!(flags, BitFlags128(0b1000_0001));
+
+    let mut flags = BitFlags128::new();
+    flags.set_field(0..=127, u128::MAX);
+    assert!(flags.is_full());
+    assert_eq!(flags.get_field(0..=127), u128::MAX);
+}
+
+#[test]
+#[should_panic]
+fn bitflags128_field_overflow() {
+    let mut flags = BitFlags128::new();
+
+    flags.set_field(1..=3, 0b1000);
+}
+
+#[test]
+fn bitflags128_named_field() {
+    const FLAG: BitField128 = BitField128::new(0, 0);
+    const LEVEL: BitField128 = BitField128::new(4, 6);
+
+    let mut flags = BitFlags128::new();
+    FLAG.set(&mut flags, 1);
+    LEVEL.set(&mut flags, 5);
+
+    assert_eq!(flags, BitFlags128(0b0101_0001));
+    assert_eq!(FLAG.get(flags), 1);
+    assert_eq!(LEVEL.get(flags), 5);
+    assert_eq!(LEVEL.width(), 3);
+    assert_eq!(LEVEL.max_value(), 7);
+    assert_eq!(LEVEL.mask(), BitFlags128(0b0111_0000));
+}
+
+#[test]
+fn bitflags128_range() {
+    assert_eq!(BitFlags128::from_range(1..4), BitFlags128(0b0000_1110));
+    assert_eq!(BitFlags128::from_range(1..=4), BitFlags128(0b0001_1110));
+    assert_eq!(BitFlags128::from_range(3..3), BitFlags128::empty());
+    assert_eq!(BitFlags128::from_range(..), BitFlags128::full());
+    assert_eq!(BitFlags128::from_range(..=127), BitFlags128::full());
+    assert_eq!(BitFlags128::from_range(127..), BitFlags128::from_index(127));
+
+    let mut flags = BitFlags128(0b1000_0001);
+    flags.insert_range(1..3);
+    assert_eq!(flags, BitFlags128(0b1000_0111));
+    flags.remove_range(..2);
+    assert_eq!(flags, BitFlags128(0b1000_0100));
+    flags.toggle_range(2..=3);
+    assert_eq!(flags, BitFlags128(0b1000_1000));
+
+    assert!(flags.contains_range(3..4));
+    assert!(flags.contains_range(5..5));
+    assert!(!flags.contains_range(3..=4));
+    assert!(flags.intersects_range(3..=4));
+    assert!(!flags.intersects_range(4..7));
+    assert_eq!(flags.count_ones_in(..), 2);
+    assert_eq!(flags.count_ones_in(..7), 1);
+}
+
+#[test]
+fn bitflags128_try_range() {
+    let mut flags = BitFlags128(0b0001);
+    let (start, end) = (4, 2);
+
+    assert_eq!(BitFlags128::try_from_range(0..2), Ok(BitFlags128(0b0011)));
+    assert!(BitFlags128::try_from_range(0..=128).is_err());
+    assert!(flags.try_insert_range(1..=128).is_err());
+    assert!(flags.try_remove_range(128..256).is_err());
+    assert!(flags.try_toggle_range(start..end).is_err());
+    assert_eq!(flags, BitFlags128(0b0001));
+
+    assert_eq!(flags.try_insert_range(1..2), Ok(()));
+    assert_eq!(flags.try_contains_range(..2), Ok(true));
+    assert_eq!(flags.try_intersects_range(2..), Ok(false));
+    assert_eq!(flags.try_count_ones_in(1..), Ok(1));
+    assert!(flags.try_count_ones_in(..256).is_err());
+}
+
+#[test]
+#[should_panic]
+fn bitflags128_range_oob() {
+    let mut flags = BitFlags128::new();
+
+    flags.insert_range(0..130);
+}
+
+#[test]
+fn bitflags128_try_index() {
+    let mut flags = BitFlags128::new();
+    let oob = BitFlagsError::IndexOutOfRange { index: 128, bits: 128 };
+
+    assert_eq!(BitFlags128::try_from_index(127), Ok(BitFlags128::from_index(127)));
+    assert_eq!(BitFlags128::try_from_index(128), Err(oob.clone()));
+    assert_eq!(BitFlags128::try_from(128_usize), Err(oob.clone()));
+    assert_eq!(BitFlags128::try_from_slice(&[2, 4, 5]), Ok(BitFlags128(0b0011_0100)));
+    assert_eq!(BitFlags128::try_from_slice(&[2, 128, 5]), Err(oob.clone()));
+
+    assert_eq!(flags.try_insert_at_index(128), Err(oob.clone()));
+    assert_eq!(flags.try_set_at_index(128, true), Err(oob.clone()));
+    assert_eq!(flags.try_toggle_at_index(128), Err(oob.clone()));
+    assert_eq!(flags.try_remove_at_index(128), Err(oob.clone()));
+    assert_eq!(flags.try_bit_at_index(128), Err(oob));
+    assert!(flags.is_empty());
+
+    assert_eq!(flags.try_insert_at_index(0), Ok(()));
+    assert_eq!(flags.try_set_at_index(1, true), Ok(()));
+    assert_eq!(flags.try_toggle_at_index(2), Ok(()));
+    assert_eq!(flags.try_remove_at_index(0), Ok(()));
+    assert_eq!(flags.try_bit_at_index(1), Ok(true));
+    assert_eq!(flags, BitFlags128(0b0110));
+}
+
+#[test]
+fn bitflags128_try_bit_range() {
+    let mut flags = BitFlags128::new();
+    let invalid = BitFlagsError::InvalidRange { start: 3, end: 3, bits: 128 };
+    let (start, end) = (3, 2);
+
+    assert_eq!(BitFlags128::try_with_set_bit_range(1, 3), Ok(BitFlags128(0b1110)));
+    assert_eq!(BitFlags128::try_with_set_bit_range(3, 2), Err(invalid.clone()));
+    assert!(BitFlags128::try_with_set_bit_range(0, 128).is_err());
+    assert_eq!(flags.try_set_bit_range(3, 2), Err(invalid.clone()));
+    assert_eq!(flags.try_set_bit_range(0, 1), Ok(()));
+    assert_eq!(flags, BitFlags128(0b0011));
+
+    assert_eq!(flags.try_get_field(0..=1), Ok(0b11));
+    assert_eq!(flags.try_get_field(start..=end), Err(invalid.clone()));
+    assert_eq!(
+        flags.try_set_field(4..=5, 0b100),
+        Err(BitFlagsError::FieldOverflow { value: 0b100, max: 0b11 })
+    );
+    assert_eq!(flags.try_set_field(start..=end, 0), Err(invalid.clone()));
+    assert_eq!(flags, BitFlags128(0b0011));
+    assert_eq!(flags.try_set_field(4..=5, 0b10), Ok(()));
+    assert_eq!(flags, BitFlags128(0b10_0011));
+
+    assert_eq!(BitField128::try_new(3, 2), Err(invalid));
+    let field = BitField128::try_new(0, 1).unwrap();
+    assert!(field.try_set(&mut flags, 4).is_err());
+    assert_eq!(field.try_set(&mut flags, 2), Ok(()));
+    assert_eq!(flags, BitFlags128(0b10_0010));
+}
+
+#[test]
+fn bitflags128_from_str() {
+    assert_eq!("6".parse(), Ok(BitFlags128(6)));
+    assert_eq!("0x1f".parse(), Ok(BitFlags128(0x1f)));
+    assert_eq!("0b0110".parse(), Ok(BitFlags128(0b0110)));
+    assert_eq!(" BitFlags128(42) ".parse(), Ok(BitFlags128(42)));
+    assert_eq!(BitFlags128(42).to_string().parse(), Ok(BitFlags128(42)));
+
+    assert!(matches!("".parse::<BitFlags128>(), Err(BitFlagsError::Parse(_))));
+    assert!(matches!("0x".parse::<BitFlags128>(), Err(BitFlagsError::Parse(_))));
+    assert!(matches!("BitFlags128(x)".parse::<BitFlags128>(), Err(BitFlagsError::Parse(_))));
+}
+
+#[test]
+fn bitflags128_index_newtype() {
+    const LAST: Index128 = Index128::new(127);
+
+    assert_eq!(LAST, Index128::MAX);
+    assert_eq!(Index128::try_new(3).map(Index128::get), Ok(3));
+    assert_eq!(
+        Index128::try_new(128),
+        Err(BitFlagsError::IndexOutOfRange { index: 128, bits: 128 })
+    );
+    assert!(Index128::try_from(128_usize).is_err());
+    assert_eq!(usize::from(LAST), 127);
+    assert_eq!(BitFlags128::from(LAST), BitFlags128::from_index(127));
+
+    let all: Vec<usize> = Index128::all().map(usize::from).collect();
+    assert_eq!(all, (0..128).collect::<Vec<_>>());
+    assert_eq!(Index128::all().len(), 128);
+    assert_eq!(Index128::all().next_back(), Some(LAST));
+
+    let mut flags = BitFlags128::new();
+    for index in Index128::all() {
+        flags.insert_at(index);
+    }
+    assert!(flags.is_full());
+
+    flags.remove_at(Index128::MIN);
+    flags.toggle_at(Index128::new(1));
+    flags.set_at(LAST, false);
+    assert!(!flags.get_at(Index128::new(0)));
+    assert!(!flags.get_at(Index128::new(1)));
+    assert!(flags.get_at(Index128::new(2)));
+    assert_eq!(flags, BitFlags128::from_range(2..127));
+}
+
+#[test]
+#[should_panic]
+fn bitflags128_index_newtype_oob() {
+    Index128::new(128);
+}
+
+#[test]
+fn bitflags128_set_semantics() {
+    let mut flags = BitFlags128::new();
+
+    assert!(flags.add(3));
+    assert!(!flags.add(3));
+    assert!(flags.add(127));
+    assert_eq!(flags.len(), 2);
+    assert!(flags[3]);
+    assert!(!flags[2]);
+
+    assert!(flags.take(3));
+    assert!(!flags.take(3));
+    assert!(!flags.replace(1, true));
+    assert!(flags.replace(1, false));
+    assert!(!flags.replace(1, false));
+    assert_eq!(flags.try_add(1), Ok(true));
+    assert_eq!(flags.try_take(1), Ok(true));
+    assert_eq!(flags.try_replace(1, true), Ok(false));
+    assert_eq!(flags.try_replace(1, false), Ok(true));
+    assert_eq!(flags.try_add(128), Err(BitFlagsError::IndexOutOfRange { index: 128, bits: 128 }));
+    assert_eq!(flags.try_take(128), Err(BitFlagsError::IndexOutOfRange { index: 128, bits: 128 }));
+    assert!(flags.try_replace(128, true).is_err());
+    assert_eq!(flags, BitFlags128::from_index(127));
+
+    flags.clear();
+    assert!(flags.is_empty());
+    assert_eq!(flags.len(), 0);
+}
+
+#[test]
+fn bitflags128_retain_drain() {
+    let mut flags = BitFlags128(0b1111_0110);
+
+    flags.retain(|i| i % 2 == 0);
+    assert_eq!(flags, BitFlags128(0b0101_0100));
+
+    let drained: Vec<usize> = flags.drain().collect();
+    assert_eq!(drained, vec![2, 4, 6]);
+    assert!(flags.is_empty());
+}
+
+#[test]
+fn bitflags128_bit_mut() {
+    let mut flags = BitFlags128(0b0001);
+
+    let mut bit = flags.bit_mut(2);
+    assert!(!bit.get());
+    bit.set(true);
+    assert!(bit.get());
+    flags.bit_mut(0).toggle();
+    flags.bit_mut(127).set(true);
+
+    assert_eq!(flags, BitFlags128(0b0100) | BitFlags128::from_index(127));
+    assert!(flags.try_bit_mut(127).is_ok());
+    assert!(matches!(
+        flags.try_bit_mut(128),
+        Err(BitFlagsError::IndexOutOfRange { index: 128, bits: 128 })
+    ));
+}
+
+#[test]
+#[should_panic]
+fn bitflags128_index_op_oob() {
+    let flags = BitFlags128::full();
+
+    let _ = flags[128];
+}
+
+#[test]
+fn bitflags128_non_empty() {
+    assert_eq!(
+        core::mem::size_of::<Option<NonEmptyBitFlags128>>(),
+        core::mem::size_of::<BitFlags128>()
+    );
+
+    assert_eq!(NonEmptyBitFlags128::new(BitFlags128::empty()), None);
+    assert_eq!(
+        NonEmptyBitFlags128::try_from(BitFlags128::empty()),
+        Err(BitFlagsError::Empty)
+    );
+    assert!(NonEmptyBitFlags128::try_from_index(128).is_err());
+
+    let f1 = NonEmptyBitFlags128::new(BitFlags128(0b0011)).unwrap();
+    let f2 = NonEmptyBitFlags128::from_index(2);
+    let f3 = NonEmptyBitFlags128::try_from(BitFlags128(0b0110)).unwrap();
+
+    assert_eq!((f1 | f2).get(), BitFlags128(0b0111));
+    assert_eq!(f1.union(f2).to_u128(), 0b0111);
+    assert_eq!(f1.intersection(f2), None);
+    assert_eq!(f1.intersection(f3).map(BitFlags128::from), Some(BitFlags128(0b0010)));
+    assert_eq!(f2.difference(f3), None);
+    assert_eq!(f3.difference(f2).map(BitFlags128::from), Some(BitFlags128(0b0010)));
+    assert!(f3.contains(f2.get()));
+    assert!(!f1.intersects(f2.get()));
+    assert_eq!(f3.count_ones(), 2);
+    assert_eq!(f3.iter().collect::<Vec<_>>(), vec![1, 2]);
+    assert!(NonEmptyBitFlags128::full().get().is_full());
+
+    let mut f4 = f1;
+    f4 |= f2;
+    assert_eq!(f4.to_string(), "NonEmptyBitFlags128(7)");
+}
+
+#[test]
+fn bitflags128_msb0_index() {
+    let mut msb = BitFlags128::new().msb0();
+
+    msb.insert_at_index(0);
+    msb.insert_at_index(127);
+    assert_eq!(msb.lsb0(), BitFlags128::from_slice(&[0, 127]));
+
+    msb.remove_at_index(127);
+    msb.toggle_at_index(1);
+    msb.set_at_index(2, true);
+    assert_eq!(msb, Msb0BitFlags128::from_slice(&[0, 1, 2]));
+    assert_eq!(msb.lsb0(), BitFlags128::from_range(125..));
+    assert_eq!(Msb0BitFlags128::from_index(0).lsb0(), BitFlags128::from_index(127));
+
+    assert!(msb.bit_at_index(1));
+    assert!(!msb.bit_at_index(127));
+    assert_eq!(msb.get_bit_at_index(128), None);
+    assert_eq!(msb.iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+    assert!(msb.try_insert_at_index(128).is_err());
+    assert!(Msb0BitFlags128::try_from_slice(&[0, 128]).is_err());
+}
+
+#[test]
+fn bitflags128_msb0_range() {
+    let mut msb = Msb0BitFlags128::from_range(0..4);
+    assert_eq!(msb.lsb0(), BitFlags128::from_range(124..));
+
+    msb.remove_range(..=1);
+    msb.toggle_range(3..5);
+    assert_eq!(msb, Msb0BitFlags128::from_slice(&[2, 4]));
+
+    msb.insert_range(127..);
+    assert!(msb.contains_range(2..3));
+    assert!(!msb.contains_range(2..5));
+    assert!(msb.intersects_range(1..=2));
+    assert!(!msb.intersects_range(5..127));
+    assert_eq!(msb.count_ones_in(..), 3);
+    assert_eq!(msb.count_ones_in(3..), 2);
+    assert!(msb.try_insert_range(0..=128).is_err());
+}
+
+#[test]
+fn bitflags128_msb0_fmt() {
+    let msb = Msb0BitFlags128::from_slice(&[0, 1, 127]);
+
+    assert_eq!(msb.to_string(), "Msb0BitFlags128{0, 1, 127}");
+    assert_eq!(Msb0BitFlags128::empty().to_string(), "Msb0BitFlags128{}");
+    assert_eq!(format!("{:b}", Msb0BitFlags128::from_index(0)), format!("0b1{}", "0".repeat(127)));
+}
+
+#[test]
+fn bitflags128_bit_twiddling() {
+    let f = BitFlags128(0b0110_1000);
+
+    assert!(f.parity());
+    assert!(!BitFlags128(0b0110).parity());
+    assert!(!BitFlags128::empty().parity());
+    assert_eq!(f.isolate_lowest(), BitFlags128(0b1000));
+    assert_eq!(f.clear_lowest(), BitFlags128(0b0110_0000));
+    assert_eq!(f.mask_up_to_lowest(), BitFlags128(0b1111));
+    assert_eq!(BitFlags128::empty().isolate_lowest(), BitFlags128::empty());
+    assert_eq!(BitFlags128::empty().clear_lowest(), BitFlags128::empty());
+    assert_eq!(BitFlags128::empty().mask_up_to_lowest(), BitFlags128::full());
+
+    assert!(!f.is_single_bit());
+    assert!(!BitFlags128::empty().is_single_bit());
+    assert_eq!(f.single_index(), None);
+    assert_eq!(BitFlags128::from_index(127).single_index(), Some(127));
+
+    assert_eq!(f.smear_right(), BitFlags128(0b0111_1111));
+    assert_eq!(f.smear_left(), !BitFlags128(0b0111));
+    assert_eq!(BitFlags128::empty().smear_left(), BitFlags128::empty());
+    assert_eq!(BitFlags128::empty().smear_right(), BitFlags128::empty());
+
+    assert_eq!(BitFlags128(1).reverse_bits(), BitFlags128::from_index(127));
+    assert_eq!(f.reverse_bits().reverse_bits(), f);
+}
+
+#[test]
+fn bitflags128_blend() {
+    let mask = BitFlags128(0b1100);
+    let a = BitFlags128(0b1010);
+    let b = BitFlags128(0b0101);
+
+    assert_eq!(BitFlags128::blend(mask, a, b), BitFlags128(0b1001));
+    assert_eq!(BitFlags128::blend(BitFlags128::full(), a, b), a);
+    assert_eq!(BitFlags128::blend(BitFlags128::empty(), a, b), b);
+}
+
+#[test]
+fn bitflags128_next_same_popcount() {
+    assert_eq!(BitFlags128::empty().next_same_popcount(), None);
+    assert_eq!(BitFlags128(0b0111).next_same_popcount(), Some(BitFlags128(0b1011)));
+    assert_eq!(BitFlags128(0b1011).next_same_popcount(), Some(BitFlags128(0b1101)));
+    assert_eq!(BitFlags128(0b1110).next_same_popcount(), Some(BitFlags128(0b1_0011)));
+    assert_eq!(BitFlags128::full().next_same_popcount(), None);
+    assert_eq!(BitFlags128::from_index(127).next_same_popcount(), None);
+    assert_eq!(
+        BitFlags128::from_index(126).next_same_popcount(),
+        Some(BitFlags128::from_index(127))
+    );
+
+    // All 3-bit subsets of the lowest 6 bits, in increasing order
+    let mut count = 1;
+    let mut f = BitFlags128(0b0111);
+    while let Some(next) = f.next_same_popcount().filter(|n| n.0 < 64) {
+        assert!(next.0 > f.0);
+        assert_eq!(next.count_ones(), 3);
+        f = next;
+        count += 1;
+    }
+    assert_eq!(count, 20);
+}
+
+fn naive_extract128(val: u128, mask: u128) -> u128 {
+    let mut result = 0;
+    let mut k = 0;
+    for i in 0..128 {
+        if (mask >> i) & 1 == 1 {
+            result |= ((val >> i) & 1) << k;
+            k += 1;
+        }
+    }
+    result
+}
+
+fn naive_deposit128(val: u128, mask: u128) -> u128 {
+    let mut result = 0;
+    let mut k = 0;
+    for i in 0..128 {
+        if (mask >> i) & 1 == 1 {
+            result |= ((val >> k) & 1) << i;
+            k += 1;
+        }
+    }
+    result
+}
+
+fn check_extract_deposit128(val: u128, mask: u128) {
+    let (f, m) = (BitFlags128(val), BitFlags128(mask));
+    let extracted = f.extract(m);
+    let deposited = f.deposit(m);
+
+    assert_eq!(extracted.0, naive_extract128(val, mask), "extract({:#x}, {:#x})", val, mask);
+    assert_eq!(deposited.0, naive_deposit128(val, mask), "deposit({:#x}, {:#x})", val, mask);
+    assert_eq!(extracted.deposit(m), f & m);
+}
+
+#[test]
+fn bitflags128_extract_deposit() {
+    let f = BitFlags128(0b1010_0110);
+
+    assert_eq!(f.extract(BitFlags128(0b1111_0000)), BitFlags128(0b1010));
+    assert_eq!(f.extract(BitFlags128(0b0101_0101)), BitFlags128(0b0010));
+    assert_eq!(f.extract(BitFlags128::full()), f);
+    assert_eq!(f.extract(BitFlags128::empty()), BitFlags128::empty());
+    assert_eq!(BitFlags128(0b1010).deposit(BitFlags128(0b1111_0000)), BitFlags128(0b1010_0000));
+    assert_eq!(BitFlags128(0b1010).deposit(BitFlags128(0b0101_0101)), BitFlags128(0b0100_0100));
+    assert_eq!(f.deposit(BitFlags128::full()), f);
+    assert_eq!(f.deposit(BitFlags128::empty()), BitFlags128::empty());
+}
+
+#[test]
+fn bitflags128_extract_deposit_randomized() {
+    // xorshift64*, seeded for reproducibility
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    };
+
+    for _ in 0..10_000 {
+        let val = (next() as u128) << 64 | next() as u128;
+        let mask = (next() as u128) << 64 | next() as u128;
+        check_extract_deposit128(val, mask);
+        check_extract_deposit128(val, mask & (mask >> 1));
+    }
+}
+
+#[test]
+fn bitflags128_rank_select() {
+    let values = [
+        BitFlags128::empty(),
+        BitFlags128::full(),
+        BitFlags128(0b1011_0100),
+        BitFlags128::from_slice(&[0, 3, 64, 127]),
+        BitFlags128(u128::MAX / 3),
+    ];
+
+    for f in values {
+        let set: Vec<usize> = f.iter().collect();
+        let unset: Vec<usize> = (!f).iter().collect();
+
+        for i in 0..=128 {
+            let expected = set.iter().filter(|&&s| s < i).count() as u32;
+            assert_eq!(f.rank(i), expected);
+            assert_eq!(f.try_rank(i), Ok(expected));
+        }
+        for i in 0..128 {
+            assert_eq!(f.rank_inclusive(i), f.rank(i + 1));
+        }
+        for k in 0..=128 {
+            assert_eq!(f.select(k), set.get(k).copied());
+            assert_eq!(f.select_unset(k), unset.get(k).copied());
+        }
+        assert_eq!(
+            f.try_rank(129),
+            Err(BitFlagsError::InvalidRange { start: 0, end: 129, bits: 128 })
+        );
+        assert!(f.try_rank_inclusive(128).is_err());
+    }
+}
+
+#[test]
+#[should_panic]
+fn bitflags128_rank_oob() {
+    BitFlags128::full().rank(129);
+}
+
+#[test]
+fn bitflags128_submasks() {
+    let flags = BitFlags128(0b0101);
+    let subs: Vec<BitFlags128> = flags.submasks().collect();
+    assert_eq!(
+        subs,
+        vec![
+            BitFlags128(0),
+            BitFlags128(0b0001),
+            BitFlags128(0b0100),
+            BitFlags128(0b0101)
+        ]
+    );
+    assert_eq!(flags.nonempty_submasks().count(), 3);
+    assert_eq!(BitFlags128(0).submasks().collect::<Vec<_>>(), vec![BitFlags128(0)]);
+    assert_eq!(BitFlags128(0).nonempty_submasks().count(), 0);
+
+    let high = BitFlags128::from_slice(&[0, 64, 127]);
+    assert_eq!(high.submasks().count(), 8);
+    assert!(high.submasks().all(|s| high.contains(s)));
+    assert_eq!(high.submasks().last(), Some(high));
+
+    let sups: Vec<BitFlags128> =
+        BitFlags128(0b0001).supersets_within(BitFlags128(0b1011)).collect();
+    assert_eq!(
+        sups,
+        vec![
+            BitFlags128(0b0001),
+            BitFlags128(0b0011),
+            BitFlags128(0b1001),
+            BitFlags128(0b1011)
+        ]
+    );
+    assert_eq!(BitFlags128(0b0100).supersets_within(BitFlags128(0b1011)).count(), 0);
+    assert_eq!(
+        BitFlags128::full()
+            .supersets_within(BitFlags128::full())
+            .collect::<Vec<_>>(),
+        vec![BitFlags128::full()]
+    );
+}
+
+#[test]
+fn bitflags128_combinations() {
+    let flags = BitFlags128(0b0111);
+    let combos: Vec<BitFlags128> = flags.combinations(2).collect();
+    assert_eq!(combos, vec![BitFlags128(0b0011), BitFlags128(0b0101), BitFlags128(0b0110)]);
+    assert_eq!(flags.combinations(0).collect::<Vec<_>>(), vec![BitFlags128(0)]);
+    assert_eq!(flags.combinations(3).collect::<Vec<_>>(), vec![flags]);
+    assert_eq!(flags.combinations(4).count(), 0);
+
+    let sparse = BitFlags128::from_slice(&[1, 3, 64, 127]);
+    let combos: Vec<BitFlags128> = sparse.combinations(2).collect();
+    assert_eq!(combos.len(), 6);
+    assert!(combos.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(combos.iter().all(|c| sparse.contains(*c) && c.0.count_ones() == 2));
+
+    let full = BitFlags128::full();
+    assert_eq!(full.combinations(1).count(), 128);
+    assert_eq!(full.combinations(127).last(), Some(BitFlags128(u128::MAX - 1)));
+    assert_eq!(full.combinations(128).collect::<Vec<_>>(), vec![full]);
+}
+
+#[test]
+fn bitflags128_gray_code() {
+    let flags = BitFlags128(0b0101);
+    let gray: Vec<BitFlags128> = flags.gray_code().collect();
+    assert_eq!(
+        gray,
+        vec![
+            BitFlags128(0),
+            BitFlags128(0b0001),
+            BitFlags128(0b0101),
+            BitFlags128(0b0100)
+        ]
+    );
+
+    let sparse = BitFlags128::from_slice(&[0, 2, 5, 64, 127]);
+    let gray: Vec<BitFlags128> = sparse.gray_code().collect();
+    assert_eq!(gray.len(), 32);
+    assert!(gray.windows(2).all(|w| (w[0] ^ w[1]).0.count_ones() == 1));
+    let mut sorted = gray.clone();
+    sorted.sort_by_key(|f| f.0);
+    assert_eq!(sorted, sparse.submasks().collect::<Vec<_>>());
+    assert_eq!(BitFlags128(0).gray_code().collect::<Vec<_>>(), vec![BitFlags128(0)]);
+}
+
+#[test]
+fn bitflags128_combinatorial_rank() {
+    let ranks: Vec<u128> = BitFlags128(0b0111)
+        .combinations(2)
+        .map(|f| f.combinatorial_rank())
+        .collect();
+    assert_eq!(ranks, vec![0, 1, 2]);
+    assert_eq!(BitFlags128(0).combinatorial_rank(), 0);
+    assert_eq!(BitFlags128::full().combinatorial_rank(), 0);
+    assert_eq!(BitFlags128::from_index(127).combinatorial_rank(), 127);
+
+    let top_half = BitFlags128(u128::MAX << 64);
+    let last = top_half.combinatorial_rank();
+    assert_eq!(last, 23951146041928082866135587776380551749); // C(128, 64) - 1
+    assert_eq!(BitFlags128::from_combinatorial_rank(64, last), Some(top_half));
+    assert_eq!(BitFlags128::from_combinatorial_rank(64, last + 1), None);
+    assert_eq!(BitFlags128::from_combinatorial_rank(128, 0), Some(BitFlags128::full()));
+    assert_eq!(BitFlags128::from_combinatorial_rank(128, 1), None);
+    assert_eq!(BitFlags128::from_combinatorial_rank(129, 0), None);
+    assert_eq!(BitFlags128::from_combinatorial_rank(0, 0), Some(BitFlags128(0)));
+
+    for flags in [
+        BitFlags128::from_slice(&[1, 3, 64, 127]),
+        BitFlags128(u128::MAX / 3),
+        BitFlags128(!0b1001),
+    ] {
+        let k = flags.0.count_ones();
+        assert_eq!(
+            BitFlags128::from_combinatorial_rank(k, flags.combinatorial_rank()),
+            Some(flags)
+        );
+    }
+
+    for (rank, flags) in BitFlags128::full().combinations(3).take(5000).enumerate() {
+        assert_eq!(flags.combinatorial_rank(), rank as u128);
+        assert_eq!(BitFlags128::from_combinatorial_rank(3, rank as u128), Some(flags));
+    }
+}
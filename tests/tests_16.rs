@@ -1,6 +1,9 @@
 //! Tests for the `BitFlags16` struct.
 
-use arctan_bitflags::BitFlags16;
+use arctan_bitflags::{
+    BitField16, BitFlags16, BitFlagsError, Index16, Msb0BitFlags16, NonEmptyBitFlags16,
+};
+use core::convert::TryFrom;
 
 #[test]
 fn bitflags16_contains() {
@@ -75,13 +78,13 @@ fn bitflags16_ops() {
     assert_eq!(f1 ^ f2, BitFlags16(0b0011));
     assert_eq!(f1 ^ f3, BitFlags16(0b1000));
 
-    assert_eq!(!f1, BitFlags16(0b11111111_1111_1110));
-    assert_eq!(!f2, BitFlags16(0b11111111_1111_1101));
-    assert_eq!(!f3, BitFlags16(0b11111111_1111_0110));
+    assert_eq!(!f1, BitFlags16(0b11111111_11111110));
+    assert_eq!(!f2, BitFlags16(0b11111111_11111101));
+    assert_eq!(!f3, BitFlags16(0b11111111_11110110));
 
-    assert_eq!(f1.complement(), BitFlags16(0b11111111_1111_1110));
-    assert_eq!(f2.complement(), BitFlags16(0b11111111_1111_1101));
-    assert_eq!(f3.complement(), BitFlags16(0b11111111_1111_0110));
+    assert_eq!(f1.complement(), BitFlags16(0b11111111_11111110));
+    assert_eq!(f2.complement(), BitFlags16(0b11111111_11111101));
+    assert_eq!(f3.complement(), BitFlags16(0b11111111_11110110));
 }
 
 #[test]
@@ -203,8 +206,8 @@ fn bitflags16_index() {
     let ix_0 = f1.bit_at_index(0);
     let ix_7 = f1.bit_at_index(7);
 
-    assert_eq!(ix_0, false);
-    assert_eq!(ix_7, true);
+    assert!(!ix_0);
+    assert!(ix_7);
 }
 
 #[test]
@@ -486,4 +489,666 @@ fn bitflags16_with_set_bit_range() {
     ];
 
     assert_eq!(actual, expected);
-}
\ No newline at end of file
+}
+
+#[test]
+fn bitflags16_field() {
+    let mut flags = BitFlags16(0b1000_0001);
+
+    flags.set_field(1..=3, 0b101);
+    assert_eq!(flags, BitFlags16(0b1000_1011));
+    assert_eq!(flags.get_field(1..=3), 0b101);
+    assert_eq!(flags.get_field(0..=0), 1);
+    assert_eq!(flags.get_field(7..=7), 1);
+
+    flags.set_field(1..=3, 0);
+    assert_eq!(flags, BitFlags16(0b1000_0001));
+
+    let mut flags = BitFlags16::new();
+    flags.set_field(0..=15, u16::MAX);
+    assert!(flags.is_full());
+    assert_eq!(flags.get_field(0..=15), u16::MAX);
+}
+
+#[test]
+#[should_panic]
+fn bitflags16_field_overflow() {
+    let mut flags = BitFlags16::new();
+
+    flags.set_field(1..=3, 0b1000);
+}
+
+#[test]
+fn bitflags16_named_field() {
+    const FLAG: BitField16 = BitField16::new(0, 0);
+    const LEVEL: BitField16 = BitField16::new(4, 6);
+
+    let mut flags = BitFlags16::new();
+    FLAG.set(&mut flags, 1);
+    LEVEL.set(&mut flags, 5);
+
+    assert_eq!(flags, BitFlags16(0b0101_0001));
+    assert_eq!(FLAG.get(flags), 1);
+    assert_eq!(LEVEL.get(flags), 5);
+    assert_eq!(LEVEL.width(), 3);
+    assert_eq!(LEVEL.max_value(), 7);
+    assert_eq!(LEVEL.mask(), BitFlags16(0b0111_0000));
+}
+
+#[test]
+fn bitflags16_range() {
+    assert_eq!(BitFlags16::from_range(1..4), BitFlags16(0b0000_1110));
+    assert_eq!(BitFlags16::from_range(1..=4), BitFlags16(0b0001_1110));
+    assert_eq!(BitFlags16::from_range(3..3), BitFlags16::empty());
+    assert_eq!(BitFlags16::from_range(..), BitFlags16::full());
+    assert_eq!(BitFlags16::from_range(..=15), BitFlags16::full());
+    assert_eq!(BitFlags16::from_range(15..), BitFlags16::from_index(15));
+
+    let mut flags = BitFlags16(0b1000_0001);
+    flags.insert_range(1..3);
+    assert_eq!(flags, BitFlags16(0b1000_0111));
+    flags.remove_range(..2);
+    assert_eq!(flags, BitFlags16(0b1000_0100));
+    flags.toggle_range(2..=3);
+    assert_eq!(flags, BitFlags16(0b1000_1000));
+
+    assert!(flags.contains_range(3..4));
+    assert!(flags.contains_range(5..5));
+    assert!(!flags.contains_range(3..=4));
+    assert!(flags.intersects_range(3..=4));
+    assert!(!flags.intersects_range(4..7));
+    assert_eq!(flags.count_ones_in(..), 2);
+    assert_eq!(flags.count_ones_in(..7), 1);
+}
+
+#[test]
+fn bitflags16_try_range() {
+    let mut flags = BitFlags16(0b0001);
+    let (start, end) = (4, 2);
+
+    assert_eq!(BitFlags16::try_from_range(0..2), Ok(BitFlags16(0b0011)));
+    assert!(BitFlags16::try_from_range(0..=16).is_err());
+    assert!(flags.try_insert_range(1..=16).is_err());
+    assert!(flags.try_remove_range(16..32).is_err());
+    assert!(flags.try_toggle_range(start..end).is_err());
+    assert_eq!(flags, BitFlags16(0b0001));
+
+    assert_eq!(flags.try_insert_range(1..2), Ok(()));
+    assert_eq!(flags.try_contains_range(..2), Ok(true));
+    assert_eq!(flags.try_intersects_range(2..), Ok(false));
+    assert_eq!(flags.try_count_ones_in(1..), Ok(1));
+    assert!(flags.try_count_ones_in(..32).is_err());
+}
+
+#[test]
+#[should_panic]
+fn bitflags16_range_oob() {
+    let mut flags = BitFlags16::new();
+
+    flags.insert_range(0..18);
+}
+
+#[test]
+fn bitflags16_try_index() {
+    let mut flags = BitFlags16::new();
+    let oob = BitFlagsError::IndexOutOfRange { index: 16, bits: 16 };
+
+    assert_eq!(BitFlags16::try_from_index(15), Ok(BitFlags16::from_index(15)));
+    assert_eq!(BitFlags16::try_from_index(16), Err(oob.clone()));
+    assert_eq!(BitFlags16::try_from(16_usize), Err(oob.clone()));
+    assert_eq!(BitFlags16::try_from_slice(&[2, 4, 5]), Ok(BitFlags16(0b0011_0100)));
+    assert_eq!(BitFlags16::try_from_slice(&[2, 16, 5]), Err(oob.clone()));
+
+    assert_eq!(flags.try_insert_at_index(16), Err(oob.clone()));
+    assert_eq!(flags.try_set_at_index(16, true), Err(oob.clone()));
+    assert_eq!(flags.try_toggle_at_index(16), Err(oob.clone()));
+    assert_eq!(flags.try_remove_at_index(16), Err(oob.clone()));
+    assert_eq!(flags.try_bit_at_index(16), Err(oob));
+    assert!(flags.is_empty());
+
+    assert_eq!(flags.try_insert_at_index(0), Ok(()));
+    assert_eq!(flags.try_set_at_index(1, true), Ok(()));
+    assert_eq!(flags.try_toggle_at_index(2), Ok(()));
+    assert_eq!(flags.try_remove_at_index(0), Ok(()));
+    assert_eq!(flags.try_bit_at_index(1), Ok(true));
+    assert_eq!(flags, BitFlags16(0b0110));
+}
+
+#[test]
+fn bitflags16_try_bit_range() {
+    let mut flags = BitFlags16::new();
+    let invalid = BitFlagsError::InvalidRange { start: 3, end: 3, bits: 16 };
+    let (start, end) = (3, 2);
+
+    assert_eq!(BitFlags16::try_with_set_bit_range(1, 3), Ok(BitFlags16(0b1110)));
+    assert_eq!(BitFlags16::try_with_set_bit_range(3, 2), Err(invalid.clone()));
+    assert!(BitFlags16::try_with_set_bit_range(0, 16).is_err());
+    assert_eq!(flags.try_set_bit_range(3, 2), Err(invalid.clone()));
+    assert_eq!(flags.try_set_bit_range(0, 1), Ok(()));
+    assert_eq!(flags, BitFlags16(0b0011));
+
+    assert_eq!(flags.try_get_field(0..=1), Ok(0b11));
+    assert_eq!(flags.try_get_field(start..=end), Err(invalid.clone()));
+    assert_eq!(
+        flags.try_set_field(4..=5, 0b100),
+        Err(BitFlagsError::FieldOverflow { value: 0b100, max: 0b11 })
+    );
+    assert_eq!(flags.try_set_field(start..=end, 0), Err(invalid.clone()));
+    assert_eq!(flags, BitFlags16(0b0011));
+    assert_eq!(flags.try_set_field(4..=5, 0b10), Ok(()));
+    assert_eq!(flags, BitFlags16(0b10_0011));
+
+    assert_eq!(BitField16::try_new(3, 2), Err(invalid));
+    let field = BitField16::try_new(0, 1).unwrap();
+    assert!(field.try_set(&mut flags, 4).is_err());
+    assert_eq!(field.try_set(&mut flags, 2), Ok(()));
+    assert_eq!(flags, BitFlags16(0b10_0010));
+}
+
+#[test]
+fn bitflags16_from_str() {
+    assert_eq!("6".parse(), Ok(BitFlags16(6)));
+    assert_eq!("0x1f".parse(), Ok(BitFlags16(0x1f)));
+    assert_eq!("0b0110".parse(), Ok(BitFlags16(0b0110)));
+    assert_eq!(" BitFlags16(42) ".parse(), Ok(BitFlags16(42)));
+    assert_eq!(BitFlags16(42).to_string().parse(), Ok(BitFlags16(42)));
+
+    assert!(matches!("".parse::<BitFlags16>(), Err(BitFlagsError::Parse(_))));
+    assert!(matches!("0x".parse::<BitFlags16>(), Err(BitFlagsError::Parse(_))));
+    assert!(matches!("BitFlags16(x)".parse::<BitFlags16>(), Err(BitFlagsError::Parse(_))));
+}
+
+#[test]
+fn bitflags16_index_newtype() {
+    const LAST: Index16 = Index16::new(15);
+
+    assert_eq!(LAST, Index16::MAX);
+    assert_eq!(Index16::try_new(3).map(Index16::get), Ok(3));
+    assert_eq!(
+        Index16::try_new(16),
+        Err(BitFlagsError::IndexOutOfRange { index: 16, bits: 16 })
+    );
+    assert!(Index16::try_from(16_usize).is_err());
+    assert_eq!(usize::from(LAST), 15);
+    assert_eq!(BitFlags16::from(LAST), BitFlags16::from_index(15));
+
+    let all: Vec<usize> = Index16::all().map(usize::from).collect();
+    assert_eq!(all, (0..16).collect::<Vec<_>>());
+    assert_eq!(Index16::all().len(), 16);
+    assert_eq!(Index16::all().next_back(), Some(LAST));
+
+    let mut flags = BitFlags16::new();
+    for index in Index16::all() {
+        flags.insert_at(index);
+    }
+    assert!(flags.is_full());
+
+    flags.remove_at(Index16::MIN);
+    flags.toggle_at(Index16::new(1));
+    flags.set_at(LAST, false);
+    assert!(!flags.get_at(Index16::new(0)));
+    assert!(!flags.get_at(Index16::new(1)));
+    assert!(flags.get_at(Index16::new(2)));
+    assert_eq!(flags, BitFlags16::from_range(2..15));
+}
+
+#[test]
+#[should_panic]
+fn bitflags16_index_newtype_oob() {
+    Index16::new(16);
+}
+
+#[test]
+fn bitflags16_set_semantics() {
+    let mut flags = BitFlags16::new();
+
+    assert!(flags.add(3));
+    assert!(!flags.add(3));
+    assert!(flags.add(15));
+    assert_eq!(flags.len(), 2);
+    assert!(flags[3]);
+    assert!(!flags[2]);
+
+    assert!(flags.take(3));
+    assert!(!flags.take(3));
+    assert!(!flags.replace(1, true));
+    assert!(flags.replace(1, false));
+    assert!(!flags.replace(1, false));
+    assert_eq!(flags.try_add(1), Ok(true));
+    assert_eq!(flags.try_take(1), Ok(true));
+    assert_eq!(flags.try_replace(1, true), Ok(false));
+    assert_eq!(flags.try_replace(1, false), Ok(true));
+    assert_eq!(flags.try_add(16), Err(BitFlagsError::IndexOutOfRange { index: 16, bits: 16 }));
+    assert_eq!(flags.try_take(16), Err(BitFlagsError::IndexOutOfRange { index: 16, bits: 16 }));
+    assert!(flags.try_replace(16, true).is_err());
+    assert_eq!(flags, BitFlags16::from_index(15));
+
+    flags.clear();
+    assert!(flags.is_empty());
+    assert_eq!(flags.len(), 0);
+}
+
+#[test]
+fn bitflags16_retain_drain() {
+    let mut flags = BitFlags16(0b1111_0110);
+
+    flags.retain(|i| i % 2 == 0);
+    assert_eq!(flags, BitFlags16(0b0101_0100));
+
+    let drained: Vec<usize> = flags.drain().collect();
+    assert_eq!(drained, vec![2, 4, 6]);
+    assert!(flags.is_empty());
+}
+
+#[test]
+fn bitflags16_bit_mut() {
+    let mut flags = BitFlags16(0b0001);
+
+    let mut bit = flags.bit_mut(2);
+    assert!(!bit.get());
+    bit.set(true);
+    assert!(bit.get());
+    flags.bit_mut(0).toggle();
+    flags.bit_mut(15).set(true);
+
+    assert_eq!(flags, BitFlags16(0b0100) | BitFlags16::from_index(15));
+    assert!(flags.try_bit_mut(15).is_ok());
+    assert!(matches!(
+        flags.try_bit_mut(16),
+        Err(BitFlagsError::IndexOutOfRange { index: 16, bits: 16 })
+    ));
+}
+
+#[test]
+#[should_panic]
+fn bitflags16_index_op_oob() {
+    let flags = BitFlags16::full();
+
+    let _ = flags[16];
+}
+
+#[test]
+fn bitflags16_non_empty() {
+    assert_eq!(
+        core::mem::size_of::<Option<NonEmptyBitFlags16>>(),
+        core::mem::size_of::<BitFlags16>()
+    );
+
+    assert_eq!(NonEmptyBitFlags16::new(BitFlags16::empty()), None);
+    assert_eq!(
+        NonEmptyBitFlags16::try_from(BitFlags16::empty()),
+        Err(BitFlagsError::Empty)
+    );
+    assert!(NonEmptyBitFlags16::try_from_index(16).is_err());
+
+    let f1 = NonEmptyBitFlags16::new(BitFlags16(0b0011)).unwrap();
+    let f2 = NonEmptyBitFlags16::from_index(2);
+    let f3 = NonEmptyBitFlags16::try_from(BitFlags16(0b0110)).unwrap();
+
+    assert_eq!((f1 | f2).get(), BitFlags16(0b0111));
+    assert_eq!(f1.union(f2).to_u16(), 0b0111);
+    assert_eq!(f1.intersection(f2), None);
+    assert_eq!(f1.intersection(f3).map(BitFlags16::from), Some(BitFlags16(0b0010)));
+    assert_eq!(f2.difference(f3), None);
+    assert_eq!(f3.difference(f2).map(BitFlags16::from), Some(BitFlags16(0b0010)));
+    assert!(f3.contains(f2.get()));
+    assert!(!f1.intersects(f2.get()));
+    assert_eq!(f3.count_ones(), 2);
+    assert_eq!(f3.iter().collect::<Vec<_>>(), vec![1, 2]);
+    assert!(NonEmptyBitFlags16::full().get().is_full());
+
+    let mut f4 = f1;
+    f4 |= f2;
+    assert_eq!(f4.to_string(), "NonEmptyBitFlags16(7)");
+}
+
+#[test]
+fn bitflags16_msb0_index() {
+    let mut msb = BitFlags16::new().msb0();
+
+    msb.insert_at_index(0);
+    msb.insert_at_index(15);
+    assert_eq!(msb.lsb0(), BitFlags16::from_slice(&[0, 15]));
+
+    msb.remove_at_index(15);
+    msb.toggle_at_index(1);
+    msb.set_at_index(2, true);
+    assert_eq!(msb, Msb0BitFlags16::from_slice(&[0, 1, 2]));
+    assert_eq!(msb.lsb0(), BitFlags16::from_range(13..));
+    assert_eq!(Msb0BitFlags16::from_index(0).lsb0(), BitFlags16::from_index(15));
+
+    assert!(msb.bit_at_index(1));
+    assert!(!msb.bit_at_index(15));
+    assert_eq!(msb.get_bit_at_index(16), None);
+    assert_eq!(msb.iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+    assert!(msb.try_insert_at_index(16).is_err());
+    assert!(Msb0BitFlags16::try_from_slice(&[0, 16]).is_err());
+}
+
+#[test]
+fn bitflags16_msb0_range() {
+    let mut msb = Msb0BitFlags16::from_range(0..4);
+    assert_eq!(msb.lsb0(), BitFlags16::from_range(12..));
+
+    msb.remove_range(..=1);
+    msb.toggle_range(3..5);
+    assert_eq!(msb, Msb0BitFlags16::from_slice(&[2, 4]));
+
+    msb.insert_range(15..);
+    assert!(msb.contains_range(2..3));
+    assert!(!msb.contains_range(2..5));
+    assert!(msb.intersects_range(1..=2));
+    assert!(!msb.intersects_range(5..15));
+    assert_eq!(msb.count_ones_in(..), 3);
+    assert_eq!(msb.count_ones_in(3..), 2);
+    assert!(msb.try_insert_range(0..=16).is_err());
+}
+
+#[test]
+fn bitflags16_msb0_fmt() {
+    let msb = Msb0BitFlags16::from_slice(&[0, 1, 15]);
+
+    assert_eq!(msb.to_string(), "Msb0BitFlags16{0, 1, 15}");
+    assert_eq!(Msb0BitFlags16::empty().to_string(), "Msb0BitFlags16{}");
+    assert_eq!(format!("{:b}", Msb0BitFlags16::from_index(0)), "0b1000000000000000");
+}
+
+#[test]
+fn bitflags16_bit_twiddling() {
+    let f = BitFlags16(0b0110_1000);
+
+    assert!(f.parity());
+    assert!(!BitFlags16(0b0110).parity());
+    assert!(!BitFlags16::empty().parity());
+    assert_eq!(f.isolate_lowest(), BitFlags16(0b1000));
+    assert_eq!(f.clear_lowest(), BitFlags16(0b0110_0000));
+    assert_eq!(f.mask_up_to_lowest(), BitFlags16(0b1111));
+    assert_eq!(BitFlags16::empty().isolate_lowest(), BitFlags16::empty());
+    assert_eq!(BitFlags16::empty().clear_lowest(), BitFlags16::empty());
+    assert_eq!(BitFlags16::empty().mask_up_to_lowest(), BitFlags16::full());
+
+    assert!(!f.is_single_bit());
+    assert!(!BitFlags16::empty().is_single_bit());
+    assert_eq!(f.single_index(), None);
+    assert_eq!(BitFlags16::from_index(15).single_index(), Some(15));
+
+    assert_eq!(f.smear_right(), BitFlags16(0b0111_1111));
+    assert_eq!(f.smear_left(), !BitFlags16(0b0111));
+    assert_eq!(BitFlags16::empty().smear_left(), BitFlags16::empty());
+    assert_eq!(BitFlags16::empty().smear_right(), BitFlags16::empty());
+
+    assert_eq!(BitFlags16(1).reverse_bits(), BitFlags16::from_index(15));
+    assert_eq!(f.reverse_bits().reverse_bits(), f);
+}
+
+#[test]
+fn bitflags16_blend() {
+    let mask = BitFlags16(0b1100);
+    let a = BitFlags16(0b1010);
+    let b = BitFlags16(0b0101);
+
+    assert_eq!(BitFlags16::blend(mask, a, b), BitFlags16(0b1001));
+    assert_eq!(BitFlags16::blend(BitFlags16::full(), a, b), a);
+    assert_eq!(BitFlags16::blend(BitFlags16::empty(), a, b), b);
+}
+
+#[test]
+fn bitflags16_next_same_popcount() {
+    assert_eq!(BitFlags16::empty().next_same_popcount(), None);
+    assert_eq!(BitFlags16(0b0111).next_same_popcount(), Some(BitFlags16(0b1011)));
+    assert_eq!(BitFlags16(0b1011).next_same_popcount(), Some(BitFlags16(0b1101)));
+    assert_eq!(BitFlags16(0b1110).next_same_popcount(), Some(BitFlags16(0b1_0011)));
+    assert_eq!(BitFlags16::full().next_same_popcount(), None);
+    assert_eq!(BitFlags16::from_index(15).next_same_popcount(), None);
+    assert_eq!(
+        BitFlags16::from_index(14).next_same_popcount(),
+        Some(BitFlags16::from_index(15))
+    );
+
+    // All 3-bit subsets of the lowest 6 bits, in increasing order
+    let mut count = 1;
+    let mut f = BitFlags16(0b0111);
+    while let Some(next) = f.next_same_popcount().filter(|n| n.0 < 64) {
+        assert!(next.0 > f.0);
+        assert_eq!(next.count_ones(), 3);
+        f = next;
+        count += 1;
+    }
+    assert_eq!(count, 20);
+}
+
+fn naive_extract16(val: u16, mask: u16) -> u16 {
+    let mut result = 0;
+    let mut k = 0;
+    for i in 0..16 {
+        if (mask >> i) & 1 == 1 {
+            result |= ((val >> i) & 1) << k;
+            k += 1;
+        }
+    }
+    result
+}
+
+fn naive_deposit16(val: u16, mask: u16) -> u16 {
+    let mut result = 0;
+    let mut k = 0;
+    for i in 0..16 {
+        if (mask >> i) & 1 == 1 {
+            result |= ((val >> k) & 1) << i;
+            k += 1;
+        }
+    }
+    result
+}
+
+fn check_extract_deposit16(val: u16, mask: u16) {
+    let (f, m) = (BitFlags16(val), BitFlags16(mask));
+    let extracted = f.extract(m);
+    let deposited = f.deposit(m);
+
+    assert_eq!(extracted.0, naive_extract16(val, mask), "extract({:#x}, {:#x})", val, mask);
+    assert_eq!(deposited.0, naive_deposit16(val, mask), "deposit({:#x}, {:#x})", val, mask);
+    assert_eq!(extracted.deposit(m), f & m);
+}
+
+#[test]
+fn bitflags16_extract_deposit() {
+    let f = BitFlags16(0b1010_0110);
+
+    assert_eq!(f.extract(BitFlags16(0b1111_0000)), BitFlags16(0b1010));
+    assert_eq!(f.extract(BitFlags16(0b0101_0101)), BitFlags16(0b0010));
+    assert_eq!(f.extract(BitFlags16::full()), f);
+    assert_eq!(f.extract(BitFlags16::empty()), BitFlags16::empty());
+    assert_eq!(BitFlags16(0b1010).deposit(BitFlags16(0b1111_0000)), BitFlags16(0b1010_0000));
+    assert_eq!(BitFlags16(0b1010).deposit(BitFlags16(0b0101_0101)), BitFlags16(0b0100_0100));
+    assert_eq!(f.deposit(BitFlags16::full()), f);
+    assert_eq!(f.deposit(BitFlags16::empty()), BitFlags16::empty());
+}
+
+#[test]
+fn bitflags16_extract_deposit_exhaustive() {
+    // Every mask against a spread of values, and every value against a spread of masks
+    let spread: Vec<u16> = (0..16).map(|i| 0x9e37_u16.rotate_left(i) ^ (1 << i)).collect();
+
+    for mask in 0..=u16::MAX {
+        for &val in spread.iter().chain(&[0, u16::MAX]) {
+            check_extract_deposit16(val, mask);
+            check_extract_deposit16(mask, val);
+        }
+    }
+}
+
+#[test]
+fn bitflags16_rank_select() {
+    let values = [
+        BitFlags16::empty(),
+        BitFlags16::full(),
+        BitFlags16(0b1011_0100),
+        BitFlags16::from_slice(&[0, 3, 8, 15]),
+        BitFlags16(u16::MAX / 3),
+    ];
+
+    for f in values {
+        let set: Vec<usize> = f.iter().collect();
+        let unset: Vec<usize> = (!f).iter().collect();
+
+        for i in 0..=16 {
+            let expected = set.iter().filter(|&&s| s < i).count() as u32;
+            assert_eq!(f.rank(i), expected);
+            assert_eq!(f.try_rank(i), Ok(expected));
+        }
+        for i in 0..16 {
+            assert_eq!(f.rank_inclusive(i), f.rank(i + 1));
+        }
+        for k in 0..=16 {
+            assert_eq!(f.select(k), set.get(k).copied());
+            assert_eq!(f.select_unset(k), unset.get(k).copied());
+        }
+        assert_eq!(
+            f.try_rank(17),
+            Err(BitFlagsError::InvalidRange { start: 0, end: 17, bits: 16 })
+        );
+        assert!(f.try_rank_inclusive(16).is_err());
+    }
+}
+
+#[test]
+#[should_panic]
+fn bitflags16_rank_oob() {
+    BitFlags16::full().rank(17);
+}
+
+#[test]
+fn bitflags16_submasks() {
+    let flags = BitFlags16(0b0101);
+    let subs: Vec<BitFlags16> = flags.submasks().collect();
+    assert_eq!(
+        subs,
+        vec![
+            BitFlags16(0),
+            BitFlags16(0b0001),
+            BitFlags16(0b0100),
+            BitFlags16(0b0101)
+        ]
+    );
+    assert_eq!(flags.nonempty_submasks().count(), 3);
+    assert_eq!(BitFlags16(0).submasks().collect::<Vec<_>>(), vec![BitFlags16(0)]);
+    assert_eq!(BitFlags16(0).nonempty_submasks().count(), 0);
+
+    let high = BitFlags16::from_slice(&[0, 8, 15]);
+    assert_eq!(high.submasks().count(), 8);
+    assert!(high.submasks().all(|s| high.contains(s)));
+    assert_eq!(high.submasks().last(), Some(high));
+
+    let sups: Vec<BitFlags16> = BitFlags16(0b0001).supersets_within(BitFlags16(0b1011)).collect();
+    assert_eq!(
+        sups,
+        vec![
+            BitFlags16(0b0001),
+            BitFlags16(0b0011),
+            BitFlags16(0b1001),
+            BitFlags16(0b1011)
+        ]
+    );
+    assert_eq!(BitFlags16(0b0100).supersets_within(BitFlags16(0b1011)).count(), 0);
+    assert_eq!(
+        BitFlags16::full()
+            .supersets_within(BitFlags16::full())
+            .collect::<Vec<_>>(),
+        vec![BitFlags16::full()]
+    );
+}
+
+#[test]
+fn bitflags16_combinations() {
+    let flags = BitFlags16(0b0111);
+    let combos: Vec<BitFlags16> = flags.combinations(2).collect();
+    assert_eq!(combos, vec![BitFlags16(0b0011), BitFlags16(0b0101), BitFlags16(0b0110)]);
+    assert_eq!(flags.combinations(0).collect::<Vec<_>>(), vec![BitFlags16(0)]);
+    assert_eq!(flags.combinations(3).collect::<Vec<_>>(), vec![flags]);
+    assert_eq!(flags.combinations(4).count(), 0);
+
+    let sparse = BitFlags16::from_slice(&[1, 3, 8, 15]);
+    let combos: Vec<BitFlags16> = sparse.combinations(2).collect();
+    assert_eq!(combos.len(), 6);
+    assert!(combos.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(combos.iter().all(|c| sparse.contains(*c) && c.0.count_ones() == 2));
+
+    let full = BitFlags16::full();
+    assert_eq!(full.combinations(1).count(), 16);
+    assert_eq!(full.combinations(15).last(), Some(BitFlags16(u16::MAX - 1)));
+    assert_eq!(full.combinations(16).collect::<Vec<_>>(), vec![full]);
+}
+
+#[test]
+fn bitflags16_gray_code() {
+    let flags = BitFlags16(0b0101);
+    let gray: Vec<BitFlags16> = flags.gray_code().collect();
+    assert_eq!(
+        gray,
+        vec![
+            BitFlags16(0),
+            BitFlags16(0b0001),
+            BitFlags16(0b0101),
+            BitFlags16(0b0100)
+        ]
+    );
+
+    let sparse = BitFlags16::from_slice(&[0, 2, 5, 8, 15]);
+    let gray: Vec<BitFlags16> = sparse.gray_code().collect();
+    assert_eq!(gray.len(), 32);
+    assert!(gray.windows(2).all(|w| (w[0] ^ w[1]).0.count_ones() == 1));
+    let mut sorted = gray.clone();
+    sorted.sort_by_key(|f| f.0);
+    assert_eq!(sorted, sparse.submasks().collect::<Vec<_>>());
+    assert_eq!(BitFlags16(0).gray_code().collect::<Vec<_>>(), vec![BitFlags16(0)]);
+}
+
+#[test]
+fn bitflags16_combinatorial_rank() {
+    let ranks: Vec<u16> = BitFlags16(0b0111)
+        .combinations(2)
+        .map(|f| f.combinatorial_rank())
+        .collect();
+    assert_eq!(ranks, vec![0, 1, 2]);
+    assert_eq!(BitFlags16(0).combinatorial_rank(), 0);
+    assert_eq!(BitFlags16::full().combinatorial_rank(), 0);
+    assert_eq!(BitFlags16::from_index(15).combinatorial_rank(), 15);
+
+    let top_half = BitFlags16(u16::MAX << 8);
+    let last = top_half.combinatorial_rank();
+    assert_eq!(BitFlags16::from_combinatorial_rank(8, last), Some(top_half));
+    assert_eq!(BitFlags16::from_combinatorial_rank(8, last + 1), None);
+    assert_eq!(BitFlags16::from_combinatorial_rank(16, 0), Some(BitFlags16::full()));
+    assert_eq!(BitFlags16::from_combinatorial_rank(16, 1), None);
+    assert_eq!(BitFlags16::from_combinatorial_rank(17, 0), None);
+    assert_eq!(BitFlags16::from_combinatorial_rank(0, 0), Some(BitFlags16(0)));
+
+    for flags in [
+        BitFlags16::from_slice(&[1, 3, 8, 15]),
+        BitFlags16(u16::MAX / 3),
+        BitFlags16(!0b1001),
+    ] {
+        let k = flags.0.count_ones();
+        assert_eq!(BitFlags16::from_combinatorial_rank(k, flags.combinatorial_rank()), Some(flags));
+    }
+}
+
+#[test]
+fn bitflags16_combinatorial_rank_exhaustive() {
+    // Every rank in `0..C(16, k)` is hit exactly once by the `k`-bit masks.
+    let mut seen = vec![Vec::new(); 17];
+    for mask in 0..=u16::MAX {
+        let flags = BitFlags16(mask);
+        let k = mask.count_ones();
+        let rank = flags.combinatorial_rank();
+        assert_eq!(BitFlags16::from_combinatorial_rank(k, rank), Some(flags));
+        seen[k as usize].push(rank);
+    }
+    for (k, ranks) in seen.iter_mut().enumerate() {
+        ranks.sort_unstable();
+        assert!(ranks.iter().enumerate().all(|(i, &rank)| rank as usize == i));
+        assert_eq!(BitFlags16::from_combinatorial_rank(k as u32, ranks.len() as u16), None);
+    }
+}
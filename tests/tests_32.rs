@@ -1,6 +1,9 @@
 //! Tests for the `BitFlags32` struct.
 
-use arctan_bitflags::BitFlags32;
+use arctan_bitflags::{
+    BitField32, BitFlags32, BitFlagsError, Index32, Msb0BitFlags32, NonEmptyBitFlags32,
+};
+use core::convert::TryFrom;
 
 #[test]
 fn bitflags32_contains() {
@@ -75,13 +78,13 @@ fn bitflags32_ops() {
     assert_eq!(f1 ^ f2, BitFlags32(0b0011));
     assert_eq!(f1 ^ f3, BitFlags32(0b1000));
 
-    assert_eq!(!f1, BitFlags32(0b11111111_11111111_11111111_1111_1110));
-    assert_eq!(!f2, BitFlags32(0b11111111_11111111_11111111_1111_1101));
-    assert_eq!(!f3, BitFlags32(0b11111111_11111111_11111111_1111_0110));
+    assert_eq!(!f1, BitFlags32(0b11111111_11111111_11111111_11111110));
+    assert_eq!(!f2, BitFlags32(0b11111111_11111111_11111111_11111101));
+    assert_eq!(!f3, BitFlags32(0b11111111_11111111_11111111_11110110));
 
-    assert_eq!(f1.complement(), BitFlags32(0b11111111_11111111_11111111_1111_1110));
-    assert_eq!(f2.complement(), BitFlags32(0b11111111_11111111_11111111_1111_1101));
-    assert_eq!(f3.complement(), BitFlags32(0b11111111_11111111_11111111_1111_0110));
+    assert_eq!(f1.complement(), BitFlags32(0b11111111_11111111_11111111_11111110));
+    assert_eq!(f2.complement(), BitFlags32(0b11111111_11111111_11111111_11111101));
+    assert_eq!(f3.complement(), BitFlags32(0b11111111_11111111_11111111_11110110));
 }
 
 #[test]
@@ -236,8 +239,8 @@ fn bitflags32_index() {
     let ix_0 = f1.bit_at_index(0);
     let ix_7 = f1.bit_at_index(7);
 
-    assert_eq!(ix_0, false);
-    assert_eq!(ix_7, true);
+    assert!(!ix_0);
+    assert!(ix_7);
 }
 
 #[test]
@@ -488,4 +491,654 @@ fn bitflags32_with_set_bit_range() {
     ];
 
     assert_eq!(actual, expected);
-}
\ No newline at end of file
+}
+
+#[test]
+fn bitflags32_field() {
+    let mut flags = BitFlags32(0b1000_0001);
+
+    flags.set_field(1..=3, 0b101);
+    assert_eq!(flags, BitFlags32(0b1000_1011));
+    assert_eq!(flags.get_field(1..=3), 0b101);
+    assert_eq!(flags.get_field(0..=0), 1);
+    assert_eq!(flags.get_field(7..=7), 1);
+
+    flags.set_field(1..=3, 0);
+    assert_eq!(flags, BitFlags32(0b1000_0001));
+
+    let mut flags = BitFlags32::new();
+    flags.set_field(0..=31, u32::MAX);
+    assert!(flags.is_full());
+    assert_eq!(flags.get_field(0..=31), u32::MAX);
+}
+
+#[test]
+#[should_panic]
+fn bitflags32_field_overflow() {
+    let mut flags = BitFlags32::new();
+
+    flags.set_field(1..=3, 0b1000);
+}
+
+#[test]
+fn bitflags32_named_field() {
+    const FLAG: BitField32 = BitField32::new(0, 0);
+    const LEVEL: BitField32 = BitField32::new(4, 6);
+
+    let mut flags = BitFlags32::new();
+    FLAG.set(&mut flags, 1);
+    LEVEL.set(&mut flags, 5);
+
+    assert_eq!(flags, BitFlags32(0b0101_0001));
+    assert_eq!(FLAG.get(flags), 1);
+    assert_eq!(LEVEL.get(flags), 5);
+    assert_eq!(LEVEL.width(), 3);
+    assert_eq!(LEVEL.max_value(), 7);
+    assert_eq!(LEVEL.mask(), BitFlags32(0b0111_0000));
+}
+
+#[test]
+fn bitflags32_range() {
+    assert_eq!(BitFlags32::from_range(1..4), BitFlags32(0b0000_1110));
+    assert_eq!(BitFlags32::from_range(1..=4), BitFlags32(0b0001_1110));
+    assert_eq!(BitFlags32::from_range(3..3), BitFlags32::empty());
+    assert_eq!(BitFlags32::from_range(..), BitFlags32::full());
+    assert_eq!(BitFlags32::from_range(..=31), BitFlags32::full());
+    assert_eq!(BitFlags32::from_range(31..), BitFlags32::from_index(31));
+
+    let mut flags = BitFlags32(0b1000_0001);
+    flags.insert_range(1..3);
+    assert_eq!(flags, BitFlags32(0b1000_0111));
+    flags.remove_range(..2);
+    assert_eq!(flags, BitFlags32(0b1000_0100));
+    flags.toggle_range(2..=3);
+    assert_eq!(flags, BitFlags32(0b1000_1000));
+
+    assert!(flags.contains_range(3..4));
+    assert!(flags.contains_range(5..5));
+    assert!(!flags.contains_range(3..=4));
+    assert!(flags.intersects_range(3..=4));
+    assert!(!flags.intersects_range(4..7));
+    assert_eq!(flags.count_ones_in(..), 2);
+    assert_eq!(flags.count_ones_in(..7), 1);
+}
+
+#[test]
+fn bitflags32_try_range() {
+    let mut flags = BitFlags32(0b0001);
+    let (start, end) = (4, 2);
+
+    assert_eq!(BitFlags32::try_from_range(0..2), Ok(BitFlags32(0b0011)));
+    assert!(BitFlags32::try_from_range(0..=32).is_err());
+    assert!(flags.try_insert_range(1..=32).is_err());
+    assert!(flags.try_remove_range(32..64).is_err());
+    assert!(flags.try_toggle_range(start..end).is_err());
+    assert_eq!(flags, BitFlags32(0b0001));
+
+    assert_eq!(flags.try_insert_range(1..2), Ok(()));
+    assert_eq!(flags.try_contains_range(..2), Ok(true));
+    assert_eq!(flags.try_intersects_range(2..), Ok(false));
+    assert_eq!(flags.try_count_ones_in(1..), Ok(1));
+    assert!(flags.try_count_ones_in(..64).is_err());
+}
+
+#[test]
+#[should_panic]
+fn bitflags32_range_oob() {
+    let mut flags = BitFlags32::new();
+
+    flags.insert_range(0..34);
+}
+
+#[test]
+fn bitflags32_try_index() {
+    let mut flags = BitFlags32::new();
+    let oob = BitFlagsError::IndexOutOfRange { index: 32, bits: 32 };
+
+    assert_eq!(BitFlags32::try_from_index(31), Ok(BitFlags32::from_index(31)));
+    assert_eq!(BitFlags32::try_from_index(32), Err(oob.clone()));
+    assert_eq!(BitFlags32::try_from(32_usize), Err(oob.clone()));
+    assert_eq!(BitFlags32::try_from_slice(&[2, 4, 5]), Ok(BitFlags32(0b0011_0100)));
+    assert_eq!(BitFlags32::try_from_slice(&[2, 32, 5]), Err(oob.clone()));
+
+    assert_eq!(flags.try_insert_at_index(32), Err(oob.clone()));
+    assert_eq!(flags.try_set_at_index(32, true), Err(oob.clone()));
+    assert_eq!(flags.try_toggle_at_index(32), Err(oob.clone()));
+    assert_eq!(flags.try_remove_at_index(32), Err(oob.clone()));
+    assert_eq!(flags.try_bit_at_index(32), Err(oob));
+    assert!(flags.is_empty());
+
+    assert_eq!(flags.try_insert_at_index(0), Ok(()));
+    assert_eq!(flags.try_set_at_index(1, true), Ok(()));
+    assert_eq!(flags.try_toggle_at_index(2), Ok(()));
+    assert_eq!(flags.try_remove_at_index(0), Ok(()));
+    assert_eq!(flags.try_bit_at_index(1), Ok(true));
+    assert_eq!(flags, BitFlags32(0b0110));
+}
+
+#[test]
+fn bitflags32_try_bit_range() {
+    let mut flags = BitFlags32::new();
+    let invalid = BitFlagsError::InvalidRange { start: 3, end: 3, bits: 32 };
+    let (start, end) = (3, 2);
+
+    assert_eq!(BitFlags32::try_with_set_bit_range(1, 3), Ok(BitFlags32(0b1110)));
+    assert_eq!(BitFlags32::try_with_set_bit_range(3, 2), Err(invalid.clone()));
+    assert!(BitFlags32::try_with_set_bit_range(0, 32).is_err());
+    assert_eq!(flags.try_set_bit_range(3, 2), Err(invalid.clone()));
+    assert_eq!(flags.try_set_bit_range(0, 1), Ok(()));
+    assert_eq!(flags, BitFlags32(0b0011));
+
+    assert_eq!(flags.try_get_field(0..=1), Ok(0b11));
+    assert_eq!(flags.try_get_field(start..=end), Err(invalid.clone()));
+    assert_eq!(
+        flags.try_set_field(4..=5, 0b100),
+        Err(BitFlagsError::FieldOverflow { value: 0b100, max: 0b11 })
+    );
+    assert_eq!(flags.try_set_field(start..=end, 0), Err(invalid.clone()));
+    assert_eq!(flags, BitFlags32(0b0011));
+    assert_eq!(flags.try_set_field(4..=5, 0b10), Ok(()));
+    assert_eq!(flags, BitFlags32(0b10_0011));
+
+    assert_eq!(BitField32::try_new(3, 2), Err(invalid));
+    let field = BitField32::try_new(0, 1).unwrap();
+    assert!(field.try_set(&mut flags, 4).is_err());
+    assert_eq!(field.try_set(&mut flags, 2), Ok(()));
+    assert_eq!(flags, BitFlags32(0b10_0010));
+}
+
+#[test]
+fn bitflags32_from_str() {
+    assert_eq!("6".parse(), Ok(BitFlags32(6)));
+    assert_eq!("0x1f".parse(), Ok(BitFlags32(0x1f)));
+    assert_eq!("0b0110".parse(), Ok(BitFlags32(0b0110)));
+    assert_eq!(" BitFlags32(42) ".parse(), Ok(BitFlags32(42)));
+    assert_eq!(BitFlags32(42).to_string().parse(), Ok(BitFlags32(42)));
+
+    assert!(matches!("".parse::<BitFlags32>(), Err(BitFlagsError::Parse(_))));
+    assert!(matches!("0x".parse::<BitFlags32>(), Err(BitFlagsError::Parse(_))));
+    assert!(matches!("BitFlags32(x)".parse::<BitFlags32>(), Err(BitFlagsError::Parse(_))));
+}
+
+#[test]
+fn bitflags32_index_newtype() {
+    const LAST: Index32 = Index32::new(31);
+
+    assert_eq!(LAST, Index32::MAX);
+    assert_eq!(Index32::try_new(3).map(Index32::get), Ok(3));
+    assert_eq!(
+        Index32::try_new(32),
+        Err(BitFlagsError::IndexOutOfRange { index: 32, bits: 32 })
+    );
+    assert!(Index32::try_from(32_usize).is_err());
+    assert_eq!(usize::from(LAST), 31);
+    assert_eq!(BitFlags32::from(LAST), BitFlags32::from_index(31));
+
+    let all: Vec<usize> = Index32::all().map(usize::from).collect();
+    assert_eq!(all, (0..32).collect::<Vec<_>>());
+    assert_eq!(Index32::all().len(), 32);
+    assert_eq!(Index32::all().next_back(), Some(LAST));
+
+    let mut flags = BitFlags32::new();
+    for index in Index32::all() {
+        flags.insert_at(index);
+    }
+    assert!(flags.is_full());
+
+    flags.remove_at(Index32::MIN);
+    flags.toggle_at(Index32::new(1));
+    flags.set_at(LAST, false);
+    assert!(!flags.get_at(Index32::new(0)));
+    assert!(!flags.get_at(Index32::new(1)));
+    assert!(flags.get_at(Index32::new(2)));
+    assert_eq!(flags, BitFlags32::from_range(2..31));
+}
+
+#[test]
+#[should_panic]
+fn bitflags32_index_newtype_oob() {
+    Index32::new(32);
+}
+
+#[test]
+fn bitflags32_set_semantics() {
+    let mut flags = BitFlags32::new();
+
+    assert!(flags.add(3));
+    assert!(!flags.add(3));
+    assert!(flags.add(31));
+    assert_eq!(flags.len(), 2);
+    assert!(flags[3]);
+    assert!(!flags[2]);
+
+    assert!(flags.take(3));
+    assert!(!flags.take(3));
+    assert!(!flags.replace(1, true));
+    assert!(flags.replace(1, false));
+    assert!(!flags.replace(1, false));
+    assert_eq!(flags.try_add(1), Ok(true));
+    assert_eq!(flags.try_take(1), Ok(true));
+    assert_eq!(flags.try_replace(1, true), Ok(false));
+    assert_eq!(flags.try_replace(1, false), Ok(true));
+    assert_eq!(flags.try_add(32), Err(BitFlagsError::IndexOutOfRange { index: 32, bits: 32 }));
+    assert_eq!(flags.try_take(32), Err(BitFlagsError::IndexOutOfRange { index: 32, bits: 32 }));
+    assert!(flags.try_replace(32, true).is_err());
+    assert_eq!(flags, BitFlags32::from_index(31));
+
+    flags.clear();
+    assert!(flags.is_empty());
+    assert_eq!(flags.len(), 0);
+}
+
+#[test]
+fn bitflags32_retain_drain() {
+    let mut flags = BitFlags32(0b1111_0110);
+
+    flags.retain(|i| i % 2 == 0);
+    assert_eq!(flags, BitFlags32(0b0101_0100));
+
+    let drained: Vec<usize> = flags.drain().collect();
+    assert_eq!(drained, vec![2, 4, 6]);
+    assert!(flags.is_empty());
+}
+
+#[test]
+fn bitflags32_bit_mut() {
+    let mut flags = BitFlags32(0b0001);
+
+    let mut bit = flags.bit_mut(2);
+    assert!(!bit.get());
+    bit.set(true);
+    assert!(bit.get());
+    flags.bit_mut(0).toggle();
+    flags.bit_mut(31).set(true);
+
+    assert_eq!(flags, BitFlags32(0b0100) | BitFlags32::from_index(31));
+    assert!(flags.try_bit_mut(31).is_ok());
+    assert!(matches!(
+        flags.try_bit_mut(32),
+        Err(BitFlagsError::IndexOutOfRange { index: 32, bits: 32 })
+    ));
+}
+
+#[test]
+#[should_panic]
+fn bitflags32_index_op_oob() {
+    let flags = BitFlags32::full();
+
+    let _ = flags[32];
+}
+
+#[test]
+fn bitflags32_non_empty() {
+    assert_eq!(
+        core::mem::size_of::<Option<NonEmptyBitFlags32>>(),
+        core::mem::size_of::<BitFlags32>()
+    );
+
+    assert_eq!(NonEmptyBitFlags32::new(BitFlags32::empty()), None);
+    assert_eq!(
+        NonEmptyBitFlags32::try_from(BitFlags32::empty()),
+        Err(BitFlagsError::Empty)
+    );
+    assert!(NonEmptyBitFlags32::try_from_index(32).is_err());
+
+    let f1 = NonEmptyBitFlags32::new(BitFlags32(0b0011)).unwrap();
+    let f2 = NonEmptyBitFlags32::from_index(2);
+    let f3 = NonEmptyBitFlags32::try_from(BitFlags32(0b0110)).unwrap();
+
+    assert_eq!((f1 | f2).get(), BitFlags32(0b0111));
+    assert_eq!(f1.union(f2).to_u32(), 0b0111);
+    assert_eq!(f1.intersection(f2), None);
+    assert_eq!(f1.intersection(f3).map(BitFlags32::from), Some(BitFlags32(0b0010)));
+    assert_eq!(f2.difference(f3), None);
+    assert_eq!(f3.difference(f2).map(BitFlags32::from), Some(BitFlags32(0b0010)));
+    assert!(f3.contains(f2.get()));
+    assert!(!f1.intersects(f2.get()));
+    assert_eq!(f3.count_ones(), 2);
+    assert_eq!(f3.iter().collect::<Vec<_>>(), vec![1, 2]);
+    assert!(NonEmptyBitFlags32::full().get().is_full());
+
+    let mut f4 = f1;
+    f4 |= f2;
+    assert_eq!(f4.to_string(), "NonEmptyBitFlags32(7)");
+}
+
+#[test]
+fn bitflags32_msb0_index() {
+    let mut msb = BitFlags32::new().msb0();
+
+    msb.insert_at_index(0);
+    msb.insert_at_index(31);
+    assert_eq!(msb.lsb0(), BitFlags32::from_slice(&[0, 31]));
+
+    msb.remove_at_index(31);
+    msb.toggle_at_index(1);
+    msb.set_at_index(2, true);
+    assert_eq!(msb, Msb0BitFlags32::from_slice(&[0, 1, 2]));
+    assert_eq!(msb.lsb0(), BitFlags32::from_range(29..));
+    assert_eq!(Msb0BitFlags32::from_index(0).lsb0(), BitFlags32::from_index(31));
+
+    assert!(msb.bit_at_index(1));
+    assert!(!msb.bit_at_index(31));
+    assert_eq!(msb.get_bit_at_index(32), None);
+    assert_eq!(msb.iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+    assert!(msb.try_insert_at_index(32).is_err());
+    assert!(Msb0BitFlags32::try_from_slice(&[0, 32]).is_err());
+}
+
+#[test]
+fn bitflags32_msb0_range() {
+    let mut msb = Msb0BitFlags32::from_range(0..4);
+    assert_eq!(msb.lsb0(), BitFlags32::from_range(28..));
+
+    msb.remove_range(..=1);
+    msb.toggle_range(3..5);
+    assert_eq!(msb, Msb0BitFlags32::from_slice(&[2, 4]));
+
+    msb.insert_range(31..);
+    assert!(msb.contains_range(2..3));
+    assert!(!msb.contains_range(2..5));
+    assert!(msb.intersects_range(1..=2));
+    assert!(!msb.intersects_range(5..31));
+    assert_eq!(msb.count_ones_in(..), 3);
+    assert_eq!(msb.count_ones_in(3..), 2);
+    assert!(msb.try_insert_range(0..=32).is_err());
+}
+
+#[test]
+fn bitflags32_msb0_fmt() {
+    let msb = Msb0BitFlags32::from_slice(&[0, 1, 31]);
+
+    assert_eq!(msb.to_string(), "Msb0BitFlags32{0, 1, 31}");
+    assert_eq!(Msb0BitFlags32::empty().to_string(), "Msb0BitFlags32{}");
+    assert_eq!(format!("{:b}", Msb0BitFlags32::from_index(0)), format!("0b1{}", "0".repeat(31)));
+}
+
+#[test]
+fn bitflags32_bit_twiddling() {
+    let f = BitFlags32(0b0110_1000);
+
+    assert!(f.parity());
+    assert!(!BitFlags32(0b0110).parity());
+    assert!(!BitFlags32::empty().parity());
+    assert_eq!(f.isolate_lowest(), BitFlags32(0b1000));
+    assert_eq!(f.clear_lowest(), BitFlags32(0b0110_0000));
+    assert_eq!(f.mask_up_to_lowest(), BitFlags32(0b1111));
+    assert_eq!(BitFlags32::empty().isolate_lowest(), BitFlags32::empty());
+    assert_eq!(BitFlags32::empty().clear_lowest(), BitFlags32::empty());
+    assert_eq!(BitFlags32::empty().mask_up_to_lowest(), BitFlags32::full());
+
+    assert!(!f.is_single_bit());
+    assert!(!BitFlags32::empty().is_single_bit());
+    assert_eq!(f.single_index(), None);
+    assert_eq!(BitFlags32::from_index(31).single_index(), Some(31));
+
+    assert_eq!(f.smear_right(), BitFlags32(0b0111_1111));
+    assert_eq!(f.smear_left(), !BitFlags32(0b0111));
+    assert_eq!(BitFlags32::empty().smear_left(), BitFlags32::empty());
+    assert_eq!(BitFlags32::empty().smear_right(), BitFlags32::empty());
+
+    assert_eq!(BitFlags32(1).reverse_bits(), BitFlags32::from_index(31));
+    assert_eq!(f.reverse_bits().reverse_bits(), f);
+}
+
+#[test]
+fn bitflags32_blend() {
+    let mask = BitFlags32(0b1100);
+    let a = BitFlags32(0b1010);
+    let b = BitFlags32(0b0101);
+
+    assert_eq!(BitFlags32::blend(mask, a, b), BitFlags32(0b1001));
+    assert_eq!(BitFlags32::blend(BitFlags32::full(), a, b), a);
+    assert_eq!(BitFlags32::blend(BitFlags32::empty(), a, b), b);
+}
+
+#[test]
+fn bitflags32_next_same_popcount() {
+    assert_eq!(BitFlags32::empty().next_same_popcount(), None);
+    assert_eq!(BitFlags32(0b0111).next_same_popcount(), Some(BitFlags32(0b1011)));
+    assert_eq!(BitFlags32(0b1011).next_same_popcount(), Some(BitFlags32(0b1101)));
+    assert_eq!(BitFlags32(0b1110).next_same_popcount(), Some(BitFlags32(0b1_0011)));
+    assert_eq!(BitFlags32::full().next_same_popcount(), None);
+    assert_eq!(BitFlags32::from_index(31).next_same_popcount(), None);
+    assert_eq!(
+        BitFlags32::from_index(30).next_same_popcount(),
+        Some(BitFlags32::from_index(31))
+    );
+
+    // All 3-bit subsets of the lowest 6 bits, in increasing order
+    let mut count = 1;
+    let mut f = BitFlags32(0b0111);
+    while let Some(next) = f.next_same_popcount().filter(|n| n.0 < 64) {
+        assert!(next.0 > f.0);
+        assert_eq!(next.count_ones(), 3);
+        f = next;
+        count += 1;
+    }
+    assert_eq!(count, 20);
+}
+
+fn naive_extract32(val: u32, mask: u32) -> u32 {
+    let mut result = 0;
+    let mut k = 0;
+    for i in 0..32 {
+        if (mask >> i) & 1 == 1 {
+            result |= ((val >> i) & 1) << k;
+            k += 1;
+        }
+    }
+    result
+}
+
+fn naive_deposit32(val: u32, mask: u32) -> u32 {
+    let mut result = 0;
+    let mut k = 0;
+    for i in 0..32 {
+        if (mask >> i) & 1 == 1 {
+            result |= ((val >> k) & 1) << i;
+            k += 1;
+        }
+    }
+    result
+}
+
+fn check_extract_deposit32(val: u32, mask: u32) {
+    let (f, m) = (BitFlags32(val), BitFlags32(mask));
+    let extracted = f.extract(m);
+    let deposited = f.deposit(m);
+
+    assert_eq!(extracted.0, naive_extract32(val, mask), "extract({:#x}, {:#x})", val, mask);
+    assert_eq!(deposited.0, naive_deposit32(val, mask), "deposit({:#x}, {:#x})", val, mask);
+    assert_eq!(extracted.deposit(m), f & m);
+}
+
+#[test]
+fn bitflags32_extract_deposit() {
+    let f = BitFlags32(0b1010_0110);
+
+    assert_eq!(f.extract(BitFlags32(0b1111_0000)), BitFlags32(0b1010));
+    assert_eq!(f.extract(BitFlags32(0b0101_0101)), BitFlags32(0b0010));
+    assert_eq!(f.extract(BitFlags32::full()), f);
+    assert_eq!(f.extract(BitFlags32::empty()), BitFlags32::empty());
+    assert_eq!(BitFlags32(0b1010).deposit(BitFlags32(0b1111_0000)), BitFlags32(0b1010_0000));
+    assert_eq!(BitFlags32(0b1010).deposit(BitFlags32(0b0101_0101)), BitFlags32(0b0100_0100));
+    assert_eq!(f.deposit(BitFlags32::full()), f);
+    assert_eq!(f.deposit(BitFlags32::empty()), BitFlags32::empty());
+}
+
+#[test]
+fn bitflags32_extract_deposit_randomized() {
+    // xorshift64*, seeded for reproducibility
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    };
+
+    for _ in 0..10_000 {
+        let val = next() as u32;
+        let mask = next() as u32;
+        check_extract_deposit32(val, mask);
+        check_extract_deposit32(val, mask & (mask >> 1));
+    }
+}
+
+#[test]
+fn bitflags32_rank_select() {
+    let values = [
+        BitFlags32::empty(),
+        BitFlags32::full(),
+        BitFlags32(0b1011_0100),
+        BitFlags32::from_slice(&[0, 3, 16, 31]),
+        BitFlags32(u32::MAX / 3),
+    ];
+
+    for f in values {
+        let set: Vec<usize> = f.iter().collect();
+        let unset: Vec<usize> = (!f).iter().collect();
+
+        for i in 0..=32 {
+            let expected = set.iter().filter(|&&s| s < i).count() as u32;
+            assert_eq!(f.rank(i), expected);
+            assert_eq!(f.try_rank(i), Ok(expected));
+        }
+        for i in 0..32 {
+            assert_eq!(f.rank_inclusive(i), f.rank(i + 1));
+        }
+        for k in 0..=32 {
+            assert_eq!(f.select(k), set.get(k).copied());
+            assert_eq!(f.select_unset(k), unset.get(k).copied());
+        }
+        assert_eq!(
+            f.try_rank(33),
+            Err(BitFlagsError::InvalidRange { start: 0, end: 33, bits: 32 })
+        );
+        assert!(f.try_rank_inclusive(32).is_err());
+    }
+}
+
+#[test]
+#[should_panic]
+fn bitflags32_rank_oob() {
+    BitFlags32::full().rank(33);
+}
+
+#[test]
+fn bitflags32_submasks() {
+    let flags = BitFlags32(0b0101);
+    let subs: Vec<BitFlags32> = flags.submasks().collect();
+    assert_eq!(
+        subs,
+        vec![
+            BitFlags32(0),
+            BitFlags32(0b0001),
+            BitFlags32(0b0100),
+            BitFlags32(0b0101)
+        ]
+    );
+    assert_eq!(flags.nonempty_submasks().count(), 3);
+    assert_eq!(BitFlags32(0).submasks().collect::<Vec<_>>(), vec![BitFlags32(0)]);
+    assert_eq!(BitFlags32(0).nonempty_submasks().count(), 0);
+
+    let high = BitFlags32::from_slice(&[0, 16, 31]);
+    assert_eq!(high.submasks().count(), 8);
+    assert!(high.submasks().all(|s| high.contains(s)));
+    assert_eq!(high.submasks().last(), Some(high));
+
+    let sups: Vec<BitFlags32> = BitFlags32(0b0001).supersets_within(BitFlags32(0b1011)).collect();
+    assert_eq!(
+        sups,
+        vec![
+            BitFlags32(0b0001),
+            BitFlags32(0b0011),
+            BitFlags32(0b1001),
+            BitFlags32(0b1011)
+        ]
+    );
+    assert_eq!(BitFlags32(0b0100).supersets_within(BitFlags32(0b1011)).count(), 0);
+    assert_eq!(
+        BitFlags32::full()
+            .supersets_within(BitFlags32::full())
+            .collect::<Vec<_>>(),
+        vec![BitFlags32::full()]
+    );
+}
+
+#[test]
+fn bitflags32_combinations() {
+    let flags = BitFlags32(0b0111);
+    let combos: Vec<BitFlags32> = flags.combinations(2).collect();
+    assert_eq!(combos, vec![BitFlags32(0b0011), BitFlags32(0b0101), BitFlags32(0b0110)]);
+    assert_eq!(flags.combinations(0).collect::<Vec<_>>(), vec![BitFlags32(0)]);
+    assert_eq!(flags.combinations(3).collect::<Vec<_>>(), vec![flags]);
+    assert_eq!(flags.combinations(4).count(), 0);
+
+    let sparse = BitFlags32::from_slice(&[1, 3, 16, 31]);
+    let combos: Vec<BitFlags32> = sparse.combinations(2).collect();
+    assert_eq!(combos.len(), 6);
+    assert!(combos.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(combos.iter().all(|c| sparse.contains(*c) && c.0.count_ones() == 2));
+
+    let full = BitFlags32::full();
+    assert_eq!(full.combinations(1).count(), 32);
+    assert_eq!(full.combinations(31).last(), Some(BitFlags32(u32::MAX - 1)));
+    assert_eq!(full.combinations(32).collect::<Vec<_>>(), vec![full]);
+}
+
+#[test]
+fn bitflags32_gray_code() {
+    let flags = BitFlags32(0b0101);
+    let gray: Vec<BitFlags32> = flags.gray_code().collect();
+    assert_eq!(
+        gray,
+        vec![
+            BitFlags32(0),
+            BitFlags32(0b0001),
+            BitFlags32(0b0101),
+            BitFlags32(0b0100)
+        ]
+    );
+
+    let sparse = BitFlags32::from_slice(&[0, 2, 5, 16, 31]);
+    let gray: Vec<BitFlags32> = sparse.gray_code().collect();
+    assert_eq!(gray.len(), 32);
+    assert!(gray.windows(2).all(|w| (w[0] ^ w[1]).0.count_ones() == 1));
+    let mut sorted = gray.clone();
+    sorted.sort_by_key(|f| f.0);
+    assert_eq!(sorted, sparse.submasks().collect::<Vec<_>>());
+    assert_eq!(BitFlags32(0).gray_code().collect::<Vec<_>>(), vec![BitFlags32(0)]);
+}
+
+#[test]
+fn bitflags32_combinatorial_rank() {
+    let ranks: Vec<u32> = BitFlags32(0b0111)
+        .combinations(2)
+        .map(|f| f.combinatorial_rank())
+        .collect();
+    assert_eq!(ranks, vec![0, 1, 2]);
+    assert_eq!(BitFlags32(0).combinatorial_rank(), 0);
+    assert_eq!(BitFlags32::full().combinatorial_rank(), 0);
+    assert_eq!(BitFlags32::from_index(31).combinatorial_rank(), 31);
+
+    let top_half = BitFlags32(u32::MAX << 16);
+    let last = top_half.combinatorial_rank();
+    assert_eq!(BitFlags32::from_combinatorial_rank(16, last), Some(top_half));
+    assert_eq!(BitFlags32::from_combinatorial_rank(16, last + 1), None);
+    assert_eq!(BitFlags32::from_combinatorial_rank(32, 0), Some(BitFlags32::full()));
+    assert_eq!(BitFlags32::from_combinatorial_rank(32, 1), None);
+    assert_eq!(BitFlags32::from_combinatorial_rank(33, 0), None);
+    assert_eq!(BitFlags32::from_combinatorial_rank(0, 0), Some(BitFlags32(0)));
+
+    for flags in [
+        BitFlags32::from_slice(&[1, 3, 16, 31]),
+        BitFlags32(u32::MAX / 3),
+        BitFlags32(!0b1001),
+    ] {
+        let k = flags.0.count_ones();
+        assert_eq!(BitFlags32::from_combinatorial_rank(k, flags.combinatorial_rank()), Some(flags));
+    }
+}
@@ -1,6 +1,9 @@
 //! Tests for the `BitFlags64` struct.
 
-use arctan_bitflags::BitFlags64;
+use arctan_bitflags::{
+    BitField64, BitFlags64, BitFlagsError, Index64, Msb0BitFlags64, NonEmptyBitFlags64,
+};
+use core::convert::TryFrom;
 
 #[test]
 fn bitflags64_contains() {
@@ -77,28 +80,28 @@ fn bitflags64_ops() {
 
     assert_eq!(
         !f1,
-        BitFlags64(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_1111_1110)
+        BitFlags64(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111110)
     );
     assert_eq!(
         !f2,
-        BitFlags64(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_1111_1101)
+        BitFlags64(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111101)
     );
     assert_eq!(
         !f3,
-        BitFlags64(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_1111_0110)
+        BitFlags64(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11110110)
     );
 
     assert_eq!(
         f1.complement(),
-        BitFlags64(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_1111_1110)
+        BitFlags64(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111110)
     );
     assert_eq!(
         f2.complement(),
-        BitFlags64(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_1111_1101)
+        BitFlags64(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11111101)
     );
     assert_eq!(
         f3.complement(),
-        BitFlags64(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_1111_0110)
+        BitFlags64(0b11111111_11111111_11111111_11111111_11111111_11111111_11111111_11110110)
     );
 }
 
@@ -254,8 +257,8 @@ fn bitflags64_index() {
     let ix_0 = f1.bit_at_index(0);
     let ix_7 = f1.bit_at_index(7);
 
-    assert_eq!(ix_0, false);
-    assert_eq!(ix_7, true);
+    assert!(!ix_0);
+    assert!(ix_7);
 }
 
 #[test]
@@ -507,3 +510,659 @@ fn bitflags64_with_set_bit_range() {
 
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn bitflags64_field() {
+    let mut flags = BitFlags64(0b1000_0001);
+
+    flags.set_field(1..=3, 0b101);
+    assert_eq!(flags, BitFlags64(0b1000_1011));
+    assert_eq!(flags.get_field(1..=3), 0b101);
+    assert_eq!(flags.get_field(0..=0), 1);
+    assert_eq!(flags.get_field(7..=7), 1);
+
+    flags.set_field(1..=3, 0);
+    assert_eq!(flags, BitFlags64(0b1000_0001));
+
+    let mut flags = BitFlags64::new();
+    flags.set_field(0..=63, u64::MAX);
+    assert!(flags.is_full());
+    assert_eq!(flags.get_field(0..=63), u64::MAX);
+}
+
+#[test]
+#[should_panic]
+fn bitflags64_field_overflow() {
+    let mut flags = BitFlags64::new();
+
+    flags.set_field(1..=3, 0b1000);
+}
+
+#[test]
+fn bitflags64_named_field() {
+    const FLAG: BitField64 = BitField64::new(0, 0);
+    const LEVEL: BitField64 = BitField64::new(4, 6);
+
+    let mut flags = BitFlags64::new();
+    FLAG.set(&mut flags, 1);
+    LEVEL.set(&mut flags, 5);
+
+    assert_eq!(flags, BitFlags64(0b0101_0001));
+    assert_eq!(FLAG.get(flags), 1);
+    assert_eq!(LEVEL.get(flags), 5);
+    assert_eq!(LEVEL.width(), 3);
+    assert_eq!(LEVEL.max_value(), 7);
+    assert_eq!(LEVEL.mask(), BitFlags64(0b0111_0000));
+}
+
+#[test]
+fn bitflags64_range() {
+    assert_eq!(BitFlags64::from_range(1..4), BitFlags64(0b0000_1110));
+    assert_eq!(BitFlags64::from_range(1..=4), BitFlags64(0b0001_1110));
+    assert_eq!(BitFlags64::from_range(3..3), BitFlags64::empty());
+    assert_eq!(BitFlags64::from_range(..), BitFlags64::full());
+    assert_eq!(BitFlags64::from_range(..=63), BitFlags64::full());
+    assert_eq!(BitFlags64::from_range(63..), BitFlags64::from_index(63));
+
+    let mut flags = BitFlags64(0b1000_0001);
+    flags.insert_range(1..3);
+    assert_eq!(flags, BitFlags64(0b1000_0111));
+    flags.remove_range(..2);
+    assert_eq!(flags, BitFlags64(0b1000_0100));
+    flags.toggle_range(2..=3);
+    assert_eq!(flags, BitFlags64(0b1000_1000));
+
+    assert!(flags.contains_range(3..4));
+    assert!(flags.contains_range(5..5));
+    assert!(!flags.contains_range(3..=4));
+    assert!(flags.intersects_range(3..=4));
+    assert!(!flags.intersects_range(4..7));
+    assert_eq!(flags.count_ones_in(..), 2);
+    assert_eq!(flags.count_ones_in(..7), 1);
+}
+
+#[test]
+fn bitflags64_try_range() {
+    let mut flags = BitFlags64(0b0001);
+    let (start, end) = (4, 2);
+
+    assert_eq!(BitFlags64::try_from_range(0..2), Ok(BitFlags64(0b0011)));
+    assert!(BitFlags64::try_from_range(0..=64).is_err());
+    assert!(flags.try_insert_range(1..=64).is_err());
+    assert!(flags.try_remove_range(64..128).is_err());
+    assert!(flags.try_toggle_range(start..end).is_err());
+    assert_eq!(flags, BitFlags64(0b0001));
+
+    assert_eq!(flags.try_insert_range(1..2), Ok(()));
+    assert_eq!(flags.try_contains_range(..2), Ok(true));
+    assert_eq!(flags.try_intersects_range(2..), Ok(false));
+    assert_eq!(flags.try_count_ones_in(1..), Ok(1));
+    assert!(flags.try_count_ones_in(..128).is_err());
+}
+
+#[test]
+#[should_panic]
+fn bitflags64_range_oob() {
+    let mut flags = BitFlags64::new();
+
+    flags.insert_range(0..66);
+}
+
+#[test]
+fn bitflags64_try_index() {
+    let mut flags = BitFlags64::new();
+    let oob = BitFlagsError::IndexOutOfRange { index: 64, bits: 64 };
+
+    assert_eq!(BitFlags64::try_from_index(63), Ok(BitFlags64::from_index(63)));
+    assert_eq!(BitFlags64::try_from_index(64), Err(oob.clone()));
+    assert_eq!(BitFlags64::try_from(64_usize), Err(oob.clone()));
+    assert_eq!(BitFlags64::try_from_slice(&[2, 4, 5]), Ok(BitFlags64(0b0011_0100)));
+    assert_eq!(BitFlags64::try_from_slice(&[2, 64, 5]), Err(oob.clone()));
+
+    assert_eq!(flags.try_insert_at_index(64), Err(oob.clone()));
+    assert_eq!(flags.try_set_at_index(64, true), Err(oob.clone()));
+    assert_eq!(flags.try_toggle_at_index(64), Err(oob.clone()));
+    assert_eq!(flags.try_remove_at_index(64), Err(oob.clone()));
+    assert_eq!(flags.try_bit_at_index(64), Err(oob));
+    assert!(flags.is_empty());
+
+    assert_eq!(flags.try_insert_at_index(0), Ok(()));
+    assert_eq!(flags.try_set_at_index(1, true), Ok(()));
+    assert_eq!(flags.try_toggle_at_index(2), Ok(()));
+    assert_eq!(flags.try_remove_at_index(0), Ok(()));
+    assert_eq!(flags.try_bit_at_index(1), Ok(true));
+    assert_eq!(flags, BitFlags64(0b0110));
+}
+
+#[test]
+fn bitflags64_try_bit_range() {
+    let mut flags = BitFlags64::new();
+    let invalid = BitFlagsError::InvalidRange { start: 3, end: 3, bits: 64 };
+    let (start, end) = (3, 2);
+
+    assert_eq!(BitFlags64::try_with_set_bit_range(1, 3), Ok(BitFlags64(0b1110)));
+    assert_eq!(BitFlags64::try_with_set_bit_range(3, 2), Err(invalid.clone()));
+    assert!(BitFlags64::try_with_set_bit_range(0, 64).is_err());
+    assert_eq!(flags.try_set_bit_range(3, 2), Err(invalid.clone()));
+    assert_eq!(flags.try_set_bit_range(0, 1), Ok(()));
+    assert_eq!(flags, BitFlags64(0b0011));
+
+    assert_eq!(flags.try_get_field(0..=1), Ok(0b11));
+    assert_eq!(flags.try_get_field(start..=end), Err(invalid.clone()));
+    assert_eq!(
+        flags.try_set_field(4..=5, 0b100),
+        Err(BitFlagsError::FieldOverflow { value: 0b100, max: 0b11 })
+    );
+    assert_eq!(flags.try_set_field(start..=end, 0), Err(invalid.clone()));
+    assert_eq!(flags, BitFlags64(0b0011));
+    assert_eq!(flags.try_set_field(4..=5, 0b10), Ok(()));
+    assert_eq!(flags, BitFlags64(0b10_0011));
+
+    assert_eq!(BitField64::try_new(3, 2), Err(invalid));
+    let field = BitField64::try_new(0, 1).unwrap();
+    assert!(field.try_set(&mut flags, 4).is_err());
+    assert_eq!(field.try_set(&mut flags, 2), Ok(()));
+    assert_eq!(flags, BitFlags64(0b10_0010));
+}
+
+#[test]
+fn bitflags64_from_str() {
+    assert_eq!("6".parse(), Ok(BitFlags64(6)));
+    assert_eq!("0x1f".parse(), Ok(BitFlags64(0x1f)));
+    assert_eq!("0b0110".parse(), Ok(BitFlags64(0b0110)));
+    assert_eq!(" BitFlags64(42) ".parse(), Ok(BitFlags64(42)));
+    assert_eq!(BitFlags64(42).to_string().parse(), Ok(BitFlags64(42)));
+
+    assert!(matches!("".parse::<BitFlags64>(), Err(BitFlagsError::Parse(_))));
+    assert!(matches!("0x".parse::<BitFlags64>(), Err(BitFlagsError::Parse(_))));
+    assert!(matches!("BitFlags64(x)".parse::<BitFlags64>(), Err(BitFlagsError::Parse(_))));
+}
+
+#[test]
+fn bitflags64_index_newtype() {
+    const LAST: Index64 = Index64::new(63);
+
+    assert_eq!(LAST, Index64::MAX);
+    assert_eq!(Index64::try_new(3).map(Index64::get), Ok(3));
+    assert_eq!(
+        Index64::try_new(64),
+        Err(BitFlagsError::IndexOutOfRange { index: 64, bits: 64 })
+    );
+    assert!(Index64::try_from(64_usize).is_err());
+    assert_eq!(usize::from(LAST), 63);
+    assert_eq!(BitFlags64::from(LAST), BitFlags64::from_index(63));
+
+    let all: Vec<usize> = Index64::all().map(usize::from).collect();
+    assert_eq!(all, (0..64).collect::<Vec<_>>());
+    assert_eq!(Index64::all().len(), 64);
+    assert_eq!(Index64::all().next_back(), Some(LAST));
+
+    let mut flags = BitFlags64::new();
+    for index in Index64::all() {
+        flags.insert_at(index);
+    }
+    assert!(flags.is_full());
+
+    flags.remove_at(Index64::MIN);
+    flags.toggle_at(Index64::new(1));
+    flags.set_at(LAST, false);
+    assert!(!flags.get_at(Index64::new(0)));
+    assert!(!flags.get_at(Index64::new(1)));
+    assert!(flags.get_at(Index64::new(2)));
+    assert_eq!(flags, BitFlags64::from_range(2..63));
+}
+
+#[test]
+#[should_panic]
+fn bitflags64_index_newtype_oob() {
+    Index64::new(64);
+}
+
+#[test]
+fn bitflags64_set_semantics() {
+    let mut flags = BitFlags64::new();
+
+    assert!(flags.add(3));
+    assert!(!flags.add(3));
+    assert!(flags.add(63));
+    assert_eq!(flags.len(), 2);
+    assert!(flags[3]);
+    assert!(!flags[2]);
+
+    assert!(flags.take(3));
+    assert!(!flags.take(3));
+    assert!(!flags.replace(1, true));
+    assert!(flags.replace(1, false));
+    assert!(!flags.replace(1, false));
+    assert_eq!(flags.try_add(1), Ok(true));
+    assert_eq!(flags.try_take(1), Ok(true));
+    assert_eq!(flags.try_replace(1, true), Ok(false));
+    assert_eq!(flags.try_replace(1, false), Ok(true));
+    assert_eq!(flags.try_add(64), Err(BitFlagsError::IndexOutOfRange { index: 64, bits: 64 }));
+    assert_eq!(flags.try_take(64), Err(BitFlagsError::IndexOutOfRange { index: 64, bits: 64 }));
+    assert!(flags.try_replace(64, true).is_err());
+    assert_eq!(flags, BitFlags64::from_index(63));
+
+    flags.clear();
+    assert!(flags.is_empty());
+    assert_eq!(flags.len(), 0);
+}
+
+#[test]
+fn bitflags64_retain_drain() {
+    let mut flags = BitFlags64(0b1111_0110);
+
+    flags.retain(|i| i % 2 == 0);
+    assert_eq!(flags, BitFlags64(0b0101_0100));
+
+    let drained: Vec<usize> = flags.drain().collect();
+    assert_eq!(drained, vec![2, 4, 6]);
+    assert!(flags.is_empty());
+}
+
+#[test]
+fn bitflags64_bit_mut() {
+    let mut flags = BitFlags64(0b0001);
+
+    let mut bit = flags.bit_mut(2);
+    assert!(!bit.get());
+    bit.set(true);
+    assert!(bit.get());
+    flags.bit_mut(0).toggle();
+    flags.bit_mut(63).set(true);
+
+    assert_eq!(flags, BitFlags64(0b0100) | BitFlags64::from_index(63));
+    assert!(flags.try_bit_mut(63).is_ok());
+    assert!(matches!(
+        flags.try_bit_mut(64),
+        Err(BitFlagsError::IndexOutOfRange { index: 64, bits: 64 })
+    ));
+}
+
+#[test]
+#[should_panic]
+fn bitflags64_index_op_oob() {
+    let flags = BitFlags64::full();
+
+    let _ = flags[64];
+}
+
+#[test]
+fn bitflags64_non_empty() {
+    assert_eq!(
+        core::mem::size_of::<Option<NonEmptyBitFlags64>>(),
+        core::mem::size_of::<BitFlags64>()
+    );
+
+    assert_eq!(NonEmptyBitFlags64::new(BitFlags64::empty()), None);
+    assert_eq!(
+        NonEmptyBitFlags64::try_from(BitFlags64::empty()),
+        Err(BitFlagsError::Empty)
+    );
+    assert!(NonEmptyBitFlags64::try_from_index(64).is_err());
+
+    let f1 = NonEmptyBitFlags64::new(BitFlags64(0b0011)).unwrap();
+    let f2 = NonEmptyBitFlags64::from_index(2);
+    let f3 = NonEmptyBitFlags64::try_from(BitFlags64(0b0110)).unwrap();
+
+    assert_eq!((f1 | f2).get(), BitFlags64(0b0111));
+    assert_eq!(f1.union(f2).to_u64(), 0b0111);
+    assert_eq!(f1.intersection(f2), None);
+    assert_eq!(f1.intersection(f3).map(BitFlags64::from), Some(BitFlags64(0b0010)));
+    assert_eq!(f2.difference(f3), None);
+    assert_eq!(f3.difference(f2).map(BitFlags64::from), Some(BitFlags64(0b0010)));
+    assert!(f3.contains(f2.get()));
+    assert!(!f1.intersects(f2.get()));
+    assert_eq!(f3.count_ones(), 2);
+    assert_eq!(f3.iter().collect::<Vec<_>>(), vec![1, 2]);
+    assert!(NonEmptyBitFlags64::full().get().is_full());
+
+    let mut f4 = f1;
+    f4 |= f2;
+    assert_eq!(f4.to_string(), "NonEmptyBitFlags64(7)");
+}
+
+#[test]
+fn bitflags64_msb0_index() {
+    let mut msb = BitFlags64::new().msb0();
+
+    msb.insert_at_index(0);
+    msb.insert_at_index(63);
+    assert_eq!(msb.lsb0(), BitFlags64::from_slice(&[0, 63]));
+
+    msb.remove_at_index(63);
+    msb.toggle_at_index(1);
+    msb.set_at_index(2, true);
+    assert_eq!(msb, Msb0BitFlags64::from_slice(&[0, 1, 2]));
+    assert_eq!(msb.lsb0(), BitFlags64::from_range(61..));
+    assert_eq!(Msb0BitFlags64::from_index(0).lsb0(), BitFlags64::from_index(63));
+
+    assert!(msb.bit_at_index(1));
+    assert!(!msb.bit_at_index(63));
+    assert_eq!(msb.get_bit_at_index(64), None);
+    assert_eq!(msb.iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+    assert!(msb.try_insert_at_index(64).is_err());
+    assert!(Msb0BitFlags64::try_from_slice(&[0, 64]).is_err());
+}
+
+#[test]
+fn bitflags64_msb0_range() {
+    let mut msb = Msb0BitFlags64::from_range(0..4);
+    assert_eq!(msb.lsb0(), BitFlags64::from_range(60..));
+
+    msb.remove_range(..=1);
+    msb.toggle_range(3..5);
+    assert_eq!(msb, Msb0BitFlags64::from_slice(&[2, 4]));
+
+    msb.insert_range(63..);
+    assert!(msb.contains_range(2..3));
+    assert!(!msb.contains_range(2..5));
+    assert!(msb.intersects_range(1..=2));
+    assert!(!msb.intersects_range(5..63));
+    assert_eq!(msb.count_ones_in(..), 3);
+    assert_eq!(msb.count_ones_in(3..), 2);
+    assert!(msb.try_insert_range(0..=64).is_err());
+}
+
+#[test]
+fn bitflags64_msb0_fmt() {
+    let msb = Msb0BitFlags64::from_slice(&[0, 1, 63]);
+
+    assert_eq!(msb.to_string(), "Msb0BitFlags64{0, 1, 63}");
+    assert_eq!(Msb0BitFlags64::empty().to_string(), "Msb0BitFlags64{}");
+    assert_eq!(format!("{:b}", Msb0BitFlags64::from_index(0)), format!("0b1{}", "0".repeat(63)));
+}
+
+#[test]
+fn bitflags64_bit_twiddling() {
+    let f = BitFlags64(0b0110_1000);
+
+    assert!(f.parity());
+    assert!(!BitFlags64(0b0110).parity());
+    assert!(!BitFlags64::empty().parity());
+    assert_eq!(f.isolate_lowest(), BitFlags64(0b1000));
+    assert_eq!(f.clear_lowest(), BitFlags64(0b0110_0000));
+    assert_eq!(f.mask_up_to_lowest(), BitFlags64(0b1111));
+    assert_eq!(BitFlags64::empty().isolate_lowest(), BitFlags64::empty());
+    assert_eq!(BitFlags64::empty().clear_lowest(), BitFlags64::empty());
+    assert_eq!(BitFlags64::empty().mask_up_to_lowest(), BitFlags64::full());
+
+    assert!(!f.is_single_bit());
+    assert!(!BitFlags64::empty().is_single_bit());
+    assert_eq!(f.single_index(), None);
+    assert_eq!(BitFlags64::from_index(63).single_index(), Some(63));
+
+    assert_eq!(f.smear_right(), BitFlags64(0b0111_1111));
+    assert_eq!(f.smear_left(), !BitFlags64(0b0111));
+    assert_eq!(BitFlags64::empty().smear_left(), BitFlags64::empty());
+    assert_eq!(BitFlags64::empty().smear_right(), BitFlags64::empty());
+
+    assert_eq!(BitFlags64(1).reverse_bits(), BitFlags64::from_index(63));
+    assert_eq!(f.reverse_bits().reverse_bits(), f);
+}
+
+#[test]
+fn bitflags64_blend() {
+    let mask = BitFlags64(0b1100);
+    let a = BitFlags64(0b1010);
+    let b = BitFlags64(0b0101);
+
+    assert_eq!(BitFlags64::blend(mask, a, b), BitFlags64(0b1001));
+    assert_eq!(BitFlags64::blend(BitFlags64::full(), a, b), a);
+    assert_eq!(BitFlags64::blend(BitFlags64::empty(), a, b), b);
+}
+
+#[test]
+fn bitflags64_next_same_popcount() {
+    assert_eq!(BitFlags64::empty().next_same_popcount(), None);
+    assert_eq!(BitFlags64(0b0111).next_same_popcount(), Some(BitFlags64(0b1011)));
+    assert_eq!(BitFlags64(0b1011).next_same_popcount(), Some(BitFlags64(0b1101)));
+    assert_eq!(BitFlags64(0b1110).next_same_popcount(), Some(BitFlags64(0b1_0011)));
+    assert_eq!(BitFlags64::full().next_same_popcount(), None);
+    assert_eq!(BitFlags64::from_index(63).next_same_popcount(), None);
+    assert_eq!(
+        BitFlags64::from_index(62).next_same_popcount(),
+        Some(BitFlags64::from_index(63))
+    );
+
+    // All 3-bit subsets of the lowest 6 bits, in increasing order
+    let mut count = 1;
+    let mut f = BitFlags64(0b0111);
+    while let Some(next) = f.next_same_popcount().filter(|n| n.0 < 64) {
+        assert!(next.0 > f.0);
+        assert_eq!(next.count_ones(), 3);
+        f = next;
+        count += 1;
+    }
+    assert_eq!(count, 20);
+}
+
+fn naive_extract64(val: u64, mask: u64) -> u64 {
+    let mut result = 0;
+    let mut k = 0;
+    for i in 0..64 {
+        if (mask >> i) & 1 == 1 {
+            result |= ((val >> i) & 1) << k;
+            k += 1;
+        }
+    }
+    result
+}
+
+fn naive_deposit64(val: u64, mask: u64) -> u64 {
+    let mut result = 0;
+    let mut k = 0;
+    for i in 0..64 {
+        if (mask >> i) & 1 == 1 {
+            result |= ((val >> k) & 1) << i;
+            k += 1;
+        }
+    }
+    result
+}
+
+fn check_extract_deposit64(val: u64, mask: u64) {
+    let (f, m) = (BitFlags64(val), BitFlags64(mask));
+    let extracted = f.extract(m);
+    let deposited = f.deposit(m);
+
+    assert_eq!(extracted.0, naive_extract64(val, mask), "extract({:#x}, {:#x})", val, mask);
+    assert_eq!(deposited.0, naive_deposit64(val, mask), "deposit({:#x}, {:#x})", val, mask);
+    assert_eq!(extracted.deposit(m), f & m);
+}
+
+#[test]
+fn bitflags64_extract_deposit() {
+    let f = BitFlags64(0b1010_0110);
+
+    assert_eq!(f.extract(BitFlags64(0b1111_0000)), BitFlags64(0b1010));
+    assert_eq!(f.extract(BitFlags64(0b0101_0101)), BitFlags64(0b0010));
+    assert_eq!(f.extract(BitFlags64::full()), f);
+    assert_eq!(f.extract(BitFlags64::empty()), BitFlags64::empty());
+    assert_eq!(BitFlags64(0b1010).deposit(BitFlags64(0b1111_0000)), BitFlags64(0b1010_0000));
+    assert_eq!(BitFlags64(0b1010).deposit(BitFlags64(0b0101_0101)), BitFlags64(0b0100_0100));
+    assert_eq!(f.deposit(BitFlags64::full()), f);
+    assert_eq!(f.deposit(BitFlags64::empty()), BitFlags64::empty());
+}
+
+#[test]
+fn bitflags64_extract_deposit_randomized() {
+    // xorshift64*, seeded for reproducibility
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    };
+
+    for _ in 0..10_000 {
+        let val = next();
+        let mask = next();
+        check_extract_deposit64(val, mask);
+        check_extract_deposit64(val, mask & (mask >> 1));
+    }
+}
+
+#[test]
+fn bitflags64_rank_select() {
+    let values = [
+        BitFlags64::empty(),
+        BitFlags64::full(),
+        BitFlags64(0b1011_0100),
+        BitFlags64::from_slice(&[0, 3, 32, 63]),
+        BitFlags64(u64::MAX / 3),
+    ];
+
+    for f in values {
+        let set: Vec<usize> = f.iter().collect();
+        let unset: Vec<usize> = (!f).iter().collect();
+
+        for i in 0..=64 {
+            let expected = set.iter().filter(|&&s| s < i).count() as u32;
+            assert_eq!(f.rank(i), expected);
+            assert_eq!(f.try_rank(i), Ok(expected));
+        }
+        for i in 0..64 {
+            assert_eq!(f.rank_inclusive(i), f.rank(i + 1));
+        }
+        for k in 0..=64 {
+            assert_eq!(f.select(k), set.get(k).copied());
+            assert_eq!(f.select_unset(k), unset.get(k).copied());
+        }
+        assert_eq!(
+            f.try_rank(65),
+            Err(BitFlagsError::InvalidRange { start: 0, end: 65, bits: 64 })
+        );
+        assert!(f.try_rank_inclusive(64).is_err());
+    }
+}
+
+#[test]
+#[should_panic]
+fn bitflags64_rank_oob() {
+    BitFlags64::full().rank(65);
+}
+
+#[test]
+fn bitflags64_submasks() {
+    let flags = BitFlags64(0b0101);
+    let subs: Vec<BitFlags64> = flags.submasks().collect();
+    assert_eq!(
+        subs,
+        vec![
+            BitFlags64(0),
+            BitFlags64(0b0001),
+            BitFlags64(0b0100),
+            BitFlags64(0b0101)
+        ]
+    );
+    assert_eq!(flags.nonempty_submasks().count(), 3);
+    assert_eq!(BitFlags64(0).submasks().collect::<Vec<_>>(), vec![BitFlags64(0)]);
+    assert_eq!(BitFlags64(0).nonempty_submasks().count(), 0);
+
+    let high = BitFlags64::from_slice(&[0, 32, 63]);
+    assert_eq!(high.submasks().count(), 8);
+    assert!(high.submasks().all(|s| high.contains(s)));
+    assert_eq!(high.submasks().last(), Some(high));
+
+    let sups: Vec<BitFlags64> = BitFlags64(0b0001).supersets_within(BitFlags64(0b1011)).collect();
+    assert_eq!(
+        sups,
+        vec![
+            BitFlags64(0b0001),
+            BitFlags64(0b0011),
+            BitFlags64(0b1001),
+            BitFlags64(0b1011)
+        ]
+    );
+    assert_eq!(BitFlags64(0b0100).supersets_within(BitFlags64(0b1011)).count(), 0);
+    assert_eq!(
+        BitFlags64::full()
+            .supersets_within(BitFlags64::full())
+            .collect::<Vec<_>>(),
+        vec![BitFlags64::full()]
+    );
+}
+
+#[test]
+fn bitflags64_combinations() {
+    let flags = BitFlags64(0b0111);
+    let combos: Vec<BitFlags64> = flags.combinations(2).collect();
+    assert_eq!(combos, vec![BitFlags64(0b0011), BitFlags64(0b0101), BitFlags64(0b0110)]);
+    assert_eq!(flags.combinations(0).collect::<Vec<_>>(), vec![BitFlags64(0)]);
+    assert_eq!(flags.combinations(3).collect::<Vec<_>>(), vec![flags]);
+    assert_eq!(flags.combinations(4).count(), 0);
+
+    let sparse = BitFlags64::from_slice(&[1, 3, 32, 63]);
+    let combos: Vec<BitFlags64> = sparse.combinations(2).collect();
+    assert_eq!(combos.len(), 6);
+    assert!(combos.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(combos.iter().all(|c| sparse.contains(*c) && c.0.count_ones() == 2));
+
+    let full = BitFlags64::full();
+    assert_eq!(full.combinations(1).count(), 64);
+    assert_eq!(full.combinations(63).last(), Some(BitFlags64(u64::MAX - 1)));
+    assert_eq!(full.combinations(64).collect::<Vec<_>>(), vec![full]);
+}
+
+#[test]
+fn bitflags64_gray_code() {
+    let flags = BitFlags64(0b0101);
+    let gray: Vec<BitFlags64> = flags.gray_code().collect();
+    assert_eq!(
+        gray,
+        vec![
+            BitFlags64(0),
+            BitFlags64(0b0001),
+            BitFlags64(0b0101),
+            BitFlags64(0b0100)
+        ]
+    );
+
+    let sparse = BitFlags64::from_slice(&[0, 2, 5, 32, 63]);
+    let gray: Vec<BitFlags64> = sparse.gray_code().collect();
+    assert_eq!(gray.len(), 32);
+    assert!(gray.windows(2).all(|w| (w[0] ^ w[1]).0.count_ones() == 1));
+    let mut sorted = gray.clone();
+    sorted.sort_by_key(|f| f.0);
+    assert_eq!(sorted, sparse.submasks().collect::<Vec<_>>());
+    assert_eq!(BitFlags64(0).gray_code().collect::<Vec<_>>(), vec![BitFlags64(0)]);
+}
+
+#[test]
+fn bitflags64_combinatorial_rank() {
+    let ranks: Vec<u64> = BitFlags64(0b0111)
+        .combinations(2)
+        .map(|f| f.combinatorial_rank())
+        .collect();
+    assert_eq!(ranks, vec![0, 1, 2]);
+    assert_eq!(BitFlags64(0).combinatorial_rank(), 0);
+    assert_eq!(BitFlags64::full().combinatorial_rank(), 0);
+    assert_eq!(BitFlags64::from_index(63).combinatorial_rank(), 63);
+
+    let top_half = BitFlags64(u64::MAX << 32);
+    let last = top_half.combinatorial_rank();
+    assert_eq!(last, 1832624140942590533); // C(64, 32) - 1
+    assert_eq!(BitFlags64::from_combinatorial_rank(32, last), Some(top_half));
+    assert_eq!(BitFlags64::from_combinatorial_rank(32, last + 1), None);
+    assert_eq!(BitFlags64::from_combinatorial_rank(64, 0), Some(BitFlags64::full()));
+    assert_eq!(BitFlags64::from_combinatorial_rank(64, 1), None);
+    assert_eq!(BitFlags64::from_combinatorial_rank(65, 0), None);
+    assert_eq!(BitFlags64::from_combinatorial_rank(0, 0), Some(BitFlags64(0)));
+
+    for flags in [
+        BitFlags64::from_slice(&[1, 3, 32, 63]),
+        BitFlags64(u64::MAX / 3),
+        BitFlags64(!0b1001),
+    ] {
+        let k = flags.0.count_ones();
+        assert_eq!(BitFlags64::from_combinatorial_rank(k, flags.combinatorial_rank()), Some(flags));
+    }
+
+    for (rank, flags) in BitFlags64::full().combinations(3).take(5000).enumerate() {
+        assert_eq!(flags.combinatorial_rank(), rank as u64);
+        assert_eq!(BitFlags64::from_combinatorial_rank(3, rank as u64), Some(flags));
+    }
+}
@@ -1,6 +1,9 @@
 //! Tests for the `BitFlags8` struct.
 
-use arctan_bitflags::BitFlags8;
+use arctan_bitflags::{
+    BitField8, BitFlags8, BitFlagsError, Index8, Msb0BitFlags8, NonEmptyBitFlags8,
+};
+use core::convert::TryFrom;
 
 #[test]
 fn bitflags8_contains() {
@@ -236,8 +239,8 @@ fn bitflags8_index() {
     let ix_0 = f1.bit_at_index(0);
     let ix_7 = f1.bit_at_index(7);
 
-    assert_eq!(ix_0, false);
-    assert_eq!(ix_7, true);
+    assert!(!ix_0);
+    assert!(ix_7);
 }
 
 #[test]
@@ -486,3 +489,663 @@ fn bitflags8_with_set_bit_range() {
 
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn bitflags8_field() {
+    let mut flags = BitFlags8(0b1000_0001);
+
+    flags.set_field(1..=3, 0b101);
+    assert_eq!(flags, BitFlags8(0b1000_1011));
+    assert_eq!(flags.get_field(1..=3), 0b101);
+    assert_eq!(flags.get_field(0..=0), 1);
+    assert_eq!(flags.get_field(7..=7), 1);
+
+    flags.set_field(1..=3, 0);
+    assert_eq!(flags, BitFlags8(0b1000_0001));
+
+    let mut flags = BitFlags8::new();
+    flags.set_field(0..=7, u8::MAX);
+    assert!(flags.is_full());
+    assert_eq!(flags.get_field(0..=7), u8::MAX);
+}
+
+#[test]
+#[should_panic]
+fn bitflags8_field_overflow() {
+    let mut flags = BitFlags8::new();
+
+    flags.set_field(1..=3, 0b1000);
+}
+
+#[test]
+fn bitflags8_named_field() {
+    const FLAG: BitField8 = BitField8::new(0, 0);
+    const LEVEL: BitField8 = BitField8::new(4, 6);
+
+    let mut flags = BitFlags8::new();
+    FLAG.set(&mut flags, 1);
+    LEVEL.set(&mut flags, 5);
+
+    assert_eq!(flags, BitFlags8(0b0101_0001));
+    assert_eq!(FLAG.get(flags), 1);
+    assert_eq!(LEVEL.get(flags), 5);
+    assert_eq!(LEVEL.width(), 3);
+    assert_eq!(LEVEL.max_value(), 7);
+    assert_eq!(LEVEL.mask(), BitFlags8(0b0111_0000));
+}
+
+#[test]
+fn bitflags8_range() {
+    assert_eq!(BitFlags8::from_range(1..4), BitFlags8(0b0000_1110));
+    assert_eq!(BitFlags8::from_range(1..=4), BitFlags8(0b0001_1110));
+    assert_eq!(BitFlags8::from_range(3..3), BitFlags8::empty());
+    assert_eq!(BitFlags8::from_range(..), BitFlags8::full());
+    assert_eq!(BitFlags8::from_range(..=7), BitFlags8::full());
+    assert_eq!(BitFlags8::from_range(7..), BitFlags8::from_index(7));
+
+    let mut flags = BitFlags8(0b1000_0001);
+    flags.insert_range(1..3);
+    assert_eq!(flags, BitFlags8(0b1000_0111));
+    flags.remove_range(..2);
+    assert_eq!(flags, BitFlags8(0b1000_0100));
+    flags.toggle_range(2..=3);
+    assert_eq!(flags, BitFlags8(0b1000_1000));
+
+    assert!(flags.contains_range(3..4));
+    assert!(flags.contains_range(5..5));
+    assert!(!flags.contains_range(3..=4));
+    assert!(flags.intersects_range(3..=4));
+    assert!(!flags.intersects_range(4..7));
+    assert_eq!(flags.count_ones_in(..), 2);
+    assert_eq!(flags.count_ones_in(..7), 1);
+}
+
+#[test]
+fn bitflags8_try_range() {
+    let mut flags = BitFlags8(0b0001);
+    let (start, end) = (4, 2);
+
+    assert_eq!(BitFlags8::try_from_range(0..2), Ok(BitFlags8(0b0011)));
+    assert!(BitFlags8::try_from_range(0..=8).is_err());
+    assert!(flags.try_insert_range(1..=8).is_err());
+    assert!(flags.try_remove_range(8..16).is_err());
+    assert!(flags.try_toggle_range(start..end).is_err());
+    assert_eq!(flags, BitFlags8(0b0001));
+
+    assert_eq!(flags.try_insert_range(1..2), Ok(()));
+    assert_eq!(flags.try_contains_range(..2), Ok(true));
+    assert_eq!(flags.try_intersects_range(2..), Ok(false));
+    assert_eq!(flags.try_count_ones_in(1..), Ok(1));
+    assert!(flags.try_count_ones_in(..16).is_err());
+}
+
+#[test]
+#[should_panic]
+fn bitflags8_range_oob() {
+    let mut flags = BitFlags8::new();
+
+    flags.insert_range(0..10);
+}
+
+#[test]
+fn bitflags8_try_index() {
+    let mut flags = BitFlags8::new();
+    let oob = BitFlagsError::IndexOutOfRange { index: 8, bits: 8 };
+
+    assert_eq!(BitFlags8::try_from_index(7), Ok(BitFlags8::from_index(7)));
+    assert_eq!(BitFlags8::try_from_index(8), Err(oob.clone()));
+    assert_eq!(BitFlags8::try_from(8_usize), Err(oob.clone()));
+    assert_eq!(BitFlags8::try_from_slice(&[2, 4, 5]), Ok(BitFlags8(0b0011_0100)));
+    assert_eq!(BitFlags8::try_from_slice(&[2, 8, 5]), Err(oob.clone()));
+
+    assert_eq!(flags.try_insert_at_index(8), Err(oob.clone()));
+    assert_eq!(flags.try_set_at_index(8, true), Err(oob.clone()));
+    assert_eq!(flags.try_toggle_at_index(8), Err(oob.clone()));
+    assert_eq!(flags.try_remove_at_index(8), Err(oob.clone()));
+    assert_eq!(flags.try_bit_at_index(8), Err(oob));
+    assert!(flags.is_empty());
+
+    assert_eq!(flags.try_insert_at_index(0), Ok(()));
+    assert_eq!(flags.try_set_at_index(1, true), Ok(()));
+    assert_eq!(flags.try_toggle_at_index(2), Ok(()));
+    assert_eq!(flags.try_remove_at_index(0), Ok(()));
+    assert_eq!(flags.try_bit_at_index(1), Ok(true));
+    assert_eq!(flags, BitFlags8(0b0110));
+}
+
+#[test]
+fn bitflags8_try_bit_range() {
+    let mut flags = BitFlags8::new();
+    let invalid = BitFlagsError::InvalidRange { start: 3, end: 3, bits: 8 };
+    let (start, end) = (3, 2);
+
+    assert_eq!(BitFlags8::try_with_set_bit_range(1, 3), Ok(BitFlags8(0b1110)));
+    assert_eq!(BitFlags8::try_with_set_bit_range(3, 2), Err(invalid.clone()));
+    assert!(BitFlags8::try_with_set_bit_range(0, 8).is_err());
+    assert_eq!(flags.try_set_bit_range(3, 2), Err(invalid.clone()));
+    assert_eq!(flags.try_set_bit_range(0, 1), Ok(()));
+    assert_eq!(flags, BitFlags8(0b0011));
+
+    assert_eq!(flags.try_get_field(0..=1), Ok(0b11));
+    assert_eq!(flags.try_get_field(start..=end), Err(invalid.clone()));
+    assert_eq!(
+        flags.try_set_field(4..=5, 0b100),
+        Err(BitFlagsError::FieldOverflow { value: 0b100, max: 0b11 })
+    );
+    assert_eq!(flags.try_set_field(start..=end, 0), Err(invalid.clone()));
+    assert_eq!(flags, BitFlags8(0b0011));
+    assert_eq!(flags.try_set_field(4..=5, 0b10), Ok(()));
+    assert_eq!(flags, BitFlags8(0b10_0011));
+
+    assert_eq!(BitField8::try_new(3, 2), Err(invalid));
+    let field = BitField8::try_new(0, 1).unwrap();
+    assert!(field.try_set(&mut flags, 4).is_err());
+    assert_eq!(field.try_set(&mut flags, 2), Ok(()));
+    assert_eq!(flags, BitFlags8(0b10_0010));
+}
+
+#[test]
+fn bitflags8_from_str() {
+    assert_eq!("6".parse(), Ok(BitFlags8(6)));
+    assert_eq!("0x1f".parse(), Ok(BitFlags8(0x1f)));
+    assert_eq!("0b0110".parse(), Ok(BitFlags8(0b0110)));
+    assert_eq!(" BitFlags8(42) ".parse(), Ok(BitFlags8(42)));
+    assert_eq!(BitFlags8(42).to_string().parse(), Ok(BitFlags8(42)));
+
+    assert!(matches!("".parse::<BitFlags8>(), Err(BitFlagsError::Parse(_))));
+    assert!(matches!("0x".parse::<BitFlags8>(), Err(BitFlagsError::Parse(_))));
+    assert!(matches!("BitFlags8(x)".parse::<BitFlags8>(), Err(BitFlagsError::Parse(_))));
+}
+
+#[test]
+fn bitflags8_index_newtype() {
+    const LAST: Index8 = Index8::new(7);
+
+    assert_eq!(LAST, Index8::MAX);
+    assert_eq!(Index8::try_new(3).map(Index8::get), Ok(3));
+    assert_eq!(
+        Index8::try_new(8),
+        Err(BitFlagsError::IndexOutOfRange { index: 8, bits: 8 })
+    );
+    assert!(Index8::try_from(8_usize).is_err());
+    assert_eq!(usize::from(LAST), 7);
+    assert_eq!(BitFlags8::from(LAST), BitFlags8::from_index(7));
+
+    let all: Vec<usize> = Index8::all().map(usize::from).collect();
+    assert_eq!(all, (0..8).collect::<Vec<_>>());
+    assert_eq!(Index8::all().len(), 8);
+    assert_eq!(Index8::all().next_back(), Some(LAST));
+
+    let mut flags = BitFlags8::new();
+    for index in Index8::all() {
+        flags.insert_at(index);
+    }
+    assert!(flags.is_full());
+
+    flags.remove_at(Index8::MIN);
+    flags.toggle_at(Index8::new(1));
+    flags.set_at(LAST, false);
+    assert!(!flags.get_at(Index8::new(0)));
+    assert!(!flags.get_at(Index8::new(1)));
+    assert!(flags.get_at(Index8::new(2)));
+    assert_eq!(flags, BitFlags8::from_range(2..7));
+}
+
+#[test]
+#[should_panic]
+fn bitflags8_index_newtype_oob() {
+    Index8::new(8);
+}
+
+#[test]
+fn bitflags8_set_semantics() {
+    let mut flags = BitFlags8::new();
+
+    assert!(flags.add(3));
+    assert!(!flags.add(3));
+    assert!(flags.add(7));
+    assert_eq!(flags.len(), 2);
+    assert!(flags[3]);
+    assert!(!flags[2]);
+
+    assert!(flags.take(3));
+    assert!(!flags.take(3));
+    assert!(!flags.replace(1, true));
+    assert!(flags.replace(1, false));
+    assert!(!flags.replace(1, false));
+    assert_eq!(flags.try_add(1), Ok(true));
+    assert_eq!(flags.try_take(1), Ok(true));
+    assert_eq!(flags.try_replace(1, true), Ok(false));
+    assert_eq!(flags.try_replace(1, false), Ok(true));
+    assert_eq!(flags.try_add(8), Err(BitFlagsError::IndexOutOfRange { index: 8, bits: 8 }));
+    assert_eq!(flags.try_take(8), Err(BitFlagsError::IndexOutOfRange { index: 8, bits: 8 }));
+    assert!(flags.try_replace(8, true).is_err());
+    assert_eq!(flags, BitFlags8::from_index(7));
+
+    flags.clear();
+    assert!(flags.is_empty());
+    assert_eq!(flags.len(), 0);
+}
+
+#[test]
+fn bitflags8_retain_drain() {
+    let mut flags = BitFlags8(0b1111_0110);
+
+    flags.retain(|i| i % 2 == 0);
+    assert_eq!(flags, BitFlags8(0b0101_0100));
+
+    let drained: Vec<usize> = flags.drain().collect();
+    assert_eq!(drained, vec![2, 4, 6]);
+    assert!(flags.is_empty());
+}
+
+#[test]
+fn bitflags8_bit_mut() {
+    let mut flags = BitFlags8(0b0001);
+
+    let mut bit = flags.bit_mut(2);
+    assert!(!bit.get());
+    bit.set(true);
+    assert!(bit.get());
+    flags.bit_mut(0).toggle();
+    flags.bit_mut(7).set(true);
+
+    assert_eq!(flags, BitFlags8(0b0100) | BitFlags8::from_index(7));
+    assert!(flags.try_bit_mut(7).is_ok());
+    assert!(matches!(
+        flags.try_bit_mut(8),
+        Err(BitFlagsError::IndexOutOfRange { index: 8, bits: 8 })
+    ));
+}
+
+#[test]
+#[should_panic]
+fn bitflags8_index_op_oob() {
+    let flags = BitFlags8::full();
+
+    let _ = flags[8];
+}
+
+#[test]
+fn bitflags8_non_empty() {
+    assert_eq!(
+        core::mem::size_of::<Option<NonEmptyBitFlags8>>(),
+        core::mem::size_of::<BitFlags8>()
+    );
+
+    assert_eq!(NonEmptyBitFlags8::new(BitFlags8::empty()), None);
+    assert_eq!(
+        NonEmptyBitFlags8::try_from(BitFlags8::empty()),
+        Err(BitFlagsError::Empty)
+    );
+    assert!(NonEmptyBitFlags8::try_from_index(8).is_err());
+
+    let f1 = NonEmptyBitFlags8::new(BitFlags8(0b0011)).unwrap();
+    let f2 = NonEmptyBitFlags8::from_index(2);
+    let f3 = NonEmptyBitFlags8::try_from(BitFlags8(0b0110)).unwrap();
+
+    assert_eq!((f1 | f2).get(), BitFlags8(0b0111));
+    assert_eq!(f1.union(f2).to_u8(), 0b0111);
+    assert_eq!(f1.intersection(f2), None);
+    assert_eq!(f1.intersection(f3).map(BitFlags8::from), Some(BitFlags8(0b0010)));
+    assert_eq!(f2.difference(f3), None);
+    assert_eq!(f3.difference(f2).map(BitFlags8::from), Some(BitFlags8(0b0010)));
+    assert!(f3.contains(f2.get()));
+    assert!(!f1.intersects(f2.get()));
+    assert_eq!(f3.count_ones(), 2);
+    assert_eq!(f3.iter().collect::<Vec<_>>(), vec![1, 2]);
+    assert!(NonEmptyBitFlags8::full().get().is_full());
+
+    let mut f4 = f1;
+    f4 |= f2;
+    assert_eq!(f4.to_string(), "NonEmptyBitFlags8(7)");
+}
+
+#[test]
+fn bitflags8_msb0_index() {
+    let mut msb = BitFlags8::new().msb0();
+
+    msb.insert_at_index(0);
+    msb.insert_at_index(7);
+    assert_eq!(msb.lsb0(), BitFlags8::from_slice(&[0, 7]));
+
+    msb.remove_at_index(7);
+    msb.toggle_at_index(1);
+    msb.set_at_index(2, true);
+    assert_eq!(msb, Msb0BitFlags8::from_slice(&[0, 1, 2]));
+    assert_eq!(msb.lsb0(), BitFlags8::from_range(5..));
+    assert_eq!(Msb0BitFlags8::from_index(0).lsb0(), BitFlags8::from_index(7));
+
+    assert!(msb.bit_at_index(1));
+    assert!(!msb.bit_at_index(7));
+    assert_eq!(msb.get_bit_at_index(8), None);
+    assert_eq!(msb.iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+    assert!(msb.try_insert_at_index(8).is_err());
+    assert!(Msb0BitFlags8::try_from_slice(&[0, 8]).is_err());
+}
+
+#[test]
+fn bitflags8_msb0_range() {
+    let mut msb = Msb0BitFlags8::from_range(0..4);
+    assert_eq!(msb.lsb0(), BitFlags8::from_range(4..));
+
+    msb.remove_range(..=1);
+    msb.toggle_range(3..5);
+    assert_eq!(msb, Msb0BitFlags8::from_slice(&[2, 4]));
+
+    msb.insert_range(7..);
+    assert!(msb.contains_range(2..3));
+    assert!(!msb.contains_range(2..5));
+    assert!(msb.intersects_range(1..=2));
+    assert!(!msb.intersects_range(5..7));
+    assert_eq!(msb.count_ones_in(..), 3);
+    assert_eq!(msb.count_ones_in(3..), 2);
+    assert!(msb.try_insert_range(0..=8).is_err());
+}
+
+#[test]
+fn bitflags8_msb0_fmt() {
+    let msb = Msb0BitFlags8::from_slice(&[0, 1, 7]);
+
+    assert_eq!(msb.to_string(), "Msb0BitFlags8{0, 1, 7}");
+    assert_eq!(Msb0BitFlags8::empty().to_string(), "Msb0BitFlags8{}");
+    assert_eq!(format!("{:b}", Msb0BitFlags8::from_index(0)), "0b10000000");
+}
+
+#[test]
+fn bitflags8_bit_twiddling() {
+    let f = BitFlags8(0b0110_1000);
+
+    assert!(f.parity());
+    assert!(!BitFlags8(0b0110).parity());
+    assert!(!BitFlags8::empty().parity());
+    assert_eq!(f.isolate_lowest(), BitFlags8(0b1000));
+    assert_eq!(f.clear_lowest(), BitFlags8(0b0110_0000));
+    assert_eq!(f.mask_up_to_lowest(), BitFlags8(0b1111));
+    assert_eq!(BitFlags8::empty().isolate_lowest(), BitFlags8::empty());
+    assert_eq!(BitFlags8::empty().clear_lowest(), BitFlags8::empty());
+    assert_eq!(BitFlags8::empty().mask_up_to_lowest(), BitFlags8::full());
+
+    assert!(!f.is_single_bit());
+    assert!(!BitFlags8::empty().is_single_bit());
+    assert_eq!(f.single_index(), None);
+    assert_eq!(BitFlags8::from_index(7).single_index(), Some(7));
+
+    assert_eq!(f.smear_right(), BitFlags8(0b0111_1111));
+    assert_eq!(f.smear_left(), !BitFlags8(0b0111));
+    assert_eq!(BitFlags8::empty().smear_left(), BitFlags8::empty());
+    assert_eq!(BitFlags8::empty().smear_right(), BitFlags8::empty());
+
+    assert_eq!(BitFlags8(1).reverse_bits(), BitFlags8::from_index(7));
+    assert_eq!(f.reverse_bits().reverse_bits(), f);
+}
+
+#[test]
+fn bitflags8_blend() {
+    let mask = BitFlags8(0b1100);
+    let a = BitFlags8(0b1010);
+    let b = BitFlags8(0b0101);
+
+    assert_eq!(BitFlags8::blend(mask, a, b), BitFlags8(0b1001));
+    assert_eq!(BitFlags8::blend(BitFlags8::full(), a, b), a);
+    assert_eq!(BitFlags8::blend(BitFlags8::empty(), a, b), b);
+}
+
+#[test]
+fn bitflags8_next_same_popcount() {
+    assert_eq!(BitFlags8::empty().next_same_popcount(), None);
+    assert_eq!(BitFlags8(0b0111).next_same_popcount(), Some(BitFlags8(0b1011)));
+    assert_eq!(BitFlags8(0b1011).next_same_popcount(), Some(BitFlags8(0b1101)));
+    assert_eq!(BitFlags8(0b1110).next_same_popcount(), Some(BitFlags8(0b1_0011)));
+    assert_eq!(BitFlags8::full().next_same_popcount(), None);
+    assert_eq!(BitFlags8::from_index(7).next_same_popcount(), None);
+    assert_eq!(
+        BitFlags8::from_index(6).next_same_popcount(),
+        Some(BitFlags8::from_index(7))
+    );
+
+    // All 3-bit subsets of the lowest 6 bits, in increasing order
+    let mut count = 1;
+    let mut f = BitFlags8(0b0111);
+    while let Some(next) = f.next_same_popcount().filter(|n| n.0 < 64) {
+        assert!(next.0 > f.0);
+        assert_eq!(next.count_ones(), 3);
+        f = next;
+        count += 1;
+    }
+    assert_eq!(count, 20);
+}
+
+fn naive_extract8(val: u8, mask: u8) -> u8 {
+    let mut result = 0;
+    let mut k = 0;
+    for i in 0..8 {
+        if (mask >> i) & 1 == 1 {
+            result |= ((val >> i) & 1) << k;
+            k += 1;
+        }
+    }
+    result
+}
+
+fn naive_deposit8(val: u8, mask: u8) -> u8 {
+    let mut result = 0;
+    let mut k = 0;
+    for i in 0..8 {
+        if (mask >> i) & 1 == 1 {
+            result |= ((val >> k) & 1) << i;
+            k += 1;
+        }
+    }
+    result
+}
+
+fn check_extract_deposit8(val: u8, mask: u8) {
+    let (f, m) = (BitFlags8(val), BitFlags8(mask));
+    let extracted = f.extract(m);
+    let deposited = f.deposit(m);
+
+    assert_eq!(extracted.0, naive_extract8(val, mask), "extract({:#x}, {:#x})", val, mask);
+    assert_eq!(deposited.0, naive_deposit8(val, mask), "deposit({:#x}, {:#x})", val, mask);
+    assert_eq!(extracted.deposit(m), f & m);
+}
+
+#[test]
+fn bitflags8_extract_deposit() {
+    let f = BitFlags8(0b1010_0110);
+
+    assert_eq!(f.extract(BitFlags8(0b1111_0000)), BitFlags8(0b1010));
+    assert_eq!(f.extract(BitFlags8(0b0101_0101)), BitFlags8(0b0010));
+    assert_eq!(f.extract(BitFlags8::full()), f);
+    assert_eq!(f.extract(BitFlags8::empty()), BitFlags8::empty());
+    assert_eq!(BitFlags8(0b1010).deposit(BitFlags8(0b1111_0000)), BitFlags8(0b1010_0000));
+    assert_eq!(BitFlags8(0b1010).deposit(BitFlags8(0b0101_0101)), BitFlags8(0b0100_0100));
+    assert_eq!(f.deposit(BitFlags8::full()), f);
+    assert_eq!(f.deposit(BitFlags8::empty()), BitFlags8::empty());
+}
+
+#[test]
+fn bitflags8_extract_deposit_exhaustive() {
+    for mask in 0..=u8::MAX {
+        for val in 0..=u8::MAX {
+            check_extract_deposit8(val, mask);
+        }
+    }
+}
+
+#[test]
+fn bitflags8_rank_select() {
+    let values = [
+        BitFlags8::empty(),
+        BitFlags8::full(),
+        BitFlags8(0b1011_0100),
+        BitFlags8::from_slice(&[0, 3, 4, 7]),
+        BitFlags8(u8::MAX / 3),
+    ];
+
+    for f in values {
+        let set: Vec<usize> = f.iter().collect();
+        let unset: Vec<usize> = (!f).iter().collect();
+
+        for i in 0..=8 {
+            let expected = set.iter().filter(|&&s| s < i).count() as u32;
+            assert_eq!(f.rank(i), expected);
+            assert_eq!(f.try_rank(i), Ok(expected));
+        }
+        for i in 0..8 {
+            assert_eq!(f.rank_inclusive(i), f.rank(i + 1));
+        }
+        for k in 0..=8 {
+            assert_eq!(f.select(k), set.get(k).copied());
+            assert_eq!(f.select_unset(k), unset.get(k).copied());
+        }
+        assert_eq!(
+            f.try_rank(9),
+            Err(BitFlagsError::InvalidRange { start: 0, end: 9, bits: 8 })
+        );
+        assert!(f.try_rank_inclusive(8).is_err());
+    }
+}
+
+#[test]
+#[should_panic]
+fn bitflags8_rank_oob() {
+    BitFlags8::full().rank(9);
+}
+
+#[test]
+fn bitflags8_submasks() {
+    let flags = BitFlags8(0b0101);
+    let subs: Vec<BitFlags8> = flags.submasks().collect();
+    assert_eq!(
+        subs,
+        vec![BitFlags8(0), BitFlags8(0b0001), BitFlags8(0b0100), BitFlags8(0b0101)]
+    );
+    assert_eq!(flags.nonempty_submasks().count(), 3);
+    assert_eq!(BitFlags8(0).submasks().collect::<Vec<_>>(), vec![BitFlags8(0)]);
+    assert_eq!(BitFlags8(0).nonempty_submasks().count(), 0);
+
+    let high = BitFlags8::from_slice(&[0, 4, 7]);
+    assert_eq!(high.submasks().count(), 8);
+    assert!(high.submasks().all(|s| high.contains(s)));
+    assert_eq!(high.submasks().last(), Some(high));
+
+    let sups: Vec<BitFlags8> = BitFlags8(0b0001).supersets_within(BitFlags8(0b1011)).collect();
+    assert_eq!(
+        sups,
+        vec![
+            BitFlags8(0b0001),
+            BitFlags8(0b0011),
+            BitFlags8(0b1001),
+            BitFlags8(0b1011)
+        ]
+    );
+    assert_eq!(BitFlags8(0b0100).supersets_within(BitFlags8(0b1011)).count(), 0);
+    assert_eq!(
+        BitFlags8::full()
+            .supersets_within(BitFlags8::full())
+            .collect::<Vec<_>>(),
+        vec![BitFlags8::full()]
+    );
+}
+
+#[test]
+fn bitflags8_combinations() {
+    let flags = BitFlags8(0b0111);
+    let combos: Vec<BitFlags8> = flags.combinations(2).collect();
+    assert_eq!(combos, vec![BitFlags8(0b0011), BitFlags8(0b0101), BitFlags8(0b0110)]);
+    assert_eq!(flags.combinations(0).collect::<Vec<_>>(), vec![BitFlags8(0)]);
+    assert_eq!(flags.combinations(3).collect::<Vec<_>>(), vec![flags]);
+    assert_eq!(flags.combinations(4).count(), 0);
+
+    let sparse = BitFlags8::from_slice(&[1, 3, 4, 7]);
+    let combos: Vec<BitFlags8> = sparse.combinations(2).collect();
+    assert_eq!(combos.len(), 6);
+    assert!(combos.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(combos.iter().all(|c| sparse.contains(*c) && c.0.count_ones() == 2));
+
+    let full = BitFlags8::full();
+    assert_eq!(full.combinations(1).count(), 8);
+    assert_eq!(full.combinations(7).last(), Some(BitFlags8(u8::MAX - 1)));
+    assert_eq!(full.combinations(8).collect::<Vec<_>>(), vec![full]);
+}
+
+#[test]
+fn bitflags8_gray_code() {
+    let flags = BitFlags8(0b0101);
+    let gray: Vec<BitFlags8> = flags.gray_code().collect();
+    assert_eq!(
+        gray,
+        vec![BitFlags8(0), BitFlags8(0b0001), BitFlags8(0b0101), BitFlags8(0b0100)]
+    );
+
+    let sparse = BitFlags8::from_slice(&[0, 2, 5, 4, 7]);
+    let gray: Vec<BitFlags8> = sparse.gray_code().collect();
+    assert_eq!(gray.len(), 32);
+    assert!(gray.windows(2).all(|w| (w[0] ^ w[1]).0.count_ones() == 1));
+    let mut sorted = gray.clone();
+    sorted.sort_by_key(|f| f.0);
+    assert_eq!(sorted, sparse.submasks().collect::<Vec<_>>());
+    assert_eq!(BitFlags8(0).gray_code().collect::<Vec<_>>(), vec![BitFlags8(0)]);
+}
+
+#[test]
+fn bitflags8_subset_counts_exhaustive() {
+    for mask in 0..=u8::MAX {
+        let flags = BitFlags8(mask);
+        let subsets = 1usize << mask.count_ones();
+        assert_eq!(flags.submasks().count(), subsets);
+        assert_eq!(flags.gray_code().count(), subsets);
+        assert_eq!((0..=8).map(|k| flags.combinations(k).count()).sum::<usize>(), subsets);
+        assert_eq!(flags.supersets_within(BitFlags8::full()).count(), 1 << mask.count_zeros());
+    }
+}
+
+#[test]
+fn bitflags8_combinatorial_rank() {
+    let ranks: Vec<u8> = BitFlags8(0b0111)
+        .combinations(2)
+        .map(|f| f.combinatorial_rank())
+        .collect();
+    assert_eq!(ranks, vec![0, 1, 2]);
+    assert_eq!(BitFlags8(0).combinatorial_rank(), 0);
+    assert_eq!(BitFlags8::full().combinatorial_rank(), 0);
+    assert_eq!(BitFlags8::from_index(7).combinatorial_rank(), 7);
+
+    let top_half = BitFlags8(u8::MAX << 4);
+    let last = top_half.combinatorial_rank();
+    assert_eq!(BitFlags8::from_combinatorial_rank(4, last), Some(top_half));
+    assert_eq!(BitFlags8::from_combinatorial_rank(4, last + 1), None);
+    assert_eq!(BitFlags8::from_combinatorial_rank(8, 0), Some(BitFlags8::full()));
+    assert_eq!(BitFlags8::from_combinatorial_rank(8, 1), None);
+    assert_eq!(BitFlags8::from_combinatorial_rank(9, 0), None);
+    assert_eq!(BitFlags8::from_combinatorial_rank(0, 0), Some(BitFlags8(0)));
+
+    for flags in [
+        BitFlags8::from_slice(&[1, 3, 4, 7]),
+        BitFlags8(u8::MAX / 3),
+        BitFlags8(!0b1001),
+    ] {
+        let k = flags.0.count_ones();
+        assert_eq!(BitFlags8::from_combinatorial_rank(k, flags.combinatorial_rank()), Some(flags));
+    }
+}
+
+#[test]
+fn bitflags8_combinatorial_rank_exhaustive() {
+    // Every rank in `0..C(8, k)` is hit exactly once by the `k`-bit masks.
+    let mut seen = vec![Vec::new(); 9];
+    for mask in 0..=u8::MAX {
+        let flags = BitFlags8(mask);
+        let k = mask.count_ones();
+        let rank = flags.combinatorial_rank();
+        assert_eq!(BitFlags8::from_combinatorial_rank(k, rank), Some(flags));
+        seen[k as usize].push(rank);
+    }
+    for (k, ranks) in seen.iter_mut().enumerate() {
+        ranks.sort_unstable();
+        assert!(ranks.iter().enumerate().all(|(i, &rank)| rank as usize == i));
+        assert_eq!(BitFlags8::from_combinatorial_rank(k as u32, ranks.len() as u8), None);
+    }
+}
@@ -0,0 +1,166 @@
+//! Exhaustive tests for the subset transforms and subset convolution over small universes.
+
+use arctan_bitflags::{
+    ranked_subset_convolution, subset_convolution, subset_mobius, subset_zeta, superset_mobius,
+    superset_zeta, BitFlags16, BitFlags32, BitFlags8, BitFlagsError, Semiring, SubsetTable,
+};
+
+/// The tropical (min, +) semiring, for checking the transforms against a non-ring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MinPlus(u32);
+
+impl Semiring for MinPlus {
+    const ZERO: Self = MinPlus(u32::MAX);
+    const ONE: Self = MinPlus(0);
+
+    fn add(self, rhs: Self) -> Self {
+        MinPlus(self.0.min(rhs.0))
+    }
+    fn mul(self, rhs: Self) -> Self {
+        MinPlus(self.0.saturating_add(rhs.0))
+    }
+}
+
+/// A deterministic table of `2^n` small signed values.
+fn table(n: usize, seed: i64) -> Vec<i64> {
+    (0..1i64 << n).map(|s| (s * 7 + seed * 13) % 11 - 5).collect()
+}
+
+#[test]
+fn transforms_zeta_mobius() {
+    for n in 0..=6 {
+        let f = table(n, 1);
+
+        let mut zeta = f.clone();
+        subset_zeta(&mut zeta).unwrap();
+        let mut sup = f.clone();
+        superset_zeta(&mut sup).unwrap();
+        let universe = BitFlags16((1 << n) - 1);
+        for s in universe.submasks() {
+            let below: i64 = s.submasks().map(|t| f[t.0 as usize]).sum();
+            let above: i64 = s.supersets_within(universe).map(|t| f[t.0 as usize]).sum();
+            assert_eq!(zeta[s.0 as usize], below);
+            assert_eq!(sup[s.0 as usize], above);
+        }
+
+        subset_mobius(&mut zeta).unwrap();
+        superset_mobius(&mut sup).unwrap();
+        assert_eq!(zeta, f);
+        assert_eq!(sup, f);
+    }
+}
+
+#[test]
+fn transforms_zeta_semirings() {
+    for n in 0..=5 {
+        let universe = BitFlags32((1 << n) - 1);
+        let costs: Vec<MinPlus> = (0..1u32 << n).map(|s| MinPlus(s * 5 % 7 + 1)).collect();
+        let mut cheapest = costs.clone();
+        subset_zeta(&mut cheapest).unwrap();
+
+        let reachable: Vec<bool> = (0..1u32 << n).map(|s| s.count_ones() == 1).collect();
+        let mut any = reachable.clone();
+        superset_zeta(&mut any).unwrap();
+
+        for s in universe.submasks() {
+            let min = s.submasks().map(|t| costs[t.0 as usize].0).min();
+            assert_eq!(Some(cheapest[s.0 as usize].0), min);
+            assert_eq!(any[s.0 as usize], n > 0 && s.0.count_ones() <= 1);
+        }
+    }
+
+    let mut wrapping = vec![u8::MAX; 8];
+    subset_zeta(&mut wrapping).unwrap();
+    subset_mobius(&mut wrapping).unwrap();
+    assert_eq!(wrapping, vec![u8::MAX; 8]);
+}
+
+#[test]
+fn transforms_subset_convolution() {
+    for n in 0..=6 {
+        let f = table(n, 2);
+        let g = table(n, 3);
+        let mut out = vec![0; 1 << n];
+        subset_convolution(&f, &g, &mut out).unwrap();
+        let ranked = ranked_subset_convolution(&f, &g).unwrap();
+
+        for s in BitFlags16((1 << n) - 1).submasks() {
+            let expected: i64 = s.submasks().map(|t| f[t.0 as usize] * g[(s ^ t).0 as usize]).sum();
+            assert_eq!(out[s.0 as usize], expected);
+            assert_eq!(ranked[s.0 as usize], expected);
+        }
+    }
+
+    // Cheapest way to cover each set with two disjoint parts.
+    let costs: Vec<MinPlus> = (0..16u32).map(|s| MinPlus(s.count_ones() * 3 % 5)).collect();
+    let mut out = vec![MinPlus::ZERO; 16];
+    subset_convolution(&costs, &costs, &mut out).unwrap();
+    for s in BitFlags16(0b1111).submasks() {
+        let min = s
+            .submasks()
+            .map(|t| costs[t.0 as usize].0 + costs[(s ^ t).0 as usize].0)
+            .min();
+        assert_eq!(Some(out[s.0 as usize].0), min);
+    }
+}
+
+#[test]
+fn transforms_invalid_lengths() {
+    assert_eq!(subset_zeta(&mut [1, 2, 3]), Err(BitFlagsError::InvalidTableLength { len: 3 }));
+    assert_eq!(
+        superset_mobius::<i32>(&mut []),
+        Err(BitFlagsError::InvalidTableLength { len: 0 })
+    );
+    assert_eq!(
+        subset_convolution(&[1, 2], &[1, 2, 3, 4], &mut [0, 0]),
+        Err(BitFlagsError::TableLengthMismatch { len: 4, expected: 2 })
+    );
+    assert_eq!(
+        ranked_subset_convolution(&[1, 2, 3, 4], &[1, 2]),
+        Err(BitFlagsError::TableLengthMismatch { len: 2, expected: 4 })
+    );
+}
+
+#[test]
+fn transforms_subset_table() {
+    let mut counts = SubsetTable::from_fn(4, |s| s.0.count_ones() as i64);
+    assert_eq!(counts.bits(), 4);
+    assert_eq!(counts.len(), 16);
+    assert_eq!(counts.universe(), BitFlags32(0b1111));
+    assert_eq!(counts[BitFlags8(0b0110)], 2);
+    assert_eq!(counts[BitFlags16(0b0111)], 3);
+    assert_eq!(counts.get(BitFlags32(0b1_0000)), None);
+
+    counts[BitFlags16(0)] = 10;
+    counts.zeta();
+    assert_eq!(counts[BitFlags32(0b0011)], 14);
+    counts.mobius();
+    assert_eq!(counts[BitFlags32(0b0011)], 2);
+    assert_eq!(counts.get(BitFlags32(0)), Some(&10));
+
+    let f = SubsetTable::from_vec(table(3, 2)).unwrap();
+    let g = SubsetTable::from_vec(table(3, 3)).unwrap();
+    let out = f.convolve(&g).unwrap();
+    assert_eq!(f.ranked_convolve(&g).unwrap(), out);
+    for (s, &value) in out.iter() {
+        let expected: i64 = s.submasks().map(|t| f[t] * g[s ^ t]).sum();
+        assert_eq!(value, expected);
+    }
+
+    assert_eq!(
+        SubsetTable::from_vec(vec![0; 6]),
+        Err(BitFlagsError::InvalidTableLength { len: 6 })
+    );
+    let small = SubsetTable::from_vec(vec![1; 2]).unwrap();
+    assert_eq!(
+        f.convolve(&small),
+        Err(BitFlagsError::TableLengthMismatch { len: 2, expected: 8 })
+    );
+}
+
+#[test]
+#[should_panic]
+fn transforms_subset_table_out_of_range() {
+    let table = SubsetTable::from_fn(3, |s| s.0);
+    let _ = table[BitFlags8(0b1000)];
+}
@@ -0,0 +1,130 @@
+//! Tests for `TypedFlags` domain-tagged bitflags.
+
+use arctan_bitflags::{BitFlags16, BitFlags32, BitFlags8, TypedFlags};
+use nanoserde::{DeBin, DeJson, DeRon, SerBin, SerJson, SerRon};
+
+enum Collision {}
+enum Render {}
+
+type CollisionLayers = TypedFlags<Collision, BitFlags32>;
+type RenderLayers = TypedFlags<Render, BitFlags32>;
+
+const WORLD: CollisionLayers = TypedFlags::from_flags(BitFlags32(0b0001));
+const PLAYER: CollisionLayers = TypedFlags::from_flags(BitFlags32(0b0010));
+
+fn collides(a: CollisionLayers, b: CollisionLayers) -> bool {
+    a.intersects(b)
+}
+
+#[test]
+fn typed_set_operations() {
+    let mut layers = WORLD | PLAYER;
+    assert!(collides(layers, PLAYER));
+    assert!(layers.contains(WORLD));
+    assert_eq!(layers & PLAYER, PLAYER);
+    assert_eq!(layers ^ PLAYER, WORLD);
+    assert_eq!(layers.difference(WORLD), PLAYER);
+    assert_eq!(!layers, CollisionLayers::from_u32(!0b0011));
+
+    layers.remove(WORLD);
+    assert_eq!(layers, PLAYER);
+    layers.toggle(WORLD);
+    layers.set(PLAYER, false);
+    assert_eq!(layers, WORLD);
+    layers |= PLAYER;
+    layers &= !WORLD;
+    assert_eq!(layers.into_flags(), BitFlags32(0b0010));
+}
+
+#[test]
+fn typed_forwarded_api() {
+    let mut layers = CollisionLayers::from_slice(&[1, 4, 6]);
+    assert_eq!(layers.count_ones(), 3);
+    assert!(layers.bit_at_index(4));
+    assert_eq!(layers.iter().collect::<Vec<_>>(), vec![1, 4, 6]);
+    assert_eq!(layers.highest_set_bit(), CollisionLayers::from_index(6));
+    assert_eq!(layers.isolate_lowest(), PLAYER);
+    assert_eq!(layers.extract(CollisionLayers::from_u32(0b0101_0000)).get(), BitFlags32(0b11));
+
+    layers.insert_at_index(0);
+    assert!(layers.take(6));
+    assert_eq!(layers, CollisionLayers::from_slice(&[0, 1, 4]));
+    assert!(CollisionLayers::try_from_index(32).is_err());
+    assert_eq!(CollisionLayers::from_range(0..2), WORLD | PLAYER);
+    assert_eq!(CollisionLayers::num_bits(), 32);
+
+    let subsets: Vec<CollisionLayers> = (WORLD | PLAYER).submasks().collect();
+    assert_eq!(subsets, vec![CollisionLayers::empty(), WORLD, PLAYER, WORLD | PLAYER]);
+    assert_eq!(CollisionLayers::from_u32(0b0111).combinations(2).count(), 3);
+
+    let mut layers = CollisionLayers::empty();
+    layers.set_field(4..=7, 0b1010);
+    assert_eq!(layers.get_field(4..=7), 0b1010);
+    assert_eq!(layers.to_u32(), 0b1010_0000);
+    assert_eq!((layers.rank(6), layers.select(1)), (1, Some(7)));
+    assert_eq!(layers.display_with(&[("ghost", 5)]).to_string(), "ghost | 0x80");
+    assert!(layers.try_insert_range(30..33).is_err());
+    assert_eq!(layers.try_add(0), Ok(true));
+    assert_eq!(layers.try_take(0), Ok(true));
+    assert_eq!(layers.try_replace(0, true), Ok(false));
+    assert!(layers.try_add(32).is_err());
+    assert!(layers.try_bit_mut(32).is_err());
+    layers.try_bit_mut(1).unwrap().set(true);
+    assert_eq!(layers.to_u32(), 0b1010_0011);
+
+    assert!(layers[1]);
+    assert!(!layers[2]);
+    assert_eq!(
+        layers.split::<BitFlags8>(),
+        [BitFlags8(0b1010_0011), BitFlags8(0), BitFlags8(0), BitFlags8(0)]
+    );
+    assert_eq!(layers.lane::<BitFlags16>(0), BitFlags16(0b1010_0011));
+    layers.set_lane(3, BitFlags8(0x80));
+    assert!(layers.try_set_lane(4, BitFlags8(1)).is_err());
+    assert_eq!(
+        CollisionLayers::join::<BitFlags16>([BitFlags16(0b1010_0011), BitFlags16(0x8000)]),
+        layers
+    );
+    assert!(layers.msb0().bit_at_index(0));
+}
+
+#[test]
+#[should_panic]
+fn typed_index_op_oob() {
+    let _ = CollisionLayers::full()[32];
+}
+
+#[test]
+fn typed_conversions() {
+    let layers = RenderLayers::from(0b0101_u32);
+    assert_eq!(u32::from(layers), 0b0101);
+    assert_eq!(BitFlags32::from(layers), BitFlags32(0b0101));
+    assert_eq!(RenderLayers::from(BitFlags32(0b0101)), layers);
+
+    let retagged: CollisionLayers = layers.retag();
+    assert_eq!(retagged, WORLD | CollisionLayers::from_index(2));
+    assert_eq!(layers.to_string(), BitFlags32(0b0101).to_string());
+    assert_eq!("0b101".parse::<RenderLayers>(), Ok(layers));
+    assert_eq!(format!("{:?}", layers), "TypedFlags(BitFlags32(5))");
+    assert_eq!(RenderLayers::default(), RenderLayers::empty());
+    assert!(TypedFlags::<Render, BitFlags8>::full().is_full());
+}
+
+#[test]
+fn typed_serde() {
+    let layers = RenderLayers::from_u32(0b0110);
+    let json = serde_json::to_string(&layers).unwrap();
+    assert_eq!(json, "6");
+    assert_eq!(serde_json::from_str::<RenderLayers>(&json).unwrap(), layers);
+    assert_eq!(ron::from_str::<RenderLayers>("6").unwrap(), layers);
+}
+
+#[test]
+fn typed_nanoserde() {
+    let layers = RenderLayers::from_u32(0b0110);
+    let bin = SerBin::serialize_bin(&layers);
+    assert_eq!(bin, vec![6, 0, 0, 0]);
+    assert_eq!(RenderLayers::deserialize_bin(&bin).unwrap(), layers);
+    assert_eq!(RenderLayers::deserialize_json(&layers.serialize_json()).unwrap(), layers);
+    assert_eq!(RenderLayers::deserialize_ron(&layers.serialize_ron()).unwrap(), layers);
+}